@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS update_attachments_updated_at ON attachments;
+
+DROP INDEX IF EXISTS idx_attachment_expense;
+DROP INDEX IF EXISTS idx_attachment_user;
+
+DROP TABLE IF EXISTS attachments;
@@ -0,0 +1,21 @@
+-- ATTACHMENT TABLE
+CREATE TABLE IF NOT EXISTS attachments (
+    attachment_id  UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    expense_id     UUID NOT NULL,
+    user_id        UUID NOT NULL,
+    file_name      VARCHAR(255) NOT NULL,
+    content_type   VARCHAR(100) NOT NULL,
+    size_bytes     BIGINT NOT NULL,
+    storage_key    VARCHAR(512) NOT NULL,
+    thumbnail_key  VARCHAR(512),
+    created_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_attachment_expense FOREIGN KEY (expense_id) REFERENCES expenses(expense_id) ON DELETE CASCADE,
+    CONSTRAINT fk_attachment_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachment_expense ON attachments(expense_id);
+CREATE INDEX IF NOT EXISTS idx_attachment_user ON attachments(user_id);
+
+CREATE TRIGGER update_attachments_updated_at BEFORE UPDATE ON attachments
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS update_tags_updated_at ON tags;
+
+DROP INDEX IF EXISTS idx_tag_user;
+DROP INDEX IF EXISTS idx_expense_tag_tag;
+
+DROP TABLE IF EXISTS expense_tags;
+DROP TABLE IF EXISTS tags;
@@ -0,0 +1,25 @@
+-- TAG TABLE
+CREATE TABLE IF NOT EXISTS tags (
+    tag_id      SERIAL PRIMARY KEY,
+    user_id     UUID NOT NULL,
+    name        VARCHAR(50) NOT NULL,
+    created_at  TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_tag_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT unique_user_tag UNIQUE (user_id, name)
+);
+
+-- EXPENSE <-> TAG JOIN TABLE
+CREATE TABLE IF NOT EXISTS expense_tags (
+    expense_id  UUID NOT NULL,
+    tag_id      INT NOT NULL,
+    PRIMARY KEY (expense_id, tag_id),
+    CONSTRAINT fk_expense_tag_expense FOREIGN KEY (expense_id) REFERENCES expenses(expense_id) ON DELETE CASCADE,
+    CONSTRAINT fk_expense_tag_tag FOREIGN KEY (tag_id) REFERENCES tags(tag_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tag_user ON tags(user_id);
+CREATE INDEX IF NOT EXISTS idx_expense_tag_tag ON expense_tags(tag_id);
+
+CREATE TRIGGER update_tags_updated_at BEFORE UPDATE ON tags
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,14 @@
+DELETE FROM categories
+WHERE user_id IS NULL AND category_name IN ('Groceries', 'Restaurants');
+
+DROP TRIGGER IF EXISTS update_user_category_overrides_updated_at ON user_category_overrides;
+DROP TABLE IF EXISTS user_category_overrides;
+
+DROP INDEX IF EXISTS idx_budget_category;
+ALTER TABLE budgets DROP CONSTRAINT IF EXISTS fk_budget_category;
+ALTER TABLE budgets DROP COLUMN IF EXISTS category_id;
+
+DROP INDEX IF EXISTS idx_category_parent;
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS check_category_not_own_parent;
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS fk_category_parent;
+ALTER TABLE categories DROP COLUMN IF EXISTS parent_id;
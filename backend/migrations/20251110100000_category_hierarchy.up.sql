@@ -0,0 +1,36 @@
+-- SUBCATEGORIES
+ALTER TABLE categories ADD COLUMN IF NOT EXISTS parent_id INT;
+ALTER TABLE categories ADD CONSTRAINT fk_category_parent
+    FOREIGN KEY (parent_id) REFERENCES categories(category_id) ON DELETE SET NULL;
+ALTER TABLE categories ADD CONSTRAINT check_category_not_own_parent
+    CHECK (parent_id IS NULL OR parent_id <> category_id);
+
+CREATE INDEX IF NOT EXISTS idx_category_parent ON categories(parent_id);
+
+-- CATEGORY BUDGETS (include spending in the category and all of its subcategories)
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS category_id INT;
+ALTER TABLE budgets ADD CONSTRAINT fk_budget_category
+    FOREIGN KEY (category_id) REFERENCES categories(category_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_budget_category ON budgets(category_id);
+
+-- PER-USER OVERRIDES OF GLOBAL CATEGORIES
+CREATE TABLE IF NOT EXISTS user_category_overrides (
+    user_id      UUID NOT NULL,
+    category_id  INT NOT NULL,
+    is_hidden    BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at   TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at   TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, category_id),
+    CONSTRAINT fk_override_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_override_category FOREIGN KEY (category_id) REFERENCES categories(category_id) ON DELETE CASCADE
+);
+
+CREATE TRIGGER update_user_category_overrides_updated_at BEFORE UPDATE ON user_category_overrides
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- GLOBAL SUBCATEGORIES
+INSERT INTO categories (user_id, category_name, parent_id)
+SELECT NULL, sub.name, parent.category_id
+FROM (VALUES ('Groceries'), ('Restaurants')) AS sub(name)
+JOIN categories parent ON parent.user_id IS NULL AND parent.category_name = 'Food';
@@ -0,0 +1,3 @@
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS check_category_color;
+ALTER TABLE categories DROP COLUMN IF EXISTS color;
+ALTER TABLE categories DROP COLUMN IF EXISTS icon;
@@ -0,0 +1,5 @@
+-- CATEGORY DISPLAY METADATA FOR THE MOBILE UI
+ALTER TABLE categories ADD COLUMN IF NOT EXISTS icon VARCHAR(50);
+ALTER TABLE categories ADD COLUMN IF NOT EXISTS color VARCHAR(7);
+ALTER TABLE categories ADD CONSTRAINT check_category_color
+    CHECK (color IS NULL OR color ~ '^#[0-9A-Fa-f]{6}$');
@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS update_expense_rules_updated_at ON expense_rules;
+
+DROP INDEX IF EXISTS idx_expense_user_name;
+DROP INDEX IF EXISTS idx_rule_user;
+
+DROP TABLE IF EXISTS expense_rules;
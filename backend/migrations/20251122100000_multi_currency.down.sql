@@ -0,0 +1,15 @@
+DROP FUNCTION IF EXISTS convert_amount(BIGINT, VARCHAR, VARCHAR, DATE, UUID);
+DROP FUNCTION IF EXISTS exchange_rate(VARCHAR, VARCHAR, DATE, UUID);
+DROP FUNCTION IF EXISTS direct_exchange_rate(VARCHAR, VARCHAR, DATE, UUID);
+
+DROP TRIGGER IF EXISTS update_exchange_rates_updated_at ON exchange_rates;
+
+DROP INDEX IF EXISTS idx_exchange_rate_pair;
+DROP INDEX IF EXISTS unique_user_rate;
+DROP INDEX IF EXISTS unique_reference_rate;
+
+DROP TABLE IF EXISTS exchange_rates;
+
+ALTER TABLE budgets DROP COLUMN IF EXISTS currency;
+ALTER TABLE expenses DROP COLUMN IF EXISTS currency;
+ALTER TABLE users DROP COLUMN IF EXISTS base_currency;
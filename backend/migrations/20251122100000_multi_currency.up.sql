@@ -0,0 +1,74 @@
+-- CURRENCIES (ISO 4217 codes)
+ALTER TABLE users ADD COLUMN IF NOT EXISTS base_currency VARCHAR(3) NOT NULL DEFAULT 'INR';
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'INR';
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'INR';
+
+-- EXCHANGE RATE TABLE
+-- 1 unit of base_currency = rate units of quote_currency on rate_date.
+-- Reference rates (e.g. loaded from ECB files) have no user_id; manual rates
+-- belong to the user who entered them and take precedence for that user.
+CREATE TABLE IF NOT EXISTS exchange_rates (
+    rate_id         UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    user_id         UUID,
+    base_currency   VARCHAR(3) NOT NULL,
+    quote_currency  VARCHAR(3) NOT NULL,
+    rate            NUMERIC(24, 10) NOT NULL,
+    rate_date       DATE NOT NULL,
+    source          VARCHAR(20) NOT NULL DEFAULT 'manual',
+    created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_exchange_rate_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT check_exchange_rate_positive CHECK (rate > 0)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS unique_reference_rate
+    ON exchange_rates(base_currency, quote_currency, rate_date) WHERE user_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS unique_user_rate
+    ON exchange_rates(user_id, base_currency, quote_currency, rate_date) WHERE user_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_exchange_rate_pair
+    ON exchange_rates(base_currency, quote_currency, rate_date);
+
+CREATE TRIGGER update_exchange_rates_updated_at BEFORE UPDATE ON exchange_rates
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Rate for a currency pair from a stored rate or its inverse: the closest rate on
+-- or before on_date, falling back to the closest later one. The user's manual
+-- rates win over reference rates for the same date.
+CREATE OR REPLACE FUNCTION direct_exchange_rate(
+    from_currency VARCHAR, to_currency VARCHAR, on_date DATE, for_user UUID
+)
+RETURNS NUMERIC AS $$
+    SELECT CASE WHEN from_currency = to_currency THEN 1 ELSE (
+        SELECT r.rate FROM (
+            SELECT rate, rate_date, user_id FROM exchange_rates
+            WHERE base_currency = from_currency AND quote_currency = to_currency
+                AND (user_id IS NULL OR user_id = for_user)
+            UNION ALL
+            SELECT 1 / rate, rate_date, user_id FROM exchange_rates
+            WHERE base_currency = to_currency AND quote_currency = from_currency
+                AND (user_id IS NULL OR user_id = for_user)
+        ) r
+        ORDER BY r.rate_date > on_date, ABS(r.rate_date - on_date), r.user_id IS NULL
+        LIMIT 1
+    ) END
+$$ LANGUAGE sql STABLE;
+
+-- Direct rate when there is one, otherwise a cross rate through EUR (the base of
+-- the ECB reference rates). NULL when no rate is known.
+CREATE OR REPLACE FUNCTION exchange_rate(
+    from_currency VARCHAR, to_currency VARCHAR, on_date DATE, for_user UUID
+)
+RETURNS NUMERIC AS $$
+    SELECT COALESCE(
+        direct_exchange_rate(from_currency, to_currency, on_date, for_user),
+        direct_exchange_rate('EUR', to_currency, on_date, for_user)
+            / direct_exchange_rate('EUR', from_currency, on_date, for_user)
+    )
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION convert_amount(
+    amount BIGINT, from_currency VARCHAR, to_currency VARCHAR, on_date DATE, for_user UUID
+)
+RETURNS BIGINT AS $$
+    SELECT ROUND(amount * exchange_rate(from_currency, to_currency, on_date, for_user))::BIGINT
+$$ LANGUAGE sql STABLE;
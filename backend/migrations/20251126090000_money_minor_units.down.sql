@@ -0,0 +1,16 @@
+CREATE OR REPLACE FUNCTION convert_amount(
+    amount BIGINT, from_currency VARCHAR, to_currency VARCHAR, on_date DATE, for_user UUID
+)
+RETURNS BIGINT AS $$
+    SELECT ROUND(amount * exchange_rate(from_currency, to_currency, on_date, for_user))::BIGINT
+$$ LANGUAGE sql STABLE;
+
+UPDATE budgets
+SET amount = ROUND(amount * 10 ^ (2 - currency_exponent(currency)))::BIGINT
+WHERE currency_exponent(currency) <> 2;
+
+UPDATE expenses
+SET amount = ROUND(amount * 10 ^ (2 - currency_exponent(currency)))::BIGINT
+WHERE currency_exponent(currency) <> 2;
+
+DROP FUNCTION IF EXISTS currency_exponent(VARCHAR);
@@ -0,0 +1,36 @@
+-- MONEY IN MINOR UNITS
+-- Amounts are integer minor units of their row's currency (paise, cents, yen).
+-- Rows written before this migration were sent by clients that always scaled by
+-- 100, which is already right for two-decimal currencies like INR; rows in
+-- zero- or three-decimal currencies are rescaled below.
+
+-- Minor unit exponent per ISO 4217 code; keep in sync with utils::currency::CURRENCIES.
+CREATE OR REPLACE FUNCTION currency_exponent(code VARCHAR)
+RETURNS INTEGER AS $$
+    SELECT CASE
+        WHEN code IN ('CLP', 'ISK', 'JPY', 'KRW', 'VND') THEN 0
+        WHEN code IN ('BHD', 'JOD', 'KWD', 'OMR') THEN 3
+        ELSE 2
+    END
+$$ LANGUAGE sql IMMUTABLE;
+
+UPDATE expenses
+SET amount = GREATEST(ROUND(amount * 10 ^ (currency_exponent(currency) - 2)), 1)::BIGINT
+WHERE currency_exponent(currency) <> 2;
+
+UPDATE budgets
+SET amount = GREATEST(ROUND(amount * 10 ^ (currency_exponent(currency) - 2)), 1)::BIGINT
+WHERE currency_exponent(currency) <> 2;
+
+-- Conversion now also rescales between currencies with different exponents,
+-- e.g. 1000 JPY (1000 minor units) to USD cents.
+CREATE OR REPLACE FUNCTION convert_amount(
+    amount BIGINT, from_currency VARCHAR, to_currency VARCHAR, on_date DATE, for_user UUID
+)
+RETURNS BIGINT AS $$
+    SELECT ROUND(
+        amount
+        * exchange_rate(from_currency, to_currency, on_date, for_user)
+        * 10::NUMERIC ^ (currency_exponent(to_currency) - currency_exponent(from_currency))
+    )::BIGINT
+$$ LANGUAGE sql STABLE;
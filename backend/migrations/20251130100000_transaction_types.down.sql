@@ -0,0 +1,14 @@
+DROP FUNCTION IF EXISTS spent_amount(VARCHAR, BIGINT);
+
+DROP INDEX IF EXISTS idx_expense_user_type_date;
+DROP INDEX IF EXISTS idx_expense_original;
+
+-- Income and refunds have no meaning without the type column
+DELETE FROM expenses WHERE transaction_type <> 'expense';
+
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS fk_expense_original;
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS check_refund_original;
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS check_transaction_type;
+
+ALTER TABLE expenses DROP COLUMN IF EXISTS original_expense_id;
+ALTER TABLE expenses DROP COLUMN IF EXISTS transaction_type;
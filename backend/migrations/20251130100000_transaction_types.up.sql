@@ -0,0 +1,28 @@
+-- TRANSACTION TYPES
+-- The expenses table now records every transaction: money spent ('expense'),
+-- money received ('income') and money returned for an earlier expense
+-- ('refund'). Amounts stay positive; the type gives the direction.
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS transaction_type VARCHAR(10) NOT NULL DEFAULT 'expense';
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS original_expense_id UUID;
+
+ALTER TABLE expenses ADD CONSTRAINT check_transaction_type
+    CHECK (transaction_type IN ('expense', 'income', 'refund'));
+ALTER TABLE expenses ADD CONSTRAINT check_refund_original
+    CHECK ((transaction_type = 'refund') = (original_expense_id IS NOT NULL));
+-- Refunds go away together with the expense they refund
+ALTER TABLE expenses ADD CONSTRAINT fk_expense_original
+    FOREIGN KEY (original_expense_id) REFERENCES expenses(expense_id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_expense_original ON expenses(original_expense_id)
+    WHERE original_expense_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_expense_user_type_date ON expenses(user_id, transaction_type, date);
+
+-- Amount a transaction adds to spending: refunds count against it and income
+-- is not spending at all (NULL, so SUM skips it).
+CREATE OR REPLACE FUNCTION spent_amount(transaction_type VARCHAR, amount BIGINT)
+RETURNS BIGINT AS $$
+    SELECT CASE transaction_type
+        WHEN 'expense' THEN amount
+        WHEN 'refund' THEN -amount
+    END
+$$ LANGUAGE sql IMMUTABLE;
@@ -0,0 +1,18 @@
+DROP FUNCTION IF EXISTS account_balance(UUID, DATE);
+DROP FUNCTION IF EXISTS account_entries(UUID);
+
+DROP INDEX IF EXISTS idx_reconciliation_account;
+DROP TABLE IF EXISTS account_reconciliations;
+
+DROP TRIGGER IF EXISTS update_transfers_updated_at ON transfers;
+DROP INDEX IF EXISTS idx_transfer_to;
+DROP INDEX IF EXISTS idx_transfer_from;
+DROP TABLE IF EXISTS transfers;
+
+DROP INDEX IF EXISTS idx_expense_account;
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS fk_expense_account;
+ALTER TABLE expenses DROP COLUMN IF EXISTS account_id;
+
+DROP TRIGGER IF EXISTS update_accounts_updated_at ON accounts;
+DROP INDEX IF EXISTS idx_account_user;
+DROP TABLE IF EXISTS accounts;
@@ -0,0 +1,113 @@
+-- ACCOUNT TABLE
+-- Where money is kept or paid from: cash, bank account, card or wallet.
+-- opening_balance is in minor units of the account currency on opening_date;
+-- credit cards usually open at 0 and go negative as they are used.
+CREATE TABLE IF NOT EXISTS accounts (
+    account_id      UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    user_id         UUID NOT NULL,
+    name            VARCHAR(100) NOT NULL,
+    account_type    VARCHAR(20) NOT NULL,
+    currency        VARCHAR(3) NOT NULL,
+    opening_balance BIGINT NOT NULL DEFAULT 0,
+    opening_date    DATE NOT NULL DEFAULT CURRENT_DATE,
+    created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_account_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT unique_account_name UNIQUE (user_id, name),
+    CONSTRAINT check_account_type
+        CHECK (account_type IN ('cash', 'bank', 'debit_card', 'credit_card', 'wallet'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_user ON accounts(user_id);
+
+CREATE TRIGGER update_accounts_updated_at BEFORE UPDATE ON accounts
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS account_id UUID;
+ALTER TABLE expenses ADD CONSTRAINT fk_expense_account
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id) ON DELETE RESTRICT;
+CREATE INDEX IF NOT EXISTS idx_expense_account ON expenses(account_id)
+    WHERE account_id IS NOT NULL;
+
+-- TRANSFER TABLE
+-- Money moved between two of the user's accounts. Transfers are neither income
+-- nor spending. amount is in the source account currency, to_amount in the
+-- destination account currency (equal when both use the same currency).
+CREATE TABLE IF NOT EXISTS transfers (
+    transfer_id     UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    user_id         UUID NOT NULL,
+    from_account_id UUID NOT NULL,
+    to_account_id   UUID NOT NULL,
+    amount          BIGINT NOT NULL,
+    to_amount       BIGINT NOT NULL,
+    date            DATE NOT NULL,
+    description     TEXT,
+    created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_transfer_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_transfer_from FOREIGN KEY (from_account_id) REFERENCES accounts(account_id) ON DELETE RESTRICT,
+    CONSTRAINT fk_transfer_to FOREIGN KEY (to_account_id) REFERENCES accounts(account_id) ON DELETE RESTRICT,
+    CONSTRAINT check_transfer_accounts CHECK (from_account_id <> to_account_id),
+    CONSTRAINT check_transfer_amount CHECK (amount > 0 AND to_amount > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_transfer_from ON transfers(from_account_id, date);
+CREATE INDEX IF NOT EXISTS idx_transfer_to ON transfers(to_account_id, date);
+
+CREATE TRIGGER update_transfers_updated_at BEFORE UPDATE ON transfers
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- RECONCILIATION TABLE
+-- A statement balance checked against the balance computed from transactions.
+CREATE TABLE IF NOT EXISTS account_reconciliations (
+    reconciliation_id UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    account_id        UUID NOT NULL,
+    statement_date    DATE NOT NULL,
+    statement_balance BIGINT NOT NULL,
+    computed_balance  BIGINT NOT NULL,
+    created_at        TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_reconciliation_account FOREIGN KEY (account_id) REFERENCES accounts(account_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_reconciliation_account
+    ON account_reconciliations(account_id, statement_date);
+
+-- Transactions of an account as signed amounts in the account currency: money
+-- in is positive, money out negative. Expenses in another currency are
+-- converted at their date (NULL when no rate is known).
+CREATE OR REPLACE FUNCTION account_entries(for_account UUID)
+RETURNS TABLE (
+    entry_id UUID, entry_type VARCHAR, date DATE, description TEXT, amount BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE
+) AS $$
+    SELECT e.expense_id, e.transaction_type::VARCHAR, e.date, e.name::TEXT,
+        convert_amount(
+            CASE e.transaction_type WHEN 'expense' THEN -e.amount ELSE e.amount END,
+            e.currency, a.currency, e.date, a.user_id
+        ),
+        e.created_at
+    FROM expenses e
+    JOIN accounts a ON a.account_id = e.account_id
+    WHERE e.account_id = for_account
+    UNION ALL
+    SELECT t.transfer_id, 'transfer_out'::VARCHAR, t.date,
+        COALESCE(t.description, 'Transfer'), -t.amount, t.created_at
+    FROM transfers t
+    WHERE t.from_account_id = for_account
+    UNION ALL
+    SELECT t.transfer_id, 'transfer_in'::VARCHAR, t.date,
+        COALESCE(t.description, 'Transfer'), t.to_amount, t.created_at
+    FROM transfers t
+    WHERE t.to_account_id = for_account
+$$ LANGUAGE sql STABLE;
+
+-- Balance of an account at the end of as_of (NULL for the current balance).
+CREATE OR REPLACE FUNCTION account_balance(for_account UUID, as_of DATE)
+RETURNS BIGINT AS $$
+    SELECT (a.opening_balance + COALESCE((
+        SELECT SUM(en.amount) FROM account_entries(a.account_id) en
+        WHERE as_of IS NULL OR en.date <= as_of
+    ), 0))::BIGINT
+    FROM accounts a
+    WHERE a.account_id = for_account
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,35 @@
+DROP FUNCTION IF EXISTS in_workspace(UUID, UUID, UUID, UUID);
+DROP FUNCTION IF EXISTS can_edit_resource(UUID, UUID, UUID);
+DROP FUNCTION IF EXISTS can_view_resource(UUID, UUID, UUID);
+
+-- Shared rows can't be mapped back to a single owner's workspace
+DELETE FROM expenses WHERE household_id IS NOT NULL;
+DELETE FROM budgets WHERE household_id IS NOT NULL;
+DELETE FROM categories WHERE household_id IS NOT NULL;
+
+DROP INDEX IF EXISTS unique_household_category;
+DROP INDEX IF EXISTS unique_user_category;
+ALTER TABLE categories ADD CONSTRAINT unique_user_category UNIQUE (user_id, category_name);
+
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS fk_category_household;
+ALTER TABLE categories DROP COLUMN IF EXISTS household_id;
+
+DROP INDEX IF EXISTS idx_budget_household;
+ALTER TABLE budgets DROP CONSTRAINT IF EXISTS fk_budget_household;
+ALTER TABLE budgets DROP COLUMN IF EXISTS household_id;
+
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS fk_expense_paid_by;
+ALTER TABLE expenses DROP COLUMN IF EXISTS paid_by;
+
+DROP INDEX IF EXISTS idx_expense_household;
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS fk_expense_household;
+ALTER TABLE expenses DROP COLUMN IF EXISTS household_id;
+
+DROP INDEX IF EXISTS idx_invitation_email;
+DROP TABLE IF EXISTS household_invitations;
+
+DROP INDEX IF EXISTS idx_member_user;
+DROP TABLE IF EXISTS household_members;
+
+DROP TRIGGER IF EXISTS update_households_updated_at ON households;
+DROP TABLE IF EXISTS households;
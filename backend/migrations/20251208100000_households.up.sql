@@ -0,0 +1,116 @@
+-- HOUSEHOLD TABLES
+-- A household is a workspace shared by several users (a couple, flatmates).
+-- Expenses, budgets and categories either belong to one user (household_id
+-- IS NULL) or to a household, where every member can see them. user_id stays
+-- the member who created the row.
+CREATE TABLE IF NOT EXISTS households (
+    household_id  UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    name          VARCHAR(100) NOT NULL,
+    created_by    UUID,
+    created_at    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_household_creator FOREIGN KEY (created_by) REFERENCES users(user_id) ON DELETE SET NULL
+);
+
+CREATE TRIGGER update_households_updated_at BEFORE UPDATE ON households
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Owners manage the household and its members, editors add and change
+-- shared data, viewers only read it.
+CREATE TABLE IF NOT EXISTS household_members (
+    household_id  UUID NOT NULL,
+    user_id       UUID NOT NULL,
+    role          VARCHAR(10) NOT NULL,
+    joined_at     TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (household_id, user_id),
+    CONSTRAINT fk_member_household FOREIGN KEY (household_id) REFERENCES households(household_id) ON DELETE CASCADE,
+    CONSTRAINT fk_member_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT check_member_role CHECK (role IN ('owner', 'editor', 'viewer'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_member_user ON household_members(user_id);
+
+-- Invitations are addressed to an email, so people can be invited before
+-- they sign up. Accepting one turns it into a membership.
+CREATE TABLE IF NOT EXISTS household_invitations (
+    invitation_id UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    household_id  UUID NOT NULL,
+    email         VARCHAR(255) NOT NULL,
+    role          VARCHAR(10) NOT NULL,
+    invited_by    UUID,
+    created_at    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_invitation_household FOREIGN KEY (household_id) REFERENCES households(household_id) ON DELETE CASCADE,
+    CONSTRAINT fk_invitation_inviter FOREIGN KEY (invited_by) REFERENCES users(user_id) ON DELETE SET NULL,
+    CONSTRAINT unique_invitation_email UNIQUE (household_id, email),
+    CONSTRAINT check_invitation_role CHECK (role IN ('owner', 'editor', 'viewer'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_invitation_email ON household_invitations(LOWER(email));
+
+-- SHARED RESOURCES
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS household_id UUID;
+ALTER TABLE expenses ADD CONSTRAINT fk_expense_household
+    FOREIGN KEY (household_id) REFERENCES households(household_id) ON DELETE CASCADE;
+CREATE INDEX IF NOT EXISTS idx_expense_household ON expenses(household_id, date)
+    WHERE household_id IS NOT NULL;
+
+-- The member who paid; defaults to whoever recorded the expense.
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS paid_by UUID;
+ALTER TABLE expenses ADD CONSTRAINT fk_expense_paid_by
+    FOREIGN KEY (paid_by) REFERENCES users(user_id) ON DELETE SET NULL;
+UPDATE expenses SET paid_by = user_id;
+
+ALTER TABLE budgets ADD COLUMN IF NOT EXISTS household_id UUID;
+ALTER TABLE budgets ADD CONSTRAINT fk_budget_household
+    FOREIGN KEY (household_id) REFERENCES households(household_id) ON DELETE CASCADE;
+CREATE INDEX IF NOT EXISTS idx_budget_household ON budgets(household_id)
+    WHERE household_id IS NOT NULL;
+
+ALTER TABLE categories ADD COLUMN IF NOT EXISTS household_id UUID;
+ALTER TABLE categories ADD CONSTRAINT fk_category_household
+    FOREIGN KEY (household_id) REFERENCES households(household_id) ON DELETE CASCADE;
+
+-- Category names are unique per workspace instead of per user
+ALTER TABLE categories DROP CONSTRAINT IF EXISTS unique_user_category;
+CREATE UNIQUE INDEX IF NOT EXISTS unique_user_category
+    ON categories(user_id, category_name) WHERE household_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS unique_household_category
+    ON categories(household_id, category_name) WHERE household_id IS NOT NULL;
+
+-- ACCESS CHECKS
+-- Whether for_user can read a row owned by owner_id in household (NULL for a
+-- personal row).
+CREATE OR REPLACE FUNCTION can_view_resource(owner_id UUID, household UUID, for_user UUID)
+RETURNS BOOLEAN AS $$
+    SELECT CASE
+        WHEN household IS NULL THEN owner_id = for_user
+        ELSE EXISTS (
+            SELECT 1 FROM household_members m
+            WHERE m.household_id = household AND m.user_id = for_user
+        )
+    END
+$$ LANGUAGE sql STABLE;
+
+-- Whether for_user can change or delete such a row.
+CREATE OR REPLACE FUNCTION can_edit_resource(owner_id UUID, household UUID, for_user UUID)
+RETURNS BOOLEAN AS $$
+    SELECT CASE
+        WHEN household IS NULL THEN owner_id = for_user
+        ELSE EXISTS (
+            SELECT 1 FROM household_members m
+            WHERE m.household_id = household AND m.user_id = for_user
+                AND m.role IN ('owner', 'editor')
+        )
+    END
+$$ LANGUAGE sql STABLE;
+
+-- Whether a row belongs to a workspace: for_user's personal data when
+-- workspace is NULL, otherwise that household's data. Membership of the
+-- household is checked by the caller.
+CREATE OR REPLACE FUNCTION in_workspace(owner_id UUID, household UUID, for_user UUID, workspace UUID)
+RETURNS BOOLEAN AS $$
+    SELECT CASE
+        WHEN workspace IS NULL THEN household IS NULL AND owner_id = for_user
+        ELSE household IS NOT DISTINCT FROM workspace
+    END
+$$ LANGUAGE sql IMMUTABLE;
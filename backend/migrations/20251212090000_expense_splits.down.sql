@@ -0,0 +1,17 @@
+DROP FUNCTION IF EXISTS split_debts(UUID, UUID);
+
+DROP TRIGGER IF EXISTS update_settlements_updated_at ON settlements;
+DROP INDEX IF EXISTS idx_settlement_household;
+DROP INDEX IF EXISTS idx_settlement_user;
+DROP TABLE IF EXISTS settlements;
+
+DROP INDEX IF EXISTS idx_split_user;
+DROP INDEX IF EXISTS unique_split_contact;
+DROP INDEX IF EXISTS unique_split_user;
+DROP TABLE IF EXISTS expense_splits;
+
+ALTER TABLE expenses DROP CONSTRAINT IF EXISTS check_split_method;
+ALTER TABLE expenses DROP COLUMN IF EXISTS split_method;
+
+DROP TRIGGER IF EXISTS update_contacts_updated_at ON contacts;
+DROP TABLE IF EXISTS contacts;
@@ -0,0 +1,109 @@
+-- CONTACT TABLE
+-- People without an account that expenses can be split with, e.g. friends on
+-- a trip. Contacts belong to the user who added them.
+CREATE TABLE IF NOT EXISTS contacts (
+    contact_id  UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    user_id     UUID NOT NULL,
+    name        VARCHAR(100) NOT NULL,
+    email       VARCHAR(255),
+    created_at  TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at  TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_contact_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT unique_contact_name UNIQUE (user_id, name)
+);
+
+CREATE TRIGGER update_contacts_updated_at BEFORE UPDATE ON contacts
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- How a split expense was divided: equal, exact, percent or shares.
+-- NULL when the expense isn't split.
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS split_method VARCHAR(10);
+ALTER TABLE expenses ADD CONSTRAINT check_split_method
+    CHECK (split_method IN ('equal', 'exact', 'percent', 'shares'));
+
+-- EXPENSE SPLIT TABLE
+-- One participant's share of a split expense: a registered user or a contact.
+-- weight is what the share was computed from (1 for equal, minor units for
+-- exact, basis points for percent, the number of shares for shares) so shares
+-- can be recomputed when the expense amount changes. amount is in minor units
+-- of the expense currency; the shares add up to the expense amount. position
+-- keeps the participants in the order they were given.
+-- Every participant other than the payer owes their share to the payer.
+CREATE TABLE IF NOT EXISTS expense_splits (
+    split_id    UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    expense_id  UUID NOT NULL,
+    user_id     UUID,
+    contact_id  UUID,
+    position    INTEGER NOT NULL,
+    weight      BIGINT NOT NULL,
+    amount      BIGINT NOT NULL,
+    CONSTRAINT fk_split_expense FOREIGN KEY (expense_id) REFERENCES expenses(expense_id) ON DELETE CASCADE,
+    CONSTRAINT fk_split_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_split_contact FOREIGN KEY (contact_id) REFERENCES contacts(contact_id) ON DELETE RESTRICT,
+    CONSTRAINT check_split_participant CHECK ((user_id IS NULL) <> (contact_id IS NULL)),
+    CONSTRAINT check_split_amount CHECK (amount >= 0 AND weight >= 0)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS unique_split_user
+    ON expense_splits(expense_id, user_id) WHERE user_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS unique_split_contact
+    ON expense_splits(expense_id, contact_id) WHERE contact_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_split_user ON expense_splits(user_id) WHERE user_id IS NOT NULL;
+
+-- SETTLEMENT TABLE
+-- A payment that settles (part of) a debt: from_* paid to_* the amount.
+-- Each side is either a registered user or a contact.
+CREATE TABLE IF NOT EXISTS settlements (
+    settlement_id   UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    user_id         UUID NOT NULL,
+    household_id    UUID,
+    from_user_id    UUID,
+    from_contact_id UUID,
+    to_user_id      UUID,
+    to_contact_id   UUID,
+    amount          BIGINT NOT NULL,
+    currency        VARCHAR(3) NOT NULL,
+    date            DATE NOT NULL DEFAULT CURRENT_DATE,
+    note            TEXT,
+    created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_settlement_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_settlement_household FOREIGN KEY (household_id) REFERENCES households(household_id) ON DELETE CASCADE,
+    CONSTRAINT fk_settlement_from_user FOREIGN KEY (from_user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_settlement_from_contact FOREIGN KEY (from_contact_id) REFERENCES contacts(contact_id) ON DELETE RESTRICT,
+    CONSTRAINT fk_settlement_to_user FOREIGN KEY (to_user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_settlement_to_contact FOREIGN KEY (to_contact_id) REFERENCES contacts(contact_id) ON DELETE RESTRICT,
+    CONSTRAINT check_settlement_from CHECK ((from_user_id IS NULL) <> (from_contact_id IS NULL)),
+    CONSTRAINT check_settlement_to CHECK ((to_user_id IS NULL) <> (to_contact_id IS NULL)),
+    CONSTRAINT check_settlement_amount CHECK (amount > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_user ON settlements(user_id);
+CREATE INDEX IF NOT EXISTS idx_settlement_household ON settlements(household_id)
+    WHERE household_id IS NOT NULL;
+
+CREATE TRIGGER update_settlements_updated_at BEFORE UPDATE ON settlements
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Debts of a workspace (see in_workspace) as one row per debtor, creditor and
+-- currency: split shares owed to the payer, minus settlements already paid.
+-- A settlement from A to B counts as B owing A, so it cancels out A's debt.
+CREATE OR REPLACE FUNCTION split_debts(for_user UUID, workspace UUID)
+RETURNS TABLE (
+    debtor_user_id UUID, debtor_contact_id UUID,
+    creditor_user_id UUID, creditor_contact_id UUID,
+    currency VARCHAR, amount BIGINT
+) AS $$
+    SELECT s.user_id, s.contact_id, COALESCE(e.paid_by, e.user_id), NULL::UUID,
+        e.currency, s.amount
+    FROM expense_splits s
+    JOIN expenses e ON e.expense_id = s.expense_id
+    WHERE in_workspace(e.user_id, e.household_id, for_user, workspace)
+        AND s.user_id IS DISTINCT FROM COALESCE(e.paid_by, e.user_id)
+        AND s.amount > 0
+    UNION ALL
+    SELECT st.to_user_id, st.to_contact_id, st.from_user_id, st.from_contact_id,
+        st.currency, st.amount
+    FROM settlements st
+    WHERE in_workspace(st.user_id, st.household_id, for_user, workspace)
+$$ LANGUAGE sql STABLE;
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_contribution_goal;
+DROP TABLE IF EXISTS goal_contributions;
+
+DROP TRIGGER IF EXISTS update_savings_goals_updated_at ON savings_goals;
+DROP INDEX IF EXISTS idx_goal_user;
+DROP TABLE IF EXISTS savings_goals;
@@ -0,0 +1,42 @@
+-- SAVINGS GOAL TABLE
+-- Money the user wants to put aside, e.g. for a holiday. target_amount is in
+-- minor units of the goal currency. A goal can be linked to the account the
+-- money is kept in, which must use the same currency. last_milestone is the
+-- highest progress milestone (25, 50, 75 or 100 percent) already notified.
+CREATE TABLE IF NOT EXISTS savings_goals (
+    goal_id         UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    user_id         UUID NOT NULL,
+    name            VARCHAR(100) NOT NULL,
+    target_amount   BIGINT NOT NULL,
+    currency        VARCHAR(3) NOT NULL,
+    target_date     DATE,
+    account_id      UUID,
+    last_milestone  SMALLINT NOT NULL DEFAULT 0,
+    created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_goal_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_goal_account FOREIGN KEY (account_id) REFERENCES accounts(account_id) ON DELETE SET NULL,
+    CONSTRAINT unique_goal_name UNIQUE (user_id, name),
+    CONSTRAINT check_goal_target CHECK (target_amount > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_goal_user ON savings_goals(user_id);
+
+CREATE TRIGGER update_savings_goals_updated_at BEFORE UPDATE ON savings_goals
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- GOAL CONTRIBUTION TABLE
+-- Money put towards a goal, in minor units of the goal currency. Negative
+-- amounts are withdrawals.
+CREATE TABLE IF NOT EXISTS goal_contributions (
+    contribution_id UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    goal_id         UUID NOT NULL,
+    amount          BIGINT NOT NULL,
+    date            DATE NOT NULL DEFAULT CURRENT_DATE,
+    note            TEXT,
+    created_at      TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_contribution_goal FOREIGN KEY (goal_id) REFERENCES savings_goals(goal_id) ON DELETE CASCADE,
+    CONSTRAINT check_contribution_amount CHECK (amount <> 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contribution_goal ON goal_contributions(goal_id, date);
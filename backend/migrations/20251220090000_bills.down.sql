@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS bill_payments;
+
+DROP TRIGGER IF EXISTS update_bills_updated_at ON bills;
+DROP INDEX IF EXISTS idx_bill_due;
+DROP INDEX IF EXISTS idx_bill_user;
+DROP TABLE IF EXISTS bills;
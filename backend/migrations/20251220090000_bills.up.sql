@@ -0,0 +1,56 @@
+-- BILL TABLE
+-- A payment that comes up on a due date, once or on a recurrence (weekly,
+-- monthly, quarterly or yearly). due_date is the next unpaid occurrence;
+-- occurrences are counted from start_date so month ends don't drift.
+-- Autopay bills are marked paid by the scheduler on their due date. The
+-- scheduler also sends a reminder remind_days_before the due date and an
+-- overdue notice once the due date passed; last_reminded_for and
+-- last_overdue_for remember the occurrence each was sent for.
+CREATE TABLE IF NOT EXISTS bills (
+    bill_id            UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    user_id            UUID NOT NULL,
+    name               VARCHAR(100) NOT NULL,
+    amount             BIGINT NOT NULL,
+    currency           VARCHAR(3) NOT NULL,
+    start_date         DATE NOT NULL,
+    due_date           DATE NOT NULL,
+    recurrence         VARCHAR(10) NOT NULL DEFAULT 'none',
+    autopay            BOOLEAN NOT NULL DEFAULT FALSE,
+    remind_days_before INTEGER NOT NULL DEFAULT 3,
+    category_id        INTEGER,
+    account_id         UUID,
+    is_active          BOOLEAN NOT NULL DEFAULT TRUE,
+    last_reminded_for  DATE,
+    last_overdue_for   DATE,
+    created_at         TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at         TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_bill_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT fk_bill_category FOREIGN KEY (category_id) REFERENCES categories(category_id) ON DELETE SET NULL,
+    CONSTRAINT fk_bill_account FOREIGN KEY (account_id) REFERENCES accounts(account_id) ON DELETE SET NULL,
+    CONSTRAINT check_bill_amount CHECK (amount > 0),
+    CONSTRAINT check_bill_recurrence
+        CHECK (recurrence IN ('none', 'weekly', 'monthly', 'quarterly', 'yearly')),
+    CONSTRAINT check_bill_reminder CHECK (remind_days_before BETWEEN 0 AND 60)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bill_user ON bills(user_id, due_date);
+CREATE INDEX IF NOT EXISTS idx_bill_due ON bills(due_date) WHERE is_active;
+
+CREATE TRIGGER update_bills_updated_at BEFORE UPDATE ON bills
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- BILL PAYMENT TABLE
+-- One paid occurrence of a bill and the expense recorded for it.
+CREATE TABLE IF NOT EXISTS bill_payments (
+    payment_id  UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    bill_id     UUID NOT NULL,
+    expense_id  UUID,
+    due_date    DATE NOT NULL,
+    paid_on     DATE NOT NULL,
+    amount      BIGINT NOT NULL,
+    autopaid    BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at  TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_payment_bill FOREIGN KEY (bill_id) REFERENCES bills(bill_id) ON DELETE CASCADE,
+    CONSTRAINT fk_payment_expense FOREIGN KEY (expense_id) REFERENCES expenses(expense_id) ON DELETE SET NULL,
+    CONSTRAINT unique_bill_occurrence UNIQUE (bill_id, due_date)
+);
@@ -0,0 +1,65 @@
+CREATE OR REPLACE FUNCTION split_debts(for_user UUID, workspace UUID)
+RETURNS TABLE (
+    debtor_user_id UUID, debtor_contact_id UUID,
+    creditor_user_id UUID, creditor_contact_id UUID,
+    currency VARCHAR, amount BIGINT
+) AS $$
+    SELECT s.user_id, s.contact_id, COALESCE(e.paid_by, e.user_id), NULL::UUID,
+        e.currency, s.amount
+    FROM expense_splits s
+    JOIN expenses e ON e.expense_id = s.expense_id
+    WHERE in_workspace(e.user_id, e.household_id, for_user, workspace)
+        AND s.user_id IS DISTINCT FROM COALESCE(e.paid_by, e.user_id)
+        AND s.amount > 0
+    UNION ALL
+    SELECT st.to_user_id, st.to_contact_id, st.from_user_id, st.from_contact_id,
+        st.currency, st.amount
+    FROM settlements st
+    WHERE in_workspace(st.user_id, st.household_id, for_user, workspace)
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION account_entries(for_account UUID)
+RETURNS TABLE (
+    entry_id UUID, entry_type VARCHAR, date DATE, description TEXT, amount BIGINT,
+    created_at TIMESTAMP WITH TIME ZONE
+) AS $$
+    SELECT e.expense_id, e.transaction_type::VARCHAR, e.date, e.name::TEXT,
+        convert_amount(
+            CASE e.transaction_type WHEN 'expense' THEN -e.amount ELSE e.amount END,
+            e.currency, a.currency, e.date, a.user_id
+        ),
+        e.created_at
+    FROM expenses e
+    JOIN accounts a ON a.account_id = e.account_id
+    WHERE e.account_id = for_account
+    UNION ALL
+    SELECT t.transfer_id, 'transfer_out'::VARCHAR, t.date,
+        COALESCE(t.description, 'Transfer'), -t.amount, t.created_at
+    FROM transfers t
+    WHERE t.from_account_id = for_account
+    UNION ALL
+    SELECT t.transfer_id, 'transfer_in'::VARCHAR, t.date,
+        COALESCE(t.description, 'Transfer'), t.to_amount, t.created_at
+    FROM transfers t
+    WHERE t.to_account_id = for_account
+$$ LANGUAGE sql STABLE;
+
+-- Without a trash, whatever is in it is gone
+DELETE FROM expenses WHERE deleted_at IS NOT NULL;
+DELETE FROM budgets WHERE deleted_at IS NOT NULL;
+DELETE FROM categories WHERE deleted_at IS NOT NULL;
+
+DROP INDEX IF EXISTS unique_household_category;
+DROP INDEX IF EXISTS unique_user_category;
+CREATE UNIQUE INDEX IF NOT EXISTS unique_user_category
+    ON categories(user_id, category_name) WHERE household_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS unique_household_category
+    ON categories(household_id, category_name) WHERE household_id IS NOT NULL;
+
+DROP INDEX IF EXISTS idx_category_deleted;
+DROP INDEX IF EXISTS idx_budget_deleted;
+DROP INDEX IF EXISTS idx_expense_deleted;
+
+ALTER TABLE categories DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE budgets DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE expenses DROP COLUMN IF EXISTS deleted_at;
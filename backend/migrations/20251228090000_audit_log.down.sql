@@ -0,0 +1,12 @@
+DROP TRIGGER IF EXISTS audit_categories ON categories;
+DROP TRIGGER IF EXISTS audit_budgets ON budgets;
+DROP TRIGGER IF EXISTS audit_expenses ON expenses;
+DROP FUNCTION IF EXISTS record_audit_log();
+
+DROP TRIGGER IF EXISTS audit_log_append_only ON audit_log;
+DROP FUNCTION IF EXISTS prevent_audit_log_change();
+
+DROP INDEX IF EXISTS idx_audit_household;
+DROP INDEX IF EXISTS idx_audit_owner;
+DROP INDEX IF EXISTS idx_audit_entity;
+DROP TABLE IF EXISTS audit_log;
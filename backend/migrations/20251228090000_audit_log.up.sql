@@ -0,0 +1,97 @@
+-- AUDIT LOG
+-- Append-only history of changes to expenses, budgets and categories, written
+-- by triggers in the same transaction as the change. user_id is whoever made
+-- the change (the app.user_id setting of the transaction, NULL for background
+-- jobs); owner_id and household_id are copied from the row to decide who can
+-- read the entry. A create stores the new row in after_data and a hard delete
+-- or purge the old row in before_data. Every other change, including moving a
+-- row to the trash and restoring it, stores only the fields that changed: old
+-- values in before_data, new ones in after_data. No foreign keys, so entries
+-- outlive the rows and users they mention.
+CREATE TABLE IF NOT EXISTS audit_log (
+    audit_id     BIGSERIAL PRIMARY KEY,
+    entity_type  VARCHAR(20) NOT NULL,
+    entity_id    VARCHAR(36) NOT NULL,
+    action       VARCHAR(10) NOT NULL,
+    user_id      UUID,
+    owner_id     UUID,
+    household_id UUID,
+    before_data  JSONB,
+    after_data   JSONB,
+    created_at   TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+
+    CONSTRAINT check_audit_entity_type CHECK (entity_type IN ('expense', 'budget', 'category')),
+    CONSTRAINT check_audit_action CHECK (action IN ('create', 'update', 'delete', 'restore', 'purge'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_entity ON audit_log(entity_type, entity_id, audit_id);
+CREATE INDEX IF NOT EXISTS idx_audit_owner ON audit_log(owner_id, audit_id);
+CREATE INDEX IF NOT EXISTS idx_audit_household ON audit_log(household_id, audit_id)
+    WHERE household_id IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION prevent_audit_log_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION prevent_audit_log_change();
+
+-- Records one change of an audited table. TG_ARGV[0] is the entity type and
+-- TG_ARGV[1] the id column.
+CREATE OR REPLACE FUNCTION record_audit_log()
+RETURNS TRIGGER AS $$
+DECLARE
+    old_row JSONB := CASE WHEN TG_OP <> 'INSERT' THEN to_jsonb(OLD) END;
+    new_row JSONB := CASE WHEN TG_OP <> 'DELETE' THEN to_jsonb(NEW) END;
+    row_data JSONB := COALESCE(new_row, old_row);
+    change_action VARCHAR;
+    before_data JSONB;
+    after_data JSONB;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        change_action := 'create';
+        after_data := new_row;
+    ELSIF TG_OP = 'DELETE' THEN
+        change_action := CASE WHEN old_row->>'deleted_at' IS NULL THEN 'delete' ELSE 'purge' END;
+        before_data := old_row;
+    ELSE
+        change_action := CASE
+            WHEN old_row->>'deleted_at' IS NULL AND new_row->>'deleted_at' IS NOT NULL THEN 'delete'
+            WHEN old_row->>'deleted_at' IS NOT NULL AND new_row->>'deleted_at' IS NULL THEN 'restore'
+            ELSE 'update'
+        END;
+        SELECT jsonb_object_agg(o.key, o.value), jsonb_object_agg(o.key, new_row->o.key)
+        INTO before_data, after_data
+        FROM jsonb_each(old_row) o
+        WHERE o.key <> 'updated_at' AND o.value IS DISTINCT FROM new_row->o.key;
+
+        IF before_data IS NULL THEN
+            RETURN NULL;
+        END IF;
+    END IF;
+
+    INSERT INTO audit_log (
+        entity_type, entity_id, action, user_id, owner_id, household_id, before_data, after_data
+    ) VALUES (
+        TG_ARGV[0],
+        row_data->>TG_ARGV[1],
+        change_action,
+        NULLIF(current_setting('app.user_id', TRUE), '')::UUID,
+        (row_data->>'user_id')::UUID,
+        (row_data->>'household_id')::UUID,
+        before_data,
+        after_data
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_expenses AFTER INSERT OR UPDATE OR DELETE ON expenses
+    FOR EACH ROW EXECUTE FUNCTION record_audit_log('expense', 'expense_id');
+CREATE TRIGGER audit_budgets AFTER INSERT OR UPDATE OR DELETE ON budgets
+    FOR EACH ROW EXECUTE FUNCTION record_audit_log('budget', 'budget_id');
+CREATE TRIGGER audit_categories AFTER INSERT OR UPDATE OR DELETE ON categories
+    FOR EACH ROW EXECUTE FUNCTION record_audit_log('category', 'category_id');
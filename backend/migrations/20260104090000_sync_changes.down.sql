@@ -0,0 +1,14 @@
+DROP TRIGGER IF EXISTS sync_household_members ON household_members;
+DROP TRIGGER IF EXISTS sync_expense_tags ON expense_tags;
+DROP TRIGGER IF EXISTS sync_notifications ON notifications;
+DROP TRIGGER IF EXISTS sync_categories ON categories;
+DROP TRIGGER IF EXISTS sync_budgets ON budgets;
+DROP TRIGGER IF EXISTS sync_expenses ON expenses;
+
+DROP FUNCTION IF EXISTS record_membership_sync_change();
+DROP FUNCTION IF EXISTS record_expense_tag_sync_change();
+DROP FUNCTION IF EXISTS record_row_sync_change();
+DROP FUNCTION IF EXISTS record_sync_change(UUID, UUID, VARCHAR, VARCHAR, BOOLEAN);
+
+DROP TABLE IF EXISTS sync_changes;
+DROP TABLE IF EXISTS sync_sequences;
@@ -0,0 +1,146 @@
+-- SYNC CHANGES
+-- Change feed for offline clients. Every user has their own change sequence
+-- (sync_sequences.last_seq) and every change to an expense, budget, category
+-- or notification they can see bumps it. sync_changes keeps only the latest
+-- change per entity, so a client catching up gets each entity once, with
+-- deleted set for rows that are gone or in the trash (tombstones).
+--
+-- Bumping the sequence locks the user's sync_sequences row until the
+-- transaction ends, so a user's changes commit in sequence order and a client
+-- that has seen last_seq has seen every change up to it.
+CREATE TABLE IF NOT EXISTS sync_sequences (
+    user_id   UUID PRIMARY KEY,
+    last_seq  BIGINT NOT NULL DEFAULT 0,
+    CONSTRAINT fk_sync_sequence_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS sync_changes (
+    user_id      UUID NOT NULL,
+    entity_type  VARCHAR(20) NOT NULL,
+    entity_id    VARCHAR(36) NOT NULL,
+    seq          BIGINT NOT NULL,
+    deleted      BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (user_id, entity_type, entity_id),
+    CONSTRAINT fk_sync_change_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE,
+    CONSTRAINT check_sync_entity_type CHECK (entity_type IN ('expense', 'budget', 'category', 'notification'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_change_seq ON sync_changes(user_id, seq);
+
+-- Records a change of one entity for everyone who can see it: the members of
+-- its household, or its owner when it is personal. Global categories have
+-- neither and never change.
+CREATE OR REPLACE FUNCTION record_sync_change(
+    owner UUID, household UUID, change_entity_type VARCHAR, change_entity_id VARCHAR, is_deleted BOOLEAN
+)
+RETURNS VOID AS $$
+DECLARE
+    member UUID;
+    next_seq BIGINT;
+BEGIN
+    -- Members in a fixed order, so concurrent changes lock sequences alike
+    FOR member IN
+        SELECT m.user_id FROM household_members m
+        WHERE household IS NOT NULL AND m.household_id = household
+        UNION
+        SELECT owner WHERE household IS NULL AND owner IS NOT NULL
+        ORDER BY 1
+    LOOP
+        INSERT INTO sync_sequences (user_id, last_seq) VALUES (member, 1)
+        ON CONFLICT (user_id) DO UPDATE SET last_seq = sync_sequences.last_seq + 1
+        RETURNING last_seq INTO next_seq;
+
+        INSERT INTO sync_changes (user_id, entity_type, entity_id, seq, deleted)
+        VALUES (member, change_entity_type, change_entity_id, next_seq, is_deleted)
+        ON CONFLICT (user_id, entity_type, entity_id)
+        DO UPDATE SET seq = EXCLUDED.seq, deleted = EXCLUDED.deleted;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Row trigger for synced tables. TG_ARGV[0] is the entity type and TG_ARGV[1]
+-- the id column. Moving a row to the trash counts as deleting it.
+CREATE OR REPLACE FUNCTION record_row_sync_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    row_data JSONB := CASE WHEN TG_OP = 'DELETE' THEN to_jsonb(OLD) ELSE to_jsonb(NEW) END;
+BEGIN
+    PERFORM record_sync_change(
+        (row_data->>'user_id')::UUID,
+        (row_data->>'household_id')::UUID,
+        TG_ARGV[0],
+        row_data->>TG_ARGV[1],
+        TG_OP = 'DELETE' OR row_data->>'deleted_at' IS NOT NULL
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Tags are part of the synced expense, so tagging one changes it
+CREATE OR REPLACE FUNCTION record_expense_tag_sync_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    expense RECORD;
+BEGIN
+    SELECT user_id, household_id, deleted_at INTO expense
+    FROM expenses
+    WHERE expense_id = CASE WHEN TG_OP = 'DELETE' THEN OLD.expense_id ELSE NEW.expense_id END;
+
+    IF FOUND AND expense.deleted_at IS NULL THEN
+        PERFORM record_sync_change(
+            expense.user_id, expense.household_id, 'expense',
+            (CASE WHEN TG_OP = 'DELETE' THEN OLD.expense_id ELSE NEW.expense_id END)::VARCHAR,
+            FALSE
+        );
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Joining a household brings its data to the new member's devices and
+-- leaving it takes the data away again.
+CREATE OR REPLACE FUNCTION record_membership_sync_change()
+RETURNS TRIGGER AS $$
+DECLARE
+    member_row household_members := CASE WHEN TG_OP = 'DELETE' THEN OLD ELSE NEW END;
+    entity RECORD;
+    next_seq BIGINT;
+BEGIN
+    FOR entity IN
+        SELECT 'expense' AS entity_type, expense_id::VARCHAR AS entity_id, deleted_at
+        FROM expenses WHERE household_id = member_row.household_id
+        UNION ALL
+        SELECT 'budget', budget_id::VARCHAR, deleted_at
+        FROM budgets WHERE household_id = member_row.household_id
+        UNION ALL
+        SELECT 'category', category_id::VARCHAR, deleted_at
+        FROM categories WHERE household_id = member_row.household_id
+    LOOP
+        INSERT INTO sync_sequences (user_id, last_seq) VALUES (member_row.user_id, 1)
+        ON CONFLICT (user_id) DO UPDATE SET last_seq = sync_sequences.last_seq + 1
+        RETURNING last_seq INTO next_seq;
+
+        INSERT INTO sync_changes (user_id, entity_type, entity_id, seq, deleted)
+        VALUES (
+            member_row.user_id, entity.entity_type, entity.entity_id, next_seq,
+            TG_OP = 'DELETE' OR entity.deleted_at IS NOT NULL
+        )
+        ON CONFLICT (user_id, entity_type, entity_id)
+        DO UPDATE SET seq = EXCLUDED.seq, deleted = EXCLUDED.deleted;
+    END LOOP;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER sync_expenses AFTER INSERT OR UPDATE OR DELETE ON expenses
+    FOR EACH ROW EXECUTE FUNCTION record_row_sync_change('expense', 'expense_id');
+CREATE TRIGGER sync_budgets AFTER INSERT OR UPDATE OR DELETE ON budgets
+    FOR EACH ROW EXECUTE FUNCTION record_row_sync_change('budget', 'budget_id');
+CREATE TRIGGER sync_categories AFTER INSERT OR UPDATE OR DELETE ON categories
+    FOR EACH ROW EXECUTE FUNCTION record_row_sync_change('category', 'category_id');
+CREATE TRIGGER sync_notifications AFTER INSERT OR UPDATE OR DELETE ON notifications
+    FOR EACH ROW EXECUTE FUNCTION record_row_sync_change('notification', 'notification_id');
+CREATE TRIGGER sync_expense_tags AFTER INSERT OR DELETE ON expense_tags
+    FOR EACH ROW EXECUTE FUNCTION record_expense_tag_sync_change();
+CREATE TRIGGER sync_household_members AFTER INSERT OR DELETE ON household_members
+    FOR EACH ROW EXECUTE FUNCTION record_membership_sync_change();
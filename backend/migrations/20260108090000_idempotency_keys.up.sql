@@ -0,0 +1,20 @@
+-- IDEMPOTENCY KEYS
+-- Responses to POST requests sent with an Idempotency-Key header, so a retry
+-- gets the original response instead of creating the resource again. A row is
+-- claimed before the request runs (status_code still NULL) and filled in with
+-- the response once it finishes. fingerprint is a hash of the method, path and
+-- body, to refuse reusing a key for a different request. Expired rows are
+-- purged by a background job and can be claimed again before that.
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    user_id          UUID NOT NULL,
+    idempotency_key  VARCHAR(255) NOT NULL,
+    fingerprint      VARCHAR(64) NOT NULL,
+    status_code      SMALLINT,
+    response_body    BYTEA,
+    created_at       TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    expires_at       TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (user_id, idempotency_key),
+    CONSTRAINT fk_idempotency_key_user FOREIGN KEY (user_id) REFERENCES users(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_key_expires ON idempotency_keys(expires_at);
@@ -0,0 +1,26 @@
+-- JOBS
+-- Background work queued by the API and run by the server's job workers, like
+-- budget alerts after an expense is created. A job is queued in the same
+-- transaction as the change that causes it, so it's never lost or run for a
+-- change that was rolled back. Workers claim due jobs with FOR UPDATE SKIP
+-- LOCKED; a failed job is retried at a later run_at until it has been tried
+-- max_attempts times, then it's marked dead and kept along with last_error.
+-- A running job whose worker died is claimed again once its lock is stale.
+CREATE TABLE IF NOT EXISTS jobs (
+    job_id        UUID PRIMARY KEY DEFAULT (uuid_generate_v4()),
+    job_type      VARCHAR(64) NOT NULL,
+    payload       JSONB NOT NULL DEFAULT '{}'::JSONB,
+    status        VARCHAR(16) NOT NULL DEFAULT 'pending'
+                  CHECK (status IN ('pending', 'running', 'succeeded', 'dead')),
+    attempts      INTEGER NOT NULL DEFAULT 0,
+    max_attempts  INTEGER NOT NULL DEFAULT 5 CHECK (max_attempts > 0),
+    run_at        TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    locked_at     TIMESTAMP WITH TIME ZONE,
+    last_error    TEXT,
+    finished_at   TIMESTAMP WITH TIME ZONE,
+    created_at    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at    TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_due ON jobs(run_at) WHERE status IN ('pending', 'running');
+CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status, job_type);
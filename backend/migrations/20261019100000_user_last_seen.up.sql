@@ -0,0 +1,6 @@
+-- USER LAST SEEN
+-- When a user last made an authenticated request, for counting active users.
+-- Updated at most every few minutes per user, not on every request.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_seen_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_users_last_seen ON users(last_seen_at);
@@ -0,0 +1,37 @@
+//! Loads ECB-style reference exchange rates into the database.
+//!
+//! Usage: `cargo run --bin load_rates -- <eurofxref.csv | eurofxref-hist.xml ...>`
+
+use backend::utils::currency::{parse_ecb_rates, store_reference_rates};
+use dotenv::dotenv;
+use sqlx::postgres::PgPoolOptions;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let files: Vec<String> = std::env::args().skip(1).collect();
+    if files.is_empty() {
+        eprintln!("Usage: load_rates <rates file>...");
+        std::process::exit(2);
+    }
+
+    let db_connection_str =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in environment variables");
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&db_connection_str)
+        .await?;
+
+    for file in files {
+        let content = std::fs::read_to_string(&file)
+            .map_err(|err| format!("Failed to read {}: {}", file, err))?;
+        let rates = parse_ecb_rates(&content).map_err(|err| format!("{}: {}", file, err))?;
+        let written = store_reference_rates(&pool, &rates, "ecb").await?;
+
+        println!("{}: loaded {} rates", file, written);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,414 @@
+use std::{collections::HashMap, fmt, net::SocketAddr, path::Path, str::FromStr};
+
+use crate::middleware::version::ClientVersion;
+
+/// File read when `CONFIG_FILE` isn't set; it's fine for it not to exist.
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Longest a login can be configured to stay valid.
+const MAX_TOKEN_LIFETIME_DAYS: i64 = 365;
+
+/// Settings of the server, loaded once at startup by [`Config::load`].
+///
+/// Every setting is read from an environment variable, or else from the
+/// lowercased key in the TOML file named by `CONFIG_FILE` (`config.toml` by
+/// default), e.g. `JWT_SECRET` or `jwt_secret = "..."`. The environment wins
+/// when both are set.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database_url: String,
+    /// `DATABASE_MAX_CONNECTIONS`, 10 by default
+    pub database_max_connections: u32,
+    /// `BIND_ADDR`, `0.0.0.0:8080` by default
+    pub bind_addr: SocketAddr,
+    /// `CORS_ALLOWED_ORIGINS`, comma separated; `*` (the default) allows any
+    pub cors_allowed_origins: Vec<String>,
+    pub jwt_secret: String,
+    /// `TOKEN_LIFETIME_DAYS`, how long a login stays valid; 30 by default,
+    /// at most 365
+    pub token_lifetime_days: i64,
+    pub storage: StorageConfig,
+    /// `BILL_SCHEDULER_INTERVAL_SECS`, hourly by default
+    pub bill_scheduler_interval_secs: u64,
+    /// `TRASH_RETENTION_DAYS`, 30 by default
+    pub trash_retention_days: i64,
+    /// `IDEMPOTENCY_KEY_TTL_HOURS`, 24 by default
+    pub idempotency_key_ttl_hours: i32,
+    /// `MIN_CLIENT_VERSION`; every app version is served when unset
+    pub min_client_version: Option<ClientVersion>,
+    /// `SHUTDOWN_TIMEOUT_SECS`, how long open requests and background tasks
+    /// get to finish after SIGTERM or Ctrl-C; 30 by default
+    pub shutdown_timeout_secs: u64,
+    /// `JOB_WORKERS`, how many queued jobs run at once; 4 by default
+    pub job_workers: usize,
+    /// `METRICS_TOKEN`; when set, `/metrics` needs it as a bearer token
+    pub metrics_token: Option<String>,
+}
+
+/// Where expense attachments are stored, from `STORAGE_BACKEND`.
+#[derive(Debug, Clone)]
+pub enum StorageConfig {
+    /// Files under `STORAGE_LOCAL_PATH` (`./uploads` by default)
+    Local { path: String },
+    /// Any S3-compatible service
+    S3 {
+        bucket: String,
+        region: String,
+        endpoint: Option<String>,
+        access_key_id: Option<String>,
+        secret_access_key: Option<String>,
+    },
+}
+
+/// Every missing or invalid setting found while loading the config.
+#[derive(Debug)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid configuration:")?;
+        for error in &self.0 {
+            writeln!(f, "  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Loads the config from the environment and the optional config file,
+    /// reporting all problems at once rather than stopping at the first.
+    pub fn load() -> Result<Config, ConfigError> {
+        // Variables that aren't valid UTF-8 count as unset
+        let vars = std::env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .collect();
+        Self::from_env(vars)
+    }
+
+    /// [`load`](Self::load) with `vars` as the environment.
+    fn from_env(vars: HashMap<String, String>) -> Result<Config, ConfigError> {
+        let mut source = Source {
+            env: vars,
+            ..Source::default()
+        };
+
+        let file = source.env.get("CONFIG_FILE").cloned();
+        let path = file.as_deref().unwrap_or(DEFAULT_CONFIG_FILE);
+        if file.is_some() || Path::new(path).exists() {
+            match std::fs::read_to_string(path) {
+                Ok(content) => match content.parse::<toml::Table>() {
+                    Ok(table) => source.file = table,
+                    Err(err) => source.errors.push(format!("{}: {}", path, err)),
+                },
+                Err(err) => source.errors.push(format!("{}: {}", path, err)),
+            }
+        }
+
+        let config = Config {
+            database_url: source.required("DATABASE_URL"),
+            database_max_connections: source.positive("DATABASE_MAX_CONNECTIONS", 10),
+            bind_addr: source.parsed("BIND_ADDR", SocketAddr::from(([0, 0, 0, 0], 8080))),
+            cors_allowed_origins: source.cors_allowed_origins(),
+            jwt_secret: source.required("JWT_SECRET"),
+            token_lifetime_days: source.at_most("TOKEN_LIFETIME_DAYS", 30, MAX_TOKEN_LIFETIME_DAYS),
+            storage: source.storage(),
+            bill_scheduler_interval_secs: source.positive("BILL_SCHEDULER_INTERVAL_SECS", 3600),
+            trash_retention_days: source.positive("TRASH_RETENTION_DAYS", 30),
+            idempotency_key_ttl_hours: source.positive("IDEMPOTENCY_KEY_TTL_HOURS", 24),
+            min_client_version: source
+                .get("MIN_CLIENT_VERSION")
+                .and_then(|version| source.parse("MIN_CLIENT_VERSION", &version)),
+            shutdown_timeout_secs: source.positive("SHUTDOWN_TIMEOUT_SECS", 30),
+            job_workers: source.positive("JOB_WORKERS", 4),
+            metrics_token: source.get("METRICS_TOKEN"),
+        };
+
+        if source.errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(source.errors))
+        }
+    }
+}
+
+/// Settings as they are read, with the problems found so far.
+#[derive(Default)]
+struct Source {
+    env: HashMap<String, String>,
+    file: toml::Table,
+    errors: Vec<String>,
+}
+
+impl Source {
+    /// The raw value of a setting; blank values count as unset.
+    fn get(&self, name: &str) -> Option<String> {
+        let value = self.env.get(name).cloned().or_else(|| {
+            self.file
+                .get(&name.to_lowercase())
+                .map(|value| match value {
+                    toml::Value::String(value) => value.clone(),
+                    other => other.to_string(),
+                })
+        })?;
+        Some(value.trim().to_string()).filter(|value| !value.is_empty())
+    }
+
+    fn required(&mut self, name: &str) -> String {
+        self.get(name).unwrap_or_else(|| {
+            self.errors.push(format!("{} must be set", name));
+            String::new()
+        })
+    }
+
+    fn parse<T: FromStr>(&mut self, name: &str, value: &str) -> Option<T>
+    where
+        T::Err: fmt::Display,
+    {
+        value
+            .parse()
+            .map_err(|err| {
+                self.errors
+                    .push(format!("{}: invalid value '{}': {}", name, value, err))
+            })
+            .ok()
+    }
+
+    fn parsed<T: FromStr>(&mut self, name: &str, default: T) -> T
+    where
+        T::Err: fmt::Display,
+    {
+        match self.get(name) {
+            Some(value) => self.parse(name, &value).unwrap_or(default),
+            None => default,
+        }
+    }
+
+    fn positive<T: FromStr + PartialOrd + Default + Copy>(&mut self, name: &str, default: T) -> T
+    where
+        T::Err: fmt::Display,
+    {
+        let value = self.parsed(name, default);
+        if value <= T::default() {
+            self.errors.push(format!("{} must be greater than 0", name));
+        }
+        value
+    }
+
+    fn at_most<T: FromStr + PartialOrd + Default + Copy + fmt::Display>(
+        &mut self,
+        name: &str,
+        default: T,
+        max: T,
+    ) -> T
+    where
+        T::Err: fmt::Display,
+    {
+        let value = self.positive(name, default);
+        if value > max {
+            self.errors
+                .push(format!("{} must be at most {}", name, max));
+        }
+        value
+    }
+
+    fn cors_allowed_origins(&mut self) -> Vec<String> {
+        let origins: Vec<String> = self
+            .get("CORS_ALLOWED_ORIGINS")
+            .unwrap_or_else(|| "*".to_string())
+            .split(',')
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+
+        for origin in &origins {
+            if origin != "*" && axum::http::HeaderValue::from_str(origin).is_err() {
+                self.errors
+                    .push(format!("CORS_ALLOWED_ORIGINS: invalid origin '{}'", origin));
+            }
+        }
+        if origins.len() > 1 && origins.iter().any(|origin| origin == "*") {
+            self.errors
+                .push("CORS_ALLOWED_ORIGINS: '*' can't be combined with other origins".to_string());
+        }
+        origins
+    }
+
+    fn storage(&mut self) -> StorageConfig {
+        match self.get("STORAGE_BACKEND").as_deref().unwrap_or("local") {
+            "s3" => StorageConfig::S3 {
+                bucket: self.required("S3_BUCKET"),
+                region: self
+                    .get("S3_REGION")
+                    .unwrap_or_else(|| "us-east-1".to_string()),
+                endpoint: self.get("S3_ENDPOINT"),
+                access_key_id: self.get("S3_ACCESS_KEY_ID"),
+                secret_access_key: self.get("S3_SECRET_ACCESS_KEY"),
+            },
+            other => {
+                if other != "local" {
+                    self.errors.push(format!(
+                        "STORAGE_BACKEND must be 'local' or 's3', not '{}'",
+                        other
+                    ));
+                }
+                StorageConfig::Local {
+                    path: self
+                        .get("STORAGE_LOCAL_PATH")
+                        .unwrap_or_else(|| "./uploads".to_string()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        let mut env: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        // Keep a config.toml in the working directory out of the tests
+        env.entry("CONFIG_FILE".to_string())
+            .or_insert_with(|| write_config_file("empty", ""));
+        env
+    }
+
+    /// Writes a config file only this test reads and returns its path.
+    fn write_config_file(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "backend-config-test-{}-{}.toml",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn required() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("DATABASE_URL", "postgres://localhost/test"),
+            ("JWT_SECRET", "secret"),
+        ]
+    }
+
+    #[test]
+    fn defaults_apply_when_only_required_settings_are_set() {
+        let config = Config::from_env(env(&required())).unwrap();
+
+        assert_eq!(config.database_url, "postgres://localhost/test");
+        assert_eq!(config.jwt_secret, "secret");
+        assert_eq!(config.database_max_connections, 10);
+        assert_eq!(config.bind_addr, SocketAddr::from(([0, 0, 0, 0], 8080)));
+        assert_eq!(config.cors_allowed_origins, vec!["*"]);
+        assert_eq!(config.token_lifetime_days, 30);
+        assert_eq!(config.bill_scheduler_interval_secs, 3600);
+        assert_eq!(config.trash_retention_days, 30);
+        assert_eq!(config.idempotency_key_ttl_hours, 24);
+        assert!(config.min_client_version.is_none());
+        assert_eq!(config.shutdown_timeout_secs, 30);
+        assert_eq!(config.job_workers, 4);
+        assert!(config.metrics_token.is_none());
+        assert!(matches!(config.storage, StorageConfig::Local { ref path } if path == "./uploads"));
+    }
+
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let err = Config::from_env(env(&[
+            ("DATABASE_MAX_CONNECTIONS", "0"),
+            ("BIND_ADDR", "not an address"),
+            ("TOKEN_LIFETIME_DAYS", "100000"),
+            ("JOB_WORKERS", "many"),
+            ("CORS_ALLOWED_ORIGINS", "*,https://example.com"),
+            ("STORAGE_BACKEND", "ftp"),
+        ]))
+        .unwrap_err();
+
+        let errors = err.0;
+        assert_eq!(errors.len(), 8, "{errors:#?}");
+        for name in [
+            "DATABASE_URL must be set",
+            "JWT_SECRET must be set",
+            "DATABASE_MAX_CONNECTIONS must be greater than 0",
+            "BIND_ADDR: invalid value 'not an address'",
+            "TOKEN_LIFETIME_DAYS must be at most 365",
+            "JOB_WORKERS: invalid value 'many'",
+            "CORS_ALLOWED_ORIGINS: '*' can't be combined",
+            "STORAGE_BACKEND must be 'local' or 's3'",
+        ] {
+            assert!(
+                errors.iter().any(|error| error.starts_with(name)),
+                "missing {name:?} in {errors:#?}"
+            );
+        }
+    }
+
+    #[test]
+    fn blank_values_count_as_unset() {
+        let mut vars = required();
+        vars.push(("JOB_WORKERS", "  "));
+        vars.push(("METRICS_TOKEN", ""));
+        let config = Config::from_env(env(&vars)).unwrap();
+
+        assert_eq!(config.job_workers, 4);
+        assert!(config.metrics_token.is_none());
+    }
+
+    #[test]
+    fn config_file_fills_in_and_environment_wins() {
+        let path = write_config_file(
+            "override",
+            r#"
+            database_url = "postgres://file/db"
+            jwt_secret = "from-file"
+            job_workers = 8
+            token_lifetime_days = 7
+            storage_backend = "s3"
+            s3_bucket = "attachments"
+            "#,
+        );
+        let config = Config::from_env(env(&[
+            ("CONFIG_FILE", &path),
+            ("JWT_SECRET", "from-env"),
+            ("JOB_WORKERS", "2"),
+        ]))
+        .unwrap();
+
+        assert_eq!(config.database_url, "postgres://file/db");
+        assert_eq!(config.jwt_secret, "from-env");
+        assert_eq!(config.job_workers, 2);
+        assert_eq!(config.token_lifetime_days, 7);
+        assert!(matches!(
+            config.storage,
+            StorageConfig::S3 { ref bucket, ref region, .. }
+                if bucket == "attachments" && region == "us-east-1"
+        ));
+    }
+
+    #[test]
+    fn unreadable_config_files_are_reported() {
+        let path = write_config_file("invalid", "job_workers = [");
+        let errors = Config::from_env(env(&[("CONFIG_FILE", &path)]))
+            .unwrap_err()
+            .0;
+        assert!(errors.iter().any(|error| error.starts_with(&path)));
+        // The settings the file would have had are still checked
+        assert!(
+            errors
+                .iter()
+                .any(|error| error == "DATABASE_URL must be set")
+        );
+
+        let missing = std::env::temp_dir().join("backend-config-test-missing.toml");
+        let missing = missing.to_string_lossy();
+        let mut vars = required();
+        vars.push(("CONFIG_FILE", &missing));
+        let errors = Config::from_env(env(&vars)).unwrap_err().0;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with(missing.as_ref()));
+    }
+}
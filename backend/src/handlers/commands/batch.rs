@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::State,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use sqlx::{Connection, PgConnection};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    handlers::{change_expense, insert_expense, trash_expense},
+    models::ExpenseModel,
+    schema::{
+        ApiResponse, ApiResult, BatchExpenseOperationSchema, BatchExpenseSchema,
+        BulkUpdateExpensesSchema, CreateExpenseSchema, UpdateExpenseSchema,
+    },
+    utils::{audit::begin_audited, budget::enqueue_budget_alert, expense::find_matching_expenses},
+};
+
+/// Most operations a single batch can carry.
+const MAX_BATCH_OPERATIONS: usize = 500;
+
+/// Most expenses a bulk update can change at once.
+const MAX_BULK_EXPENSES: i64 = 2000;
+
+/// Runs up to 500 creates, updates and deletes of expenses in one transaction,
+/// with the same validation as the single-expense endpoints. Every operation
+/// gets a result in the usual response envelope, in order. By default the
+/// batch is all-or-nothing: when an operation fails nothing is committed.
+/// With `atomic: false` the failed operations are rolled back on their own and
+/// the rest is committed.
+///
+/// Validation runs in the batch's transaction, so an operation sees what the
+/// operations before it did, e.g. it can refund an expense created earlier in
+/// the batch, and refunds across the batch can't add up to more than the
+/// original.
+#[utoipa::path(
+    post,
+    path = "/expense/batch",
+    tag = "expense",
+    request_body = BatchExpenseSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `committed`, `succeeded`, `failed`, `results`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn batch_expenses(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<BatchExpenseSchema>,
+) -> ApiResult<serde_json::Value> {
+    if body.operations.is_empty() {
+        return Err(ApiResponse::error(
+            "A batch needs at least one operation",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if body.operations.len() > MAX_BATCH_OPERATIONS {
+        return Err(ApiResponse::error(
+            &format!(
+                "A batch can carry at most {} operations",
+                MAX_BATCH_OPERATIONS
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let atomic = body.atomic.unwrap_or(true);
+    let total = body.operations.len();
+    let mut results = Vec::with_capacity(total);
+    let mut failed = 0;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    for (index, operation) in body.operations.into_iter().enumerate() {
+        // Each operation gets a savepoint, so a failed one leaves the
+        // transaction usable for the rest.
+        let mut savepoint = tx.begin().await?;
+        let op = operation.op.clone();
+
+        let mut result = match apply_batch_operation(&mut savepoint, user_id, operation).await {
+            Ok(expense) => {
+                if op == "create"
+                    && let Some(expense) = &expense
+                {
+                    enqueue_budget_alert(&mut savepoint, user_id, expense).await?;
+                }
+                savepoint.commit().await?;
+                let response = match expense {
+                    Some(expense) => ApiResponse::success(json!({ "expense": expense })),
+                    None => ApiResponse::success(json!({
+                        "message": "Expense moved to trash"
+                    })),
+                };
+                json!(response)
+            }
+            Err(err) => {
+                savepoint.rollback().await?;
+                failed += 1;
+                json!(err)
+            }
+        };
+
+        result["index"] = json!(index);
+        result["op"] = json!(op);
+        results.push(result);
+    }
+
+    let committed = !atomic || failed == 0;
+    if committed {
+        tx.commit().await?;
+    } else {
+        tx.rollback().await?;
+    }
+
+    Ok(ApiResponse::success(json!({
+        "committed": committed,
+        "succeeded": if committed { total - failed } else { 0 },
+        "failed": failed,
+        "results": results
+    })))
+}
+
+/// Applies one batch operation, returning the created or updated expense.
+async fn apply_batch_operation(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    operation: BatchExpenseOperationSchema,
+) -> Result<Option<ExpenseModel>, ApiResponse<serde_json::Value>> {
+    let mut headers = HeaderMap::new();
+    if let Some(ref if_match) = operation.if_match {
+        let value = HeaderValue::from_str(if_match)
+            .map_err(|_| ApiResponse::error("Invalid ifMatch", StatusCode::BAD_REQUEST))?;
+        headers.insert(header::IF_MATCH, value);
+    }
+
+    let expense_id = match &operation.id {
+        Some(id) => Some(Uuid::parse_str(id).map_err(|_| {
+            ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST)
+        })?),
+        None => None,
+    };
+
+    match (operation.op.as_str(), expense_id) {
+        ("create", _) => {
+            let body: CreateExpenseSchema = parse_operation_data(operation.data)?;
+            Ok(Some(insert_expense(conn, user_id, body).await?))
+        }
+        ("update", Some(expense_id)) => {
+            let body: UpdateExpenseSchema = parse_operation_data(operation.data)?;
+            Ok(Some(
+                change_expense(conn, user_id, expense_id, body, &headers).await?,
+            ))
+        }
+        ("delete", Some(expense_id)) => {
+            trash_expense(conn, user_id, expense_id, &headers).await?;
+            Ok(None)
+        }
+        ("update" | "delete", None) => Err(ApiResponse::error(
+            "Updates and deletes need the id of the expense",
+            StatusCode::BAD_REQUEST,
+        )),
+        _ => Err(ApiResponse::error(
+            "op must be one of 'create', 'update' or 'delete'",
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+fn parse_operation_data<T: DeserializeOwned>(
+    data: Option<serde_json::Value>,
+) -> Result<T, ApiResponse<serde_json::Value>> {
+    let data = data.ok_or_else(|| {
+        ApiResponse::error("Creates and updates need data", StatusCode::BAD_REQUEST)
+    })?;
+    serde_json::from_value(data).map_err(|err| ApiResponse::from(err.to_string()))
+}
+
+/// Applies the same update, e.g. a new category or budget, to every expense
+/// matching a filter, validating each like `PUT /expense/{id}`. All or
+/// nothing: the first expense that can't take the update fails the request.
+#[utoipa::path(
+    post,
+    path = "/expense/bulk-update",
+    tag = "expense",
+    request_body = BulkUpdateExpensesSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `updated`, `expenseIds`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn bulk_update_expenses(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<BulkUpdateExpensesSchema>,
+) -> ApiResult<serde_json::Value> {
+    let expense_ids =
+        find_matching_expenses(&state.db, user_id, &body.filter, MAX_BULK_EXPENSES + 1).await?;
+
+    if expense_ids.len() as i64 > MAX_BULK_EXPENSES {
+        return Err(ApiResponse::error(
+            &format!(
+                "More than {} expenses match, narrow the filter",
+                MAX_BULK_EXPENSES
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let headers = HeaderMap::new();
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    for expense_id in &expense_ids {
+        change_expense(&mut tx, user_id, *expense_id, body.set.clone(), &headers)
+            .await
+            .map_err(|err| {
+                ApiResponse::error(
+                    &format!(
+                        "Expense {}: {}",
+                        expense_id,
+                        err.message.unwrap_or_default()
+                    ),
+                    StatusCode::from_u16(err.status).unwrap_or(StatusCode::BAD_REQUEST),
+                )
+            })?;
+    }
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "updated": expense_ids.len(),
+        "expenseIds": expense_ids
+    })))
+}
@@ -1,21 +1,76 @@
 use crate::{
     AppState,
-    models::{BudgetModel, CategoryModel, ExpenseModel, UserModel},
+    metrics::record_notifications,
+    models::{
+        AccountModel, AttachmentModel, BillModel, BudgetModel, CategoryModel, ContactModel,
+        ExchangeRateModel, ExpenseModel, ExpenseRuleModel, GoalContributionModel,
+        HouseholdInvitationModel, Money, ReconciliationModel, SettlementModel, TagModel,
+        TransferModel, UserModel,
+    },
     schema::{
-        ApiResponse, ApiResult, CreateBudgetSchema, CreateCategorySchema, CreateExpenseSchema,
-        CreateUserSchema,
+        ApiResponse, ApiResult, CreateAccountSchema, CreateBillSchema, CreateBudgetSchema,
+        CreateCategorySchema, CreateContactSchema, CreateContributionSchema,
+        CreateExchangeRateSchema, CreateExpenseSchema, CreateGoalSchema, CreateHouseholdSchema,
+        CreateRuleSchema, CreateSettlementSchema, CreateTagSchema, CreateTransferSchema,
+        CreateUserSchema, InviteMemberSchema, PayBillSchema, ReconcileAccountSchema,
     },
+    storage::delete_keys,
     utils::{
+        account::{get_account, validate_account_type},
+        attachment::{check_attachment_quota, generate_thumbnail, validate_attachment},
+        audit::begin_audited,
+        bill::{
+            BILL_COLUMNS, get_bill, record_bill_payment, validate_recurrence, validate_remind_days,
+        },
+        budget::{enqueue_budget_alert, get_workspace_budget},
+        category::{
+            get_workspace_category, validate_category_name, validate_category_parent,
+            validate_category_style,
+        },
+        currency::{
+            DEFAULT_CURRENCY, EXCHANGE_RATE_COLUMNS, convert_to_base_currency,
+            get_user_base_currency, parse_amount, validate_currency, validate_exchange_rate,
+        },
+        expense::{
+            fetch_expense, get_refundable_expense, validate_refund, validate_transaction_type,
+        },
+        goal::{get_goal, sync_goal_milestone, validate_goal_account},
         hash_password,
-        helper::{user_exists, validate_email, validate_name, validate_password},
+        helper::{
+            ensure_id_available, user_exists, validate_email, validate_name, validate_password,
+        },
+        household::{
+            get_household, require_edit_access, require_household_role, resolve_target_household,
+            validate_household_role, validate_paid_by,
+        },
+        rule::{find_matching_rule, validate_rule_actions, validate_rule_conditions},
         sign,
+        split::resolve_split_party,
+        tag::{normalize_tag, normalize_tags, set_expense_tags},
     },
 };
-use axum::{Extension, Json, extract::State, http::StatusCode};
+use axum::{
+    Extension, Json,
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+};
 use serde_json::json;
+use sqlx::PgConnection;
 use std::sync::Arc;
 use uuid::Uuid;
 
+#[utoipa::path(
+    post,
+    path = "/user",
+    tag = "user",
+    summary = "Create user",
+    request_body = CreateUserSchema,
+    security(()),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn create_user(
     State(state): State<Arc<AppState>>,
     Json(body): Json<CreateUserSchema>,
@@ -31,118 +86,239 @@ pub async fn create_user(
         ));
     }
 
+    let base_currency = match &body.base_currency {
+        Some(currency) => validate_currency(currency)?,
+        None => DEFAULT_CURRENCY.to_string(),
+    };
+
     let password_hash = hash_password(&body.password)
-        .map_err(|e| ApiResponse::error(&e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+        .map_err(|e| ApiResponse::error(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
     let new_user = sqlx::query_as::<_, UserModel>(
-        "INSERT INTO users (name, email, password_hash) VALUES ($1, $2, $3) RETURNING *",
+        "INSERT INTO users (name, email, password_hash, base_currency) VALUES ($1, $2, $3, $4) RETURNING *",
     )
     .bind(body.name)
     .bind(body.email)
     .bind(password_hash)
+    .bind(base_currency)
     .fetch_one(&state.db)
     .await?;
 
-    let token = sign(&new_user.user_id.to_string())
-        .map_err(|e| ApiResponse::error(&e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+    let token = sign(
+        &new_user.user_id.to_string(),
+        &state.config.jwt_secret,
+        state.config.token_lifetime_days,
+    )
+    .map_err(|e| ApiResponse::error(&e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
 
     Ok(ApiResponse::success(
         json!({"user": new_user, "token": token}),
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/expense",
+    tag = "expense",
+    summary = "Create expense",
+    request_body = CreateExpenseSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `expense`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn create_expense(
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
     Json(body): Json<CreateExpenseSchema>,
 ) -> ApiResult<serde_json::Value> {
+    let mut tx = begin_audited(&state.db, user_id).await?;
+    let new_expense = insert_expense(&mut tx, user_id, body).await?;
+    enqueue_budget_alert(&mut tx, user_id, &new_expense).await?;
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "expense": new_expense
+    })))
+}
+
+/// Validates and inserts a new expense in `conn`, a transaction started with
+/// `begin_audited`. Validation reads go through `conn` too, so they see what
+/// the transaction has written so far.
+pub async fn insert_expense(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    body: CreateExpenseSchema,
+) -> Result<ExpenseModel, ApiResponse<serde_json::Value>> {
     validate_name(&body.name)?;
-    if body.amount <= 0 {
+
+    let expense_id = match &body.expense_id {
+        Some(expense_id) => {
+            let expense_id = Uuid::parse_str(expense_id)?;
+            ensure_id_available(
+                &mut *conn,
+                "expenses",
+                "expense_id",
+                expense_id,
+                "An expense",
+            )
+            .await?;
+            expense_id
+        }
+        None => Uuid::new_v4(),
+    };
+
+    let mut budget_id = match &body.budget_id {
+        Some(b) => Some(Uuid::parse_str(b)?),
+        None => None,
+    };
+
+    let transaction_type = match &body.transaction_type {
+        Some(transaction_type) => validate_transaction_type(transaction_type)?,
+        None => "expense".to_string(),
+    };
+
+    let original = match (transaction_type.as_str(), &body.original_expense_id) {
+        ("refund", Some(original_id)) => {
+            Some(get_refundable_expense(&mut *conn, user_id, Uuid::parse_str(original_id)?).await?)
+        }
+        ("refund", None) => {
+            return Err(ApiResponse::error(
+                "A refund needs the originalExpenseId it refunds",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        (_, Some(_)) => {
+            return Err(ApiResponse::error(
+                "Only refunds can have an originalExpenseId",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        _ => None,
+    };
+
+    if transaction_type == "income" && budget_id.is_some() {
         return Err(ApiResponse::error(
-            "Amount can't be less than zero",
+            "Income can't be linked to a budget",
             StatusCode::BAD_REQUEST,
         ));
     }
 
-    let budget_id = match &body.budget_id {
-        Some(b) => Some(Uuid::parse_str(&b)?),
+    // Refunds live in the same workspace as the expense they refund
+    let household_id = match &original {
+        Some(original) => {
+            require_edit_access(&mut *conn, user_id, original.household_id).await?;
+            original.household_id
+        }
+        None => resolve_target_household(&mut *conn, user_id, body.household_id.as_deref()).await?,
+    };
+
+    let paid_by = match &body.paid_by {
+        Some(paid_by) => {
+            let paid_by = Uuid::parse_str(paid_by)?;
+            validate_paid_by(&mut *conn, user_id, household_id, paid_by).await?;
+            paid_by
+        }
+        None => user_id,
+    };
+
+    if let Some(category_id) = body.category_id {
+        get_workspace_category(&mut *conn, user_id, household_id, category_id).await?;
+    }
+
+    if let Some(budget_id) = budget_id {
+        get_workspace_budget(&mut *conn, user_id, household_id, budget_id).await?;
+    }
+
+    let account_id = match &body.account_id {
+        Some(account_id) => Some(
+            get_account(&mut *conn, user_id, Uuid::parse_str(account_id)?)
+                .await?
+                .account_id,
+        ),
         None => None,
     };
 
-    let new_expense = sqlx::query_as::<_, ExpenseModel>(
-        "INSERT INTO expenses (name, amount, date, description, category_id, user_id, budget_id) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *"
+    let currency = match (&body.currency, &original) {
+        (Some(currency), _) => validate_currency(currency)?,
+        (None, Some(original)) => original.currency.clone(),
+        (None, None) => get_user_base_currency(&mut *conn, user_id).await?,
+    };
+
+    let amount = parse_amount(&body.amount, &currency)?;
+    if !amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let mut tags = normalize_tags(body.tags.as_deref().unwrap_or_default())?;
+    let mut category_id = body.category_id;
+
+    // Refunds count against the same category and budget as the expense they
+    // refund, unless given explicitly.
+    if let Some(ref original) = original {
+        validate_refund(&mut *conn, original, amount, &currency, None).await?;
+        category_id = category_id.or(original.category_id);
+        budget_id = budget_id.or(original.budget_id);
+    }
+
+    // Expenses created without a category go through the user's rules. Rules
+    // are personal, so shared expenses are left alone. Their amount bounds are
+    // in the user's base currency.
+    if transaction_type == "expense" && household_id.is_none() && category_id.is_none() {
+        let base_amount =
+            convert_to_base_currency(&mut *conn, user_id, amount, &currency, body.date).await?;
+        if let Some(rule) = find_matching_rule(&mut *conn, user_id, &body.name, base_amount).await?
+        {
+            category_id = rule.category_id;
+            budget_id = budget_id.or(rule.budget_id);
+            for tag in rule.tags {
+                if !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+    }
+
+    sqlx::query(
+        "INSERT INTO expenses (expense_id, name, amount, currency, date, description, category_id, user_id, budget_id, transaction_type, original_expense_id, account_id, household_id, paid_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)"
     )
+    .bind(expense_id)
     .bind(body.name)
-    .bind(body.amount)
+    .bind(amount)
+    .bind(currency)
     .bind(body.date)
     .bind(body.description)
-    .bind(body.category_id)
-    .bind(&user_id)
-    .bind(&budget_id)
-    .fetch_one(&state.db)
+    .bind(category_id)
+    .bind(user_id)
+    .bind(budget_id)
+    .bind(&transaction_type)
+    .bind(original.as_ref().map(|o| o.expense_id))
+    .bind(account_id)
+    .bind(household_id)
+    .bind(paid_by)
+    .execute(&mut *conn)
     .await?;
 
-    if let Some(budget_id) = budget_id {
-        let db_state = state.db.clone();
-        tokio::spawn(async move {
-            let budget_result = sqlx::query_as::<_, BudgetModel>(
-                "SELECT * FROM budgets WHERE budget_id = $1 AND user_id = $2",
-            )
-            .bind(budget_id)
-            .bind(user_id)
-            .fetch_optional(&db_state)
-            .await;
-
-            if let Ok(Some(budget)) = budget_result {
-                let total_spent_result: Result<Option<i64>, _> = sqlx::query_scalar(
-                    "SELECT COALESCE(SUM(amount), 0)::BIGINT FROM expenses WHERE budget_id = $1",
-                )
-                .bind(budget_id)
-                .fetch_one(&db_state)
-                .await;
-
-                if let Ok(Some(total_spent)) = total_spent_result {
-                    let budget_amount = budget.amount;
-                    let usage_percentage = (total_spent as f64 / budget_amount as f64) * 100.0;
-
-                    let msg = if usage_percentage >= 100.0 {
-                        Some(format!(
-                            "🚨 BUDGET ALERT: Budget '{}' (ID: {}) has been EXCEEDED! \
-                            Total spent: {} / Budget: {} ({:.1}%)",
-                            budget.name, budget_id, total_spent, budget_amount, usage_percentage
-                        ))
-                    } else if usage_percentage >= 80.0 {
-                        Some(format!(
-                            "⚠️  BUDGET WARNING: Budget '{}' (ID: {}) is at {:.1}% usage. \
-                            Total spent: {} / Budget: {}",
-                            budget.name, budget_id, usage_percentage, total_spent, budget_amount
-                        ))
-                    } else {
-                        None
-                    };
-
-                    if msg.is_some() {
-                        let _ = sqlx::query(
-                            "INSERT INTO notifications (user_id, category, message) VALUES ($1, $2, $3)"
-                        )
-                        .bind(&user_id)
-                        .bind("BUDGET_ALERT")
-                        .bind(msg)
-                        .execute(&db_state)
-                        .await;
-                    }
-                } else {
-                    eprintln!("Failed to calculate total spent for budget {}", budget_id);
-                }
-            }
-        });
-    }
+    set_expense_tags(&mut *conn, user_id, expense_id, &tags).await?;
 
-    Ok(ApiResponse::success(json!({
-        "expense": new_expense
-    })))
+    Ok(fetch_expense(conn, expense_id).await?)
 }
 
+#[utoipa::path(
+    post,
+    path = "/budget",
+    tag = "budget",
+    summary = "Create budget",
+    request_body = CreateBudgetSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `budget`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn create_budget(
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
@@ -150,53 +326,95 @@ pub async fn create_budget(
 ) -> ApiResult<serde_json::Value> {
     validate_name(&body.name)?;
 
-    if body.amount <= 0 {
+    if body.end_date <= body.start_date {
         return Err(ApiResponse::error(
-            "Amount can't be less than zero",
+            "End date must be after start date",
             StatusCode::BAD_REQUEST,
         ));
     }
 
-    if body.end_date <= body.start_date {
+    let budget_id = match &body.budget_id {
+        Some(budget_id) => {
+            let budget_id = Uuid::parse_str(budget_id)?;
+            ensure_id_available(&state.db, "budgets", "budget_id", budget_id, "A budget").await?;
+            budget_id
+        }
+        None => Uuid::new_v4(),
+    };
+
+    let household_id =
+        resolve_target_household(&state.db, user_id, body.household_id.as_deref()).await?;
+
+    if let Some(category_id) = body.category_id {
+        get_workspace_category(&state.db, user_id, household_id, category_id).await?;
+    }
+
+    let currency = match &body.currency {
+        Some(currency) => validate_currency(currency)?,
+        None => get_user_base_currency(&state.db, user_id).await?,
+    };
+
+    let amount = parse_amount(&body.amount, &currency)?;
+    if !amount.is_positive() {
         return Err(ApiResponse::error(
-            "End date must be after start date",
+            "Amount can't be less than zero",
             StatusCode::BAD_REQUEST,
         ));
     }
 
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
     let new_budget = sqlx::query_as::<_, BudgetModel>(
-        "INSERT INTO budgets (name, amount, start_date, end_date, user_id) VALUES ($1, $2, $3, $4, $5) RETURNING *"
+        "INSERT INTO budgets (budget_id, name, amount, currency, start_date, end_date, user_id, category_id, household_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING *"
     )
+    .bind(budget_id)
     .bind(body.name)
-    .bind(body.amount)
+    .bind(amount)
+    .bind(currency)
     .bind(body.start_date)
     .bind(body.end_date)
     .bind(user_id)
-    .fetch_one(&state.db)
+    .bind(body.category_id)
+    .bind(household_id)
+    .fetch_one(&mut *tx)
     .await?;
 
+    tx.commit().await?;
+
     Ok(ApiResponse::success(json!({
         "budget": new_budget
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/category",
+    tag = "category",
+    summary = "Create category",
+    request_body = CreateCategorySchema,
+    responses(
+        (status = 200, description = "Success; `data` has `category`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn create_category(
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
     Json(body): Json<CreateCategorySchema>,
 ) -> ApiResult<serde_json::Value> {
-    if body.category_name.trim().is_empty() {
-        return Err(ApiResponse::error(
-            "Category name cannot be empty",
-            StatusCode::BAD_REQUEST,
-        ));
-    }
+    validate_category_name(&body.category_name)?;
+    validate_category_style(body.icon.as_deref(), body.color.as_deref())?;
+
+    let household_id =
+        resolve_target_household(&state.db, user_id, body.household_id.as_deref()).await?;
 
     let existing_category = sqlx::query_as::<_, CategoryModel>(
-        "SELECT * FROM categories WHERE user_id = $1 AND category_name = $2",
+        "SELECT * FROM categories
+         WHERE in_workspace(user_id, household_id, $1, $3) AND category_name = $2 AND deleted_at IS NULL",
     )
     .bind(user_id)
     .bind(&body.category_name)
+    .bind(household_id)
     .fetch_optional(&state.db)
     .await?;
 
@@ -207,15 +425,1149 @@ pub async fn create_category(
         ));
     }
 
+    if let Some(parent_id) = body.parent_id {
+        validate_category_parent(&state.db, user_id, household_id, None, parent_id).await?;
+    }
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
     let new_category = sqlx::query_as::<_, CategoryModel>(
-        "INSERT INTO categories (category_name, user_id) VALUES ($1, $2) RETURNING *",
+        "INSERT INTO categories (category_name, user_id, parent_id, icon, color, household_id) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
     )
     .bind(body.category_name)
     .bind(user_id)
-    .fetch_one(&state.db)
+    .bind(body.parent_id)
+    .bind(body.icon)
+    .bind(body.color)
+    .bind(household_id)
+    .fetch_one(&mut *tx)
     .await?;
 
+    tx.commit().await?;
+
     Ok(ApiResponse::success(json!({
         "category": new_category
     })))
 }
+
+#[utoipa::path(
+    post,
+    path = "/tag",
+    tag = "tag",
+    summary = "Create tag",
+    request_body = CreateTagSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `tag`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_tag(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateTagSchema>,
+) -> ApiResult<serde_json::Value> {
+    let name = normalize_tag(&body.name)?;
+
+    let new_tag = sqlx::query_as::<_, TagModel>(
+        "INSERT INTO tags (user_id, name) VALUES ($1, $2)
+         ON CONFLICT (user_id, name) DO NOTHING RETURNING *",
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match new_tag {
+        Some(tag) => Ok(ApiResponse::success(json!({
+            "tag": tag
+        }))),
+        None => Err(ApiResponse::error(
+            "Tag with this name already exists",
+            StatusCode::CONFLICT,
+        )),
+    }
+}
+
+/// Adds a manual exchange rate for the user, replacing their rate for the same
+/// pair and date. Manual rates take precedence over loaded reference rates.
+#[utoipa::path(
+    post,
+    path = "/exchange-rate",
+    tag = "exchange_rate",
+    request_body = CreateExchangeRateSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `exchangeRate`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_exchange_rate(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateExchangeRateSchema>,
+) -> ApiResult<serde_json::Value> {
+    let base_currency = validate_currency(&body.base_currency)?;
+    let quote_currency = validate_currency(&body.quote_currency)?;
+
+    if base_currency == quote_currency {
+        return Err(ApiResponse::error(
+            "Base and quote currency must be different",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    validate_exchange_rate(&body.rate)?;
+
+    let exchange_rate = sqlx::query_as::<_, ExchangeRateModel>(&format!(
+        "INSERT INTO exchange_rates (user_id, base_currency, quote_currency, rate, rate_date, source)
+         VALUES ($1, $2, $3, $4::NUMERIC, $5, 'manual')
+         ON CONFLICT (user_id, base_currency, quote_currency, rate_date) WHERE user_id IS NOT NULL
+         DO UPDATE SET rate = EXCLUDED.rate
+         RETURNING {}",
+        EXCHANGE_RATE_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(base_currency)
+    .bind(quote_currency)
+    .bind(body.rate.trim())
+    .bind(body.rate_date)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "exchangeRate": exchange_rate
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/account",
+    tag = "account",
+    summary = "Create account",
+    request_body = CreateAccountSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `account`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_account(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateAccountSchema>,
+) -> ApiResult<serde_json::Value> {
+    validate_name(&body.name)?;
+    let account_type = validate_account_type(&body.account_type)?;
+
+    let currency = match &body.currency {
+        Some(currency) => validate_currency(currency)?,
+        None => get_user_base_currency(&state.db, user_id).await?,
+    };
+
+    let opening_balance = match &body.opening_balance {
+        Some(amount) => parse_amount(amount, &currency)?,
+        None => Money::ZERO,
+    };
+
+    let new_account = sqlx::query_as::<_, AccountModel>(
+        "INSERT INTO accounts (user_id, name, account_type, currency, opening_balance, opening_date)
+         VALUES ($1, $2, $3, $4, $5, COALESCE($6, CURRENT_DATE))
+         ON CONFLICT (user_id, name) DO NOTHING
+         RETURNING *, opening_balance AS balance",
+    )
+    .bind(user_id)
+    .bind(body.name)
+    .bind(account_type)
+    .bind(currency)
+    .bind(opening_balance)
+    .bind(body.opening_date)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match new_account {
+        Some(account) => Ok(ApiResponse::success(json!({
+            "account": account
+        }))),
+        None => Err(ApiResponse::error(
+            "Account with this name already exists",
+            StatusCode::CONFLICT,
+        )),
+    }
+}
+
+/// Moves money between two of the user's accounts. Between currencies the
+/// destination amount is taken from the request or converted at the transfer date.
+#[utoipa::path(
+    post,
+    path = "/transfer",
+    tag = "account",
+    request_body = CreateTransferSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `transfer`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_transfer(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateTransferSchema>,
+) -> ApiResult<serde_json::Value> {
+    let from_account =
+        get_account(&state.db, user_id, Uuid::parse_str(&body.from_account_id)?).await?;
+    let to_account = get_account(&state.db, user_id, Uuid::parse_str(&body.to_account_id)?).await?;
+
+    if from_account.account_id == to_account.account_id {
+        return Err(ApiResponse::error(
+            "Can't transfer to the same account",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let amount = parse_amount(&body.amount, &from_account.currency)?;
+    let to_amount = match &body.to_amount {
+        Some(to_amount) => parse_amount(to_amount, &to_account.currency)?,
+        None if from_account.currency == to_account.currency => amount,
+        None => sqlx::query_scalar::<_, Option<Money>>("SELECT convert_amount($1, $2, $3, $4, $5)")
+            .bind(amount)
+            .bind(&from_account.currency)
+            .bind(&to_account.currency)
+            .bind(body.date)
+            .bind(user_id)
+            .fetch_one(&state.db)
+            .await?
+            .ok_or_else(|| {
+                ApiResponse::error(
+                    &format!(
+                        "No exchange rate from {} to {}, send toAmount",
+                        from_account.currency, to_account.currency
+                    ),
+                    StatusCode::BAD_REQUEST,
+                )
+            })?,
+    };
+
+    if !amount.is_positive() || !to_amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let new_transfer = sqlx::query_as::<_, TransferModel>(
+        "INSERT INTO transfers (user_id, from_account_id, to_account_id, amount, to_amount, date, description)
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+    )
+    .bind(user_id)
+    .bind(from_account.account_id)
+    .bind(to_account.account_id)
+    .bind(amount)
+    .bind(to_amount)
+    .bind(body.date)
+    .bind(body.description)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "transfer": new_transfer
+    })))
+}
+
+/// Records a statement balance for an account and compares it with the balance
+/// computed from its transactions up to and including the statement date.
+#[utoipa::path(
+    post,
+    path = "/account/{id}/reconcile",
+    tag = "account",
+    params(("id" = String, Path, description = "Account id")),
+    request_body = ReconcileAccountSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `reconciliation`, `reconciled`, `currency`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn reconcile_account(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ReconcileAccountSchema>,
+) -> ApiResult<serde_json::Value> {
+    let account_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid account ID format", StatusCode::BAD_REQUEST))?;
+    let account = get_account(&state.db, user_id, account_id).await?;
+    let statement_balance = parse_amount(&body.statement_balance, &account.currency)?;
+
+    let reconciliation = sqlx::query_as::<_, ReconciliationModel>(
+        "INSERT INTO account_reconciliations (account_id, statement_date, statement_balance, computed_balance)
+         VALUES ($1, $2, $3, account_balance($1, $2))
+         RETURNING *, statement_balance - computed_balance AS difference",
+    )
+    .bind(account.account_id)
+    .bind(body.statement_date)
+    .bind(statement_balance)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "reconciliation": reconciliation,
+        "reconciled": reconciliation.difference == Money::ZERO,
+        "currency": account.currency
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/contact",
+    tag = "split",
+    summary = "Create contact",
+    request_body = CreateContactSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `contact`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_contact(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateContactSchema>,
+) -> ApiResult<serde_json::Value> {
+    validate_name(&body.name)?;
+    if let Some(ref email) = body.email {
+        validate_email(email)?;
+    }
+
+    let new_contact = sqlx::query_as::<_, ContactModel>(
+        "INSERT INTO contacts (user_id, name, email) VALUES ($1, $2, $3)
+         ON CONFLICT (user_id, name) DO NOTHING
+         RETURNING *",
+    )
+    .bind(user_id)
+    .bind(body.name)
+    .bind(body.email.map(|email| email.trim().to_lowercase()))
+    .fetch_optional(&state.db)
+    .await?;
+
+    match new_contact {
+        Some(contact) => Ok(ApiResponse::success(json!({
+            "contact": contact
+        }))),
+        None => Err(ApiResponse::error(
+            "Contact with this name already exists",
+            StatusCode::CONFLICT,
+        )),
+    }
+}
+
+/// Records a payment from one person to another that settles (part of) what
+/// they owe. In a household both sides must be members or their contacts; for
+/// personal balances one side must be the current user.
+#[utoipa::path(
+    post,
+    path = "/settlement",
+    tag = "split",
+    request_body = CreateSettlementSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `settlement`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_settlement(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateSettlementSchema>,
+) -> ApiResult<serde_json::Value> {
+    let household_id =
+        resolve_target_household(&state.db, user_id, body.household_id.as_deref()).await?;
+
+    let (from_user_id, from_contact_id) = resolve_split_party(
+        &state.db,
+        user_id,
+        household_id,
+        body.from_user_id.as_deref(),
+        body.from_contact_id.as_deref(),
+    )
+    .await?;
+    let (to_user_id, to_contact_id) = resolve_split_party(
+        &state.db,
+        user_id,
+        household_id,
+        body.to_user_id.as_deref(),
+        body.to_contact_id.as_deref(),
+    )
+    .await?;
+
+    if (from_user_id, from_contact_id) == (to_user_id, to_contact_id) {
+        return Err(ApiResponse::error(
+            "Can't settle up with yourself",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if household_id.is_none() && from_user_id != Some(user_id) && to_user_id != Some(user_id) {
+        return Err(ApiResponse::error(
+            "You must be one side of a personal settlement",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let currency = match &body.currency {
+        Some(currency) => validate_currency(currency)?,
+        None => get_user_base_currency(&state.db, user_id).await?,
+    };
+
+    let amount = parse_amount(&body.amount, &currency)?;
+    if !amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let new_settlement = sqlx::query_as::<_, SettlementModel>(
+        "INSERT INTO settlements (user_id, household_id, from_user_id, from_contact_id, to_user_id,
+            to_contact_id, amount, currency, date, note)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, COALESCE($9, CURRENT_DATE), $10)
+         RETURNING *",
+    )
+    .bind(user_id)
+    .bind(household_id)
+    .bind(from_user_id)
+    .bind(from_contact_id)
+    .bind(to_user_id)
+    .bind(to_contact_id)
+    .bind(amount)
+    .bind(currency)
+    .bind(body.date)
+    .bind(body.note)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "settlement": new_settlement
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/goal",
+    tag = "goal",
+    summary = "Create goal",
+    request_body = CreateGoalSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `goal`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_goal(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateGoalSchema>,
+) -> ApiResult<serde_json::Value> {
+    validate_name(&body.name)?;
+
+    let currency = match (&body.currency, &body.account_id) {
+        (Some(currency), _) => validate_currency(currency)?,
+        (None, Some(account_id)) => {
+            get_account(&state.db, user_id, Uuid::parse_str(account_id)?)
+                .await?
+                .currency
+        }
+        (None, None) => get_user_base_currency(&state.db, user_id).await?,
+    };
+
+    let account_id = match &body.account_id {
+        Some(account_id) => {
+            Some(validate_goal_account(&state.db, user_id, account_id, &currency).await?)
+        }
+        None => None,
+    };
+
+    let target_amount = parse_amount(&body.target_amount, &currency)?;
+    if !target_amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Target amount must be greater than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let goal_id: Option<Uuid> = sqlx::query_scalar(
+        "INSERT INTO savings_goals (user_id, name, target_amount, currency, target_date, account_id)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (user_id, name) DO NOTHING
+         RETURNING goal_id",
+    )
+    .bind(user_id)
+    .bind(body.name)
+    .bind(target_amount)
+    .bind(currency)
+    .bind(body.target_date)
+    .bind(account_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(goal_id) = goal_id else {
+        return Err(ApiResponse::error(
+            "Savings goal with this name already exists",
+            StatusCode::CONFLICT,
+        ));
+    };
+
+    let mut conn = state.db.acquire().await?;
+    let goal = get_goal(&mut conn, user_id, goal_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "goal": goal
+    })))
+}
+
+/// Puts money towards a goal (or takes it out with a negative amount) and
+/// notifies the user when a new milestone is reached.
+#[utoipa::path(
+    post,
+    path = "/goal/{id}/contribution",
+    tag = "goal",
+    params(("id" = String, Path, description = "Goal id")),
+    request_body = CreateContributionSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `contribution`, `goal`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_goal_contribution(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateContributionSchema>,
+) -> ApiResult<serde_json::Value> {
+    let goal_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid goal ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = state.db.begin().await?;
+
+    // Lock the goal so concurrent contributions announce each milestone once
+    let locked: Option<String> = sqlx::query_scalar(
+        "SELECT currency FROM savings_goals WHERE goal_id = $1 AND user_id = $2 FOR UPDATE",
+    )
+    .bind(goal_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(currency) = locked else {
+        return Err(ApiResponse::error(
+            "Savings goal not found",
+            StatusCode::NOT_FOUND,
+        ));
+    };
+
+    let amount = parse_amount(&body.amount, &currency)?;
+    if amount == Money::ZERO {
+        return Err(ApiResponse::error(
+            "Amount can't be zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let contribution = sqlx::query_as::<_, GoalContributionModel>(
+        "INSERT INTO goal_contributions (goal_id, amount, date, note)
+         VALUES ($1, $2, COALESCE($3, CURRENT_DATE), $4)
+         RETURNING *",
+    )
+    .bind(goal_id)
+    .bind(amount)
+    .bind(body.date)
+    .bind(body.note)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let goal = get_goal(&mut tx, user_id, goal_id).await?;
+    if goal.saved_amount < Money::ZERO {
+        return Err(ApiResponse::error(
+            "Can't withdraw more than has been saved",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    sync_goal_milestone(&mut tx, &goal).await?;
+    let goal = get_goal(&mut tx, user_id, goal_id).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "contribution": contribution,
+        "goal": goal
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/bill",
+    tag = "bill",
+    summary = "Create bill",
+    request_body = CreateBillSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `bill`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_bill(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateBillSchema>,
+) -> ApiResult<serde_json::Value> {
+    validate_name(&body.name)?;
+
+    let currency = match &body.currency {
+        Some(currency) => validate_currency(currency)?,
+        None => get_user_base_currency(&state.db, user_id).await?,
+    };
+
+    let amount = parse_amount(&body.amount, &currency)?;
+    if !amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let recurrence = validate_recurrence(body.recurrence.as_deref().unwrap_or("none"))?;
+    let remind_days_before = body.remind_days_before.unwrap_or(3);
+    validate_remind_days(remind_days_before)?;
+
+    // Bills are personal, like the expenses they create
+    if let Some(category_id) = body.category_id {
+        get_workspace_category(&state.db, user_id, None, category_id).await?;
+    }
+
+    let account_id = match &body.account_id {
+        Some(account_id) => Some(
+            get_account(&state.db, user_id, Uuid::parse_str(account_id)?)
+                .await?
+                .account_id,
+        ),
+        None => None,
+    };
+
+    let bill_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO bills (user_id, name, amount, currency, start_date, due_date, recurrence, autopay,
+            remind_days_before, category_id, account_id)
+         VALUES ($1, $2, $3, $4, $5, $5, $6, $7, $8, $9, $10)
+         RETURNING bill_id",
+    )
+    .bind(user_id)
+    .bind(body.name)
+    .bind(amount)
+    .bind(currency)
+    .bind(body.due_date)
+    .bind(recurrence)
+    .bind(body.autopay.unwrap_or(false))
+    .bind(remind_days_before)
+    .bind(body.category_id)
+    .bind(account_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let new_bill = get_bill(&state.db, user_id, bill_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "bill": new_bill
+    })))
+}
+
+/// Marks the bill's current occurrence as paid: records the expense for it and
+/// moves the bill to its next due date.
+#[utoipa::path(
+    post,
+    path = "/bill/{id}/pay",
+    tag = "bill",
+    params(("id" = String, Path, description = "Bill id")),
+    request_body = PayBillSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `payment`, `expense`, `bill`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn pay_bill(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<PayBillSchema>,
+) -> ApiResult<serde_json::Value> {
+    let bill_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid bill ID format", StatusCode::BAD_REQUEST))?;
+
+    let account_id = match &body.account_id {
+        Some(account_id) => Some(
+            get_account(&state.db, user_id, Uuid::parse_str(account_id)?)
+                .await?
+                .account_id,
+        ),
+        None => None,
+    };
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    // Lock the bill so the same occurrence can't be paid twice
+    let bill = sqlx::query_as::<_, BillModel>(&format!(
+        "SELECT {} FROM bills b WHERE b.bill_id = $1 AND b.user_id = $2 FOR UPDATE",
+        BILL_COLUMNS
+    ))
+    .bind(bill_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Bill not found", StatusCode::NOT_FOUND))?;
+
+    if !bill.is_active {
+        return Err(ApiResponse::error(
+            "This bill has already been paid",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let amount = match &body.amount {
+        Some(amount) => parse_amount(amount, &bill.currency)?,
+        None => bill.amount,
+    };
+    if !amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let paid_on = body
+        .paid_on
+        .unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let payment = record_bill_payment(
+        &mut tx,
+        &bill,
+        amount,
+        paid_on,
+        account_id.or(bill.account_id),
+        false,
+    )
+    .await?;
+
+    let expense = match payment.expense_id {
+        Some(expense_id) => Some(fetch_expense(&mut tx, expense_id).await?),
+        None => None,
+    };
+    let updated_bill = sqlx::query_as::<_, BillModel>(&format!(
+        "SELECT {} FROM bills b WHERE b.bill_id = $1",
+        BILL_COLUMNS
+    ))
+    .bind(bill_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "payment": payment,
+        "expense": expense,
+        "bill": updated_bill
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/rule",
+    tag = "rule",
+    summary = "Create rule",
+    request_body = CreateRuleSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `rule`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_rule(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateRuleSchema>,
+) -> ApiResult<serde_json::Value> {
+    validate_name(&body.name)?;
+
+    // Amount limits are given in the user's base currency
+    let base_currency = get_user_base_currency(&state.db, user_id).await?;
+    let min_amount = match &body.min_amount {
+        Some(amount) => Some(parse_amount(amount, &base_currency)?),
+        None => None,
+    };
+    let max_amount = match &body.max_amount {
+        Some(amount) => Some(parse_amount(amount, &base_currency)?),
+        None => None,
+    };
+
+    validate_rule_conditions(
+        body.name_contains.as_deref(),
+        body.name_regex.as_deref(),
+        min_amount,
+        max_amount,
+    )?;
+
+    let budget_id = match &body.budget_id {
+        Some(b) => Some(Uuid::parse_str(b)?),
+        None => None,
+    };
+
+    let tags = normalize_tags(body.tags.as_deref().unwrap_or_default())?;
+
+    validate_rule_actions(&state.db, user_id, body.category_id, &tags, budget_id).await?;
+
+    let new_rule = sqlx::query_as::<_, ExpenseRuleModel>(
+        "INSERT INTO expense_rules (user_id, name, priority, is_active, name_contains, name_regex, min_amount, max_amount, category_id, tags, budget_id)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING *",
+    )
+    .bind(user_id)
+    .bind(body.name)
+    .bind(body.priority.unwrap_or(100))
+    .bind(body.is_active.unwrap_or(true))
+    .bind(body.name_contains)
+    .bind(body.name_regex)
+    .bind(min_amount)
+    .bind(max_amount)
+    .bind(body.category_id)
+    .bind(tags)
+    .bind(budget_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "rule": new_rule
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/expense/{id}/attachments",
+    tag = "expense",
+    summary = "Upload attachment",
+    params(("id" = String, Path, description = "Expense id")),
+    request_body(content_type = "multipart/form-data", description = "The file in a `file` field"),
+    responses(
+        (status = 200, description = "Success; `data` has `attachment`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn upload_attachment(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> ApiResult<serde_json::Value> {
+    let expense_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
+
+    let expense_exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(
+            SELECT 1 FROM expenses
+            WHERE expense_id = $1 AND deleted_at IS NULL AND can_edit_resource(user_id, household_id, $2)
+        )",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    if !expense_exists {
+        return Err(ApiResponse::error(
+            "Expense not found",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiResponse::error(&e.body_text(), e.status()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or("attachment").to_string();
+        let declared_type = field.content_type().map(|c| c.to_string());
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiResponse::error(&e.body_text(), e.status()))?;
+
+        upload = Some((file_name, declared_type, data));
+        break;
+    }
+
+    let (file_name, declared_type, data) = upload.ok_or_else(|| {
+        ApiResponse::error(
+            "Multipart field 'file' is required",
+            StatusCode::BAD_REQUEST,
+        )
+    })?;
+
+    let content_type = validate_attachment(declared_type.as_deref(), &data)?;
+
+    // Checked again when the row is inserted; this spares storing files that
+    // can't fit
+    let size_bytes = data.len() as i64;
+    check_attachment_quota(&state.db, user_id, size_bytes).await?;
+
+    let attachment_id = Uuid::new_v4();
+    let storage_key = format!("{}/{}/{}", user_id, expense_id, attachment_id);
+
+    let thumbnail_source = data.clone();
+    let thumbnail =
+        tokio::task::spawn_blocking(move || generate_thumbnail(content_type, &thumbnail_source))
+            .await
+            .map_err(|e| ApiResponse::error(&e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?
+            .map_err(|e| {
+                ApiResponse::error(
+                    &format!("Failed to read image: {}", e),
+                    StatusCode::BAD_REQUEST,
+                )
+            })?;
+
+    state
+        .storage
+        .put(&storage_key, data, content_type)
+        .await
+        .map_err(|e| ApiResponse::error(&e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    let mut stored_keys = vec![storage_key.clone()];
+    let thumbnail_key = match thumbnail {
+        Some(thumbnail) => {
+            let key = format!("{}_thumb", storage_key);
+            if let Err(e) = state
+                .storage
+                .put(&key, thumbnail.into(), "image/jpeg")
+                .await
+            {
+                delete_keys(state.storage.as_ref(), stored_keys).await;
+                return Err(ApiResponse::error(&e, StatusCode::INTERNAL_SERVER_ERROR));
+            }
+            stored_keys.push(key.clone());
+            Some(key)
+        }
+        None => None,
+    };
+
+    // The user's row stays locked while the quota is summed again, so
+    // concurrent uploads can't each see room for themselves and overshoot it
+    let new_attachment = async {
+        let mut tx = state.db.begin().await?;
+        sqlx::query("SELECT 1 FROM users WHERE user_id = $1 FOR UPDATE")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        check_attachment_quota(&mut *tx, user_id, size_bytes).await?;
+
+        let attachment = sqlx::query_as::<_, AttachmentModel>(
+            "INSERT INTO attachments (attachment_id, expense_id, user_id, file_name, content_type, size_bytes, storage_key, thumbnail_key)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *",
+        )
+        .bind(attachment_id)
+        .bind(expense_id)
+        .bind(user_id)
+        .bind(file_name)
+        .bind(content_type)
+        .bind(size_bytes)
+        .bind(&storage_key)
+        .bind(&thumbnail_key)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok::<_, ApiResponse<serde_json::Value>>(attachment)
+    }
+    .await;
+
+    match new_attachment {
+        Ok(attachment) => Ok(ApiResponse::success(json!({
+            "attachment": attachment
+        }))),
+        Err(err) => {
+            delete_keys(state.storage.as_ref(), stored_keys).await;
+            Err(err)
+        }
+    }
+}
+
+/// Creates a household with the current user as its first owner.
+#[utoipa::path(
+    post,
+    path = "/household",
+    tag = "household",
+    request_body = CreateHouseholdSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `household`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn create_household(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CreateHouseholdSchema>,
+) -> ApiResult<serde_json::Value> {
+    validate_name(&body.name)?;
+
+    let mut tx = state.db.begin().await?;
+
+    let household_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO households (name, created_by) VALUES ($1, $2) RETURNING household_id",
+    )
+    .bind(body.name.trim())
+    .bind(user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO household_members (household_id, user_id, role) VALUES ($1, $2, 'owner')",
+    )
+    .bind(household_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let household = get_household(&state.db, household_id, user_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "household": household
+    })))
+}
+
+/// Invites someone to a household by email. They don't need an account yet;
+/// registered users also get a notification.
+#[utoipa::path(
+    post,
+    path = "/household/{id}/invitation",
+    tag = "household",
+    params(("id" = String, Path, description = "Household id")),
+    request_body = InviteMemberSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `invitation`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn invite_member(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<InviteMemberSchema>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid household ID format", StatusCode::BAD_REQUEST))?;
+    require_household_role(&state.db, household_id, user_id, &["owner"]).await?;
+
+    let email = body.email.trim().to_lowercase();
+    validate_email(&email)?;
+
+    let role = match &body.role {
+        Some(role) => validate_household_role(role)?,
+        None => "editor".to_string(),
+    };
+
+    let already_member: bool = sqlx::query_scalar(
+        "SELECT EXISTS(
+            SELECT 1 FROM household_members m
+            JOIN users u ON u.user_id = m.user_id
+            WHERE m.household_id = $1 AND LOWER(u.email) = $2
+        )",
+    )
+    .bind(household_id)
+    .bind(&email)
+    .fetch_one(&state.db)
+    .await?;
+
+    if already_member {
+        return Err(ApiResponse::error(
+            "This person is already a member of the household",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let invitation = sqlx::query_as::<_, HouseholdInvitationModel>(
+        "INSERT INTO household_invitations (household_id, email, role, invited_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (household_id, email) DO NOTHING
+         RETURNING *, (SELECT name FROM households WHERE household_id = $1) AS household_name",
+    )
+    .bind(household_id)
+    .bind(&email)
+    .bind(&role)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| {
+        ApiResponse::error(
+            "This email has already been invited to the household",
+            StatusCode::CONFLICT,
+        )
+    })?;
+
+    let notified = sqlx::query(
+        "INSERT INTO notifications (user_id, category, message)
+         SELECT user_id, 'HOUSEHOLD_INVITE', $2 FROM users WHERE LOWER(email) = $1",
+    )
+    .bind(&email)
+    .bind(format!(
+        "You have been invited to join the household '{}' as {}",
+        invitation.household_name.as_deref().unwrap_or_default(),
+        role
+    ))
+    .execute(&state.db)
+    .await?;
+    record_notifications("HOUSEHOLD_INVITE", notified.rows_affected());
+
+    Ok(ApiResponse::success(json!({
+        "invitation": invitation
+    })))
+}
+
+/// Accepts an invitation sent to the current user's email and joins the household.
+#[utoipa::path(
+    post,
+    path = "/invitation/{id}/accept",
+    tag = "household",
+    params(("id" = String, Path, description = "Invitation id")),
+    responses(
+        (status = 200, description = "Success; `data` has `household`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn accept_invitation(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let invitation_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid invitation ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = state.db.begin().await?;
+
+    let invitation = sqlx::query_as::<_, HouseholdInvitationModel>(
+        "DELETE FROM household_invitations
+         WHERE invitation_id = $1
+            AND LOWER(email) = (SELECT LOWER(email) FROM users WHERE user_id = $2)
+         RETURNING *",
+    )
+    .bind(invitation_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Invitation not found", StatusCode::NOT_FOUND))?;
+
+    sqlx::query(
+        "INSERT INTO household_members (household_id, user_id, role) VALUES ($1, $2, $3)
+         ON CONFLICT (household_id, user_id) DO NOTHING",
+    )
+    .bind(invitation.household_id)
+    .bind(user_id)
+    .bind(&invitation.role)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let household = get_household(&state.db, invitation.household_id, user_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "household": household
+    })))
+}
@@ -3,14 +3,29 @@ use std::sync::Arc;
 use axum::{
     Extension,
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
 };
 use serde_json::json;
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 use crate::{
     AppState,
+    models::{AttachmentModel, Money},
     schema::{ApiResponse, ApiResult},
+    storage::delete_keys,
+    utils::{
+        account::get_account,
+        attachment::attachment_keys,
+        audit::begin_audited,
+        etag::lock_if_match,
+        goal::{get_goal, sync_goal_milestone},
+        household::{
+            SHARED_TABLES, get_household_role, has_other_owner, lock_household_members,
+            require_household_role,
+        },
+        trash::TRASHABLE_TABLES,
+    },
 };
 
 /// Common delete function for UUID-based resources
@@ -20,8 +35,15 @@ use crate::{
 /// * `table_name` - Name of the table to delete from
 /// * `id_column` - Name of the ID column (e.g., "expense_id", "budget_id")
 /// * `id` - UUID string to parse and use for deletion
-/// * `user_id` - User ID to ensure ownership
+/// * `user_id` - User ID to check access: the owner, or an editor/owner of the
+///   row's household for tables in `SHARED_TABLES`
 /// * `resource_name` - Human-readable resource name for error messages
+///
+/// * `if_match` - Request headers to honour `If-Match` with, for resources
+///   that hand out ETags
+///
+/// Rows of tables in `TRASHABLE_TABLES` are moved to the trash instead of
+/// being deleted.
 async fn delete_resource_by_uuid(
     state: &Arc<AppState>,
     table_name: &str,
@@ -29,6 +51,7 @@ async fn delete_resource_by_uuid(
     id: &str,
     user_id: Uuid,
     resource_name: &str,
+    if_match: Option<&HeaderMap>,
 ) -> ApiResult<serde_json::Value> {
     // Parse the UUID
     let resource_id = Uuid::parse_str(id).map_err(|_| {
@@ -38,21 +61,57 @@ async fn delete_resource_by_uuid(
         )
     })?;
 
-    // Execute the delete query
-    let query = format!(
-        "DELETE FROM {} WHERE {} = $1 AND user_id = $2 RETURNING {}",
-        table_name, id_column, id_column
-    );
+    let access_filter = if SHARED_TABLES.contains(&table_name) {
+        "can_edit_resource(user_id, household_id, $2)"
+    } else {
+        "user_id = $2"
+    };
+
+    let (query, action) = if TRASHABLE_TABLES.contains(&table_name) {
+        (
+            format!(
+                "UPDATE {} SET deleted_at = now()
+                 WHERE {} = $1 AND deleted_at IS NULL AND {} RETURNING {}",
+                table_name, id_column, access_filter, id_column
+            ),
+            "moved to trash",
+        )
+    } else {
+        (
+            format!(
+                "DELETE FROM {} WHERE {} = $1 AND {} RETURNING {}",
+                table_name, id_column, access_filter, id_column
+            ),
+            "deleted successfully",
+        )
+    };
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    if let Some(headers) = if_match {
+        lock_if_match(
+            &mut tx,
+            table_name,
+            id_column,
+            resource_id,
+            access_filter,
+            user_id,
+            headers,
+        )
+        .await?;
+    }
 
     let result = sqlx::query(&query)
         .bind(resource_id)
         .bind(user_id)
-        .fetch_optional(&state.db)
+        .fetch_optional(&mut *tx)
         .await?;
 
+    tx.commit().await?;
+
     match result {
         Some(_) => Ok(ApiResponse::success(json!({
-            "message": format!("{} deleted successfully", resource_name)
+            "message": format!("{} {}", resource_name, action)
         }))),
         None => Err(ApiResponse::error(
             &format!(
@@ -64,22 +123,567 @@ async fn delete_resource_by_uuid(
     }
 }
 
+/// Moves an expense to the trash together with its refunds. Attachments, tags
+/// and splits stay in place until the trash is purged. Honours `If-Match`.
+#[utoipa::path(
+    delete,
+    path = "/expense/{id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense id"),
+        ("If-Match" = Option<String>, Header, description = "Only apply while the `ETag` still matches"),
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = 412, description = "The resource changed since the given `ETag`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn delete_expense(
     Path(id): Path<String>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> ApiResult<serde_json::Value> {
+    let expense_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+    trash_expense(&mut tx, user_id, expense_id, &headers).await?;
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "message": "Expense moved to trash"
+    })))
+}
+
+/// Moves an expense and its refunds to the trash in `conn`, a transaction
+/// started with `begin_audited`, honouring `If-Match` in `headers`.
+pub async fn trash_expense(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    expense_id: Uuid,
+    headers: &HeaderMap,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    lock_if_match(
+        &mut *conn,
+        "expenses",
+        "expense_id",
+        expense_id,
+        "can_edit_resource(user_id, household_id, $2)",
+        user_id,
+        headers,
+    )
+    .await?;
+
+    // now() is the same for every row, so the refunds can be restored with it
+    let trashed: Vec<Uuid> = sqlx::query_scalar(
+        "WITH target AS (
+            SELECT expense_id FROM expenses
+            WHERE expense_id = $1 AND deleted_at IS NULL
+                AND can_edit_resource(user_id, household_id, $2)
+        )
+        UPDATE expenses SET deleted_at = now()
+        WHERE deleted_at IS NULL
+            AND (expense_id IN (SELECT expense_id FROM target)
+                OR original_expense_id IN (SELECT expense_id FROM target))
+        RETURNING expense_id",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_all(conn)
+    .await?;
+
+    if trashed.is_empty() {
+        return Err(ApiResponse::error(
+            "Expense not found or you don't have permission to delete it",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/attachments/{id}",
+    tag = "expense",
+    summary = "Delete attachment",
+    params(("id" = String, Path, description = "Attachment id")),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_attachment(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let attachment_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid attachment ID format", StatusCode::BAD_REQUEST))?;
+
+    let deleted = sqlx::query_as::<_, AttachmentModel>(
+        "DELETE FROM attachments a
+         USING expenses e
+         WHERE a.attachment_id = $1
+            AND e.expense_id = a.expense_id
+            AND can_edit_resource(e.user_id, e.household_id, $2)
+         RETURNING a.*",
+    )
+    .bind(attachment_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match deleted {
+        Some(attachment) => {
+            delete_keys(state.storage.as_ref(), attachment_keys(vec![attachment])).await;
+            Ok(ApiResponse::success(json!({
+                "message": "Attachment deleted successfully"
+            })))
+        }
+        None => Err(ApiResponse::error(
+            "Attachment not found or you don't have permission to delete it",
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/rule/{id}",
+    tag = "rule",
+    summary = "Delete rule",
+    params(("id" = String, Path, description = "Rule id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_rule(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    delete_resource_by_uuid(
+        &state,
+        "expense_rules",
+        "rule_id",
+        &id,
+        user_id,
+        "Rule",
+        None,
+    )
+    .await
+}
+
+/// Deletes one of the user's manual rates; reference rates can't be deleted.
+#[utoipa::path(
+    delete,
+    path = "/exchange-rate/{id}",
+    tag = "exchange_rate",
+    params(("id" = String, Path, description = "Exchange rate id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_exchange_rate(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    delete_resource_by_uuid(
+        &state,
+        "exchange_rates",
+        "rate_id",
+        &id,
+        user_id,
+        "Exchange rate",
+        None,
+    )
+    .await
+}
+
+/// Deletes an account that no transaction or transfer refers to anymore.
+#[utoipa::path(
+    delete,
+    path = "/account/{id}",
+    tag = "account",
+    params(("id" = String, Path, description = "Account id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_account(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let account_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid account ID format", StatusCode::BAD_REQUEST))?;
+
+    get_account(&state.db, user_id, account_id).await?;
+
+    // Accounts are personal, so only the owner's transactions can use one,
+    // whichever workspace they are in
+    let in_use: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM expenses WHERE account_id = $1 AND user_id = $2)
+            OR EXISTS(
+                SELECT 1 FROM transfers
+                WHERE (from_account_id = $1 OR to_account_id = $1) AND user_id = $2
+            )",
+    )
+    .bind(account_id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    // Trashed transactions still point at the account until they are purged
+    if in_use {
+        return Err(ApiResponse::error(
+            "Account still has transactions (possibly in the trash) or transfers, move or delete them first",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    delete_resource_by_uuid(
+        &state,
+        "accounts",
+        "account_id",
+        &id,
+        user_id,
+        "Account",
+        None,
+    )
+    .await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/transfer/{id}",
+    tag = "account",
+    summary = "Delete transfer",
+    params(("id" = String, Path, description = "Transfer id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_transfer(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    delete_resource_by_uuid(
+        &state,
+        "transfers",
+        "transfer_id",
+        &id,
+        user_id,
+        "Transfer",
+        None,
+    )
+    .await
+}
+
+/// Deletes a contact that no split or settlement refers to anymore.
+#[utoipa::path(
+    delete,
+    path = "/contact/{id}",
+    tag = "split",
+    params(("id" = String, Path, description = "Contact id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_contact(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let contact_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid contact ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    // The lock holds off new splits and settlements naming the contact until
+    // it is gone, as their foreign key checks need to share it
+    let owned: Option<i32> = sqlx::query_scalar(
+        "SELECT 1 FROM contacts WHERE contact_id = $1 AND user_id = $2 FOR UPDATE",
+    )
+    .bind(contact_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if owned.is_none() {
+        return Err(ApiResponse::error(
+            "Contact not found or you don't have permission to delete it",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let in_use: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM expense_splits WHERE contact_id = $1)
+            OR EXISTS(SELECT 1 FROM settlements WHERE from_contact_id = $1 OR to_contact_id = $1)",
+    )
+    .bind(contact_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if in_use {
+        return Err(ApiResponse::error(
+            "Contact still takes part in splits or settlements, remove them first",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    sqlx::query("DELETE FROM contacts WHERE contact_id = $1")
+        .bind(contact_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "message": "Contact deleted successfully"
+    })))
+}
+
+/// Removes the split of an expense, so nobody owes anything for it anymore.
+#[utoipa::path(
+    delete,
+    path = "/expense/{id}/split",
+    tag = "split",
+    params(("id" = String, Path, description = "Expense id")),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_expense_split(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let expense_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    let result = sqlx::query(
+        "UPDATE expenses SET split_method = NULL
+         WHERE expense_id = $1 AND split_method IS NOT NULL
+            AND can_edit_resource(user_id, household_id, $2)",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiResponse::error(
+            "Split not found or you don't have permission to delete it",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    sqlx::query("DELETE FROM expense_splits WHERE expense_id = $1")
+        .bind(expense_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "message": "Split deleted successfully"
+    })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/settlement/{id}",
+    tag = "split",
+    summary = "Delete settlement",
+    params(("id" = String, Path, description = "Settlement id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_settlement(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
 ) -> ApiResult<serde_json::Value> {
-    delete_resource_by_uuid(&state, "expenses", "expense_id", &id, user_id, "Expense").await
+    delete_resource_by_uuid(
+        &state,
+        "settlements",
+        "settlement_id",
+        &id,
+        user_id,
+        "Settlement",
+        None,
+    )
+    .await
 }
 
+#[utoipa::path(
+    delete,
+    path = "/goal/{id}",
+    tag = "goal",
+    summary = "Delete goal",
+    params(("id" = String, Path, description = "Goal id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_goal(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    delete_resource_by_uuid(
+        &state,
+        "savings_goals",
+        "goal_id",
+        &id,
+        user_id,
+        "Savings goal",
+        None,
+    )
+    .await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/goal/{id}/contribution/{contribution_id}",
+    tag = "goal",
+    summary = "Delete goal contribution",
+    params(
+        ("id" = String, Path, description = "Goal id"),
+        ("contribution_id" = String, Path, description = "Contribution id"),
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_goal_contribution(
+    Path((id, contribution_id)): Path<(String, String)>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let goal_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid goal ID format", StatusCode::BAD_REQUEST))?;
+    let contribution_id = Uuid::parse_str(&contribution_id).map_err(|_| {
+        ApiResponse::error("Invalid contribution ID format", StatusCode::BAD_REQUEST)
+    })?;
+
+    let mut tx = state.db.begin().await?;
+
+    let result = sqlx::query(
+        "DELETE FROM goal_contributions c USING savings_goals g
+         WHERE c.contribution_id = $1 AND c.goal_id = $2
+            AND g.goal_id = c.goal_id AND g.user_id = $3",
+    )
+    .bind(contribution_id)
+    .bind(goal_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiResponse::error(
+            "Contribution not found or you don't have permission to delete it",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let goal = get_goal(&mut tx, user_id, goal_id).await?;
+    if goal.saved_amount < Money::ZERO {
+        return Err(ApiResponse::error(
+            "Deleting this contribution would leave the goal with less than nothing saved",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    sync_goal_milestone(&mut tx, &goal).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "message": "Contribution deleted successfully"
+    })))
+}
+
+/// Deletes a bill and its payment history. Expenses recorded for past
+/// payments are kept.
+#[utoipa::path(
+    delete,
+    path = "/bill/{id}",
+    tag = "bill",
+    params(("id" = String, Path, description = "Bill id")),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_bill(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    delete_resource_by_uuid(&state, "bills", "bill_id", &id, user_id, "Bill", None).await
+}
+
+/// Moves a budget to the trash. Honours `If-Match`.
+#[utoipa::path(
+    delete,
+    path = "/budget/{id}",
+    tag = "budget",
+    params(
+        ("id" = String, Path, description = "Budget id"),
+        ("If-Match" = Option<String>, Header, description = "Only apply while the `ETag` still matches"),
+    ),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = 412, description = "The resource changed since the given `ETag`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn delete_budget(
     Path(id): Path<String>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
 ) -> ApiResult<serde_json::Value> {
-    delete_resource_by_uuid(&state, "budgets", "budget_id", &id, user_id, "Budget").await
+    delete_resource_by_uuid(
+        &state,
+        "budgets",
+        "budget_id",
+        &id,
+        user_id,
+        "Budget",
+        Some(&headers),
+    )
+    .await
 }
 
+#[utoipa::path(
+    delete,
+    path = "/category/{id}",
+    tag = "category",
+    summary = "Delete category",
+    params(("id" = String, Path, description = "Category id")),
+    responses(
+        (status = 200, description = "Success; `data` has `message`, `trashedCategories`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn delete_category(
     Path(id): Path<String>,
     Extension(user_id): Extension<Uuid>,
@@ -89,19 +693,240 @@ pub async fn delete_category(
         .parse()
         .map_err(|_| ApiResponse::error("Invalid category ID format", StatusCode::BAD_REQUEST))?;
 
-    let result = sqlx::query("DELETE FROM categories WHERE category_id = $1 AND user_id = $2")
-        .bind(resource_id)
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    // Subcategories go to the trash along with the category, with the same
+    // deleted_at so they are restored together. Expenses, budgets, rules and
+    // bills keep their category_id so a restore brings them back as they were;
+    // the links are only cleared when the purge deletes the category.
+    let trashed: Vec<i32> = sqlx::query_scalar(
+        "WITH RECURSIVE subtree AS (
+            SELECT category_id FROM categories
+            WHERE category_id = $1 AND deleted_at IS NULL
+                AND can_edit_resource(user_id, household_id, $2)
+            UNION
+            SELECT c.category_id FROM categories c
+            JOIN subtree s ON c.parent_id = s.category_id
+            WHERE c.deleted_at IS NULL
+        )
+        UPDATE categories SET deleted_at = now()
+        WHERE category_id IN (SELECT category_id FROM subtree)
+        RETURNING category_id",
+    )
+    .bind(resource_id)
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    if trashed.is_empty() {
+        return Err(ApiResponse::error(
+            "category not found or you don't have permission to delete it",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    Ok(ApiResponse::success(json!({
+        "message": "category moved to trash",
+        "trashedCategories": trashed.len()
+    })))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/tag/{id}",
+    tag = "tag",
+    summary = "Delete tag",
+    params(("id" = String, Path, description = "Tag id")),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_tag(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let tag_id: i32 = id
+        .parse()
+        .map_err(|_| ApiResponse::error("Invalid tag ID format", StatusCode::BAD_REQUEST))?;
+
+    let result = sqlx::query("DELETE FROM tags WHERE tag_id = $1 AND user_id = $2")
+        .bind(tag_id)
         .bind(user_id)
-        .fetch_optional(&state.db)
+        .execute(&state.db)
         .await?;
 
-    match result {
-        Some(_) => Ok(ApiResponse::success(json!({
-            "message": "category deleted successfully"
-        }))),
-        None => Err(ApiResponse::error(
-            "category not found or you don't have permission to delete it",
+    if result.rows_affected() == 0 {
+        return Err(ApiResponse::error(
+            "Tag not found or you don't have permission to delete it",
             StatusCode::NOT_FOUND,
-        )),
+        ));
     }
+
+    Ok(ApiResponse::success(json!({
+        "message": "Tag deleted successfully"
+    })))
+}
+
+/// Deletes a household together with everything shared in it.
+#[utoipa::path(
+    delete,
+    path = "/household/{id}",
+    tag = "household",
+    params(("id" = String, Path, description = "Household id")),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_household(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid household ID format", StatusCode::BAD_REQUEST))?;
+    let mut tx = begin_audited(&state.db, user_id).await?;
+    require_household_role(&mut *tx, household_id, user_id, &["owner"]).await?;
+
+    // Locking the household and its expenses holds off new expenses and
+    // attachments, so every stored file of the household is known here.
+    // Attachment rows of shared expenses go away with them.
+    for query in [
+        "SELECT 1 FROM households WHERE household_id = $1 FOR UPDATE",
+        "SELECT 1 FROM expenses WHERE household_id = $1 FOR UPDATE",
+    ] {
+        sqlx::query(query)
+            .bind(household_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let attachments = sqlx::query_as::<_, AttachmentModel>(
+        "SELECT a.* FROM attachments a
+         JOIN expenses e ON e.expense_id = a.expense_id
+         WHERE e.household_id = $1",
+    )
+    .bind(household_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM households WHERE household_id = $1")
+        .bind(household_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    delete_keys(state.storage.as_ref(), attachment_keys(attachments)).await;
+
+    Ok(ApiResponse::success(json!({
+        "message": "Household deleted successfully"
+    })))
+}
+
+/// Removes a member from a household. Owners can remove anyone, other members
+/// can only leave themselves. What a member shared stays in the household.
+#[utoipa::path(
+    delete,
+    path = "/household/{id}/member/{member}",
+    tag = "household",
+    params(
+        ("id" = String, Path, description = "Household id"),
+        ("member" = String, Path, description = "User id of the member"),
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn remove_member(
+    Path((id, member)): Path<(String, String)>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid household ID format", StatusCode::BAD_REQUEST))?;
+    let member_id = Uuid::parse_str(&member)
+        .map_err(|_| ApiResponse::error("Invalid member ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+    lock_household_members(&mut *tx, household_id).await?;
+
+    if member_id != user_id {
+        require_household_role(&mut *tx, household_id, user_id, &["owner"]).await?;
+    }
+
+    let member_role = get_household_role(&mut *tx, household_id, member_id)
+        .await?
+        .ok_or_else(|| ApiResponse::error("Member not found", StatusCode::NOT_FOUND))?;
+
+    if member_role == "owner" && !has_other_owner(&mut *tx, household_id, member_id).await? {
+        return Err(ApiResponse::error(
+            "The last owner can't leave, make another member owner or delete the household",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    sqlx::query("DELETE FROM household_members WHERE household_id = $1 AND user_id = $2")
+        .bind(household_id)
+        .bind(member_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "message": "Member removed successfully"
+    })))
+}
+
+/// Revokes an invitation (household owners) or declines it (the invitee).
+#[utoipa::path(
+    delete,
+    path = "/invitation/{id}",
+    tag = "household",
+    params(("id" = String, Path, description = "Invitation id")),
+    responses(
+        (status = 200, description = "Success; `data` has `message`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn delete_invitation(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let invitation_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid invitation ID format", StatusCode::BAD_REQUEST))?;
+
+    let result = sqlx::query(
+        "DELETE FROM household_invitations i
+         WHERE i.invitation_id = $1
+            AND (
+                LOWER(i.email) = (SELECT LOWER(email) FROM users WHERE user_id = $2)
+                OR EXISTS (
+                    SELECT 1 FROM household_members m
+                    WHERE m.household_id = i.household_id AND m.user_id = $2 AND m.role = 'owner'
+                )
+            )",
+    )
+    .bind(invitation_id)
+    .bind(user_id)
+    .execute(&state.db)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiResponse::error(
+            "Invitation not found or you don't have permission to delete it",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    Ok(ApiResponse::success(json!({
+        "message": "Invitation deleted successfully"
+    })))
 }
@@ -1,8 +1,14 @@
+pub mod batch;
+pub use batch::*;
+
 pub mod create;
 pub use create::*;
 
 pub mod delete;
 pub use delete::*;
 
+pub mod sync;
+pub use sync::*;
+
 pub mod update;
-pub use update::*;
\ No newline at end of file
+pub use update::*;
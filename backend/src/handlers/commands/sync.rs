@@ -0,0 +1,353 @@
+use std::sync::Arc;
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    handlers::{
+        create_budget, create_expense, delete_budget, delete_expense, update_budget, update_expense,
+    },
+    schema::{
+        ApiResponse, ApiResult, CreateBudgetSchema, CreateExpenseSchema, SyncMutationSchema,
+        SyncSchema, UpdateBudgetSchema, UpdateExpenseSchema,
+    },
+    utils::{
+        etag::etag,
+        sync::{
+            MAX_SYNC_MUTATIONS, current_sync_seq, get_sync_budgets, get_sync_categories,
+            get_sync_changes, get_sync_expenses, get_sync_notifications, parse_sync_token,
+            validate_sync_mutation,
+        },
+    },
+};
+
+/// What became of one mutation of a sync.
+enum SyncOutcome {
+    Applied,
+    /// The server copy changed or went away; the client keeps the server's
+    Conflict(String),
+    /// The mutation is invalid and will never apply
+    Rejected(String),
+}
+
+impl From<ApiResponse<serde_json::Value>> for SyncOutcome {
+    fn from(err: ApiResponse<serde_json::Value>) -> Self {
+        let message = err.message.unwrap_or_default();
+        if err.status == StatusCode::PRECONDITION_FAILED.as_u16() {
+            SyncOutcome::Conflict(message)
+        } else {
+            SyncOutcome::Rejected(message)
+        }
+    }
+}
+
+impl SyncOutcome {
+    /// The outcome of a mutation applied by one of the regular handlers. Server
+    /// errors, like a failed database query, say nothing about the mutation and
+    /// may not happen again, so they abort the sync instead of rejecting it.
+    fn from_result(
+        result: Result<(), ApiResponse<serde_json::Value>>,
+    ) -> Result<Self, ApiResponse<serde_json::Value>> {
+        match result {
+            Ok(()) => Ok(SyncOutcome::Applied),
+            Err(err) if err.status >= 500 => Err(err),
+            Err(err) => Ok(err.into()),
+        }
+    }
+}
+
+/// Two-way sync for offline clients. The mutations are applied first, each on
+/// its own and in order, then every change since `syncToken` is returned:
+/// the current state of changed expenses, budgets, categories and
+/// notifications, and tombstones for deleted (or trashed) ones. Without a token,
+/// or with one the server can't catch up from, all live data is returned with
+/// `full` set and the client should replace its copy.
+///
+/// Mutations are idempotent, so a batch can be resent after a lost response or
+/// a server error, which aborts the sync rather than rejecting a mutation:
+/// - `upsert` creates the entity under the client's id, or updates it when it
+///   already exists.
+/// - `delete` moves the entity to the trash; deleting one that is gone already
+///   is a no-op.
+///
+/// Conflicts are resolved in favour of the server. A mutation carrying
+/// `baseUpdatedAt` only applies while the server copy is still at that
+/// version, and an upsert never brings back an entity deleted on the server.
+/// Conflicting mutations are skipped and reported with the server's copy; the
+/// client drops or reapplies them on top of it. Without `baseUpdatedAt` the
+/// last write wins.
+#[utoipa::path(
+    post,
+    path = "/sync",
+    tag = "sync",
+    request_body = SyncSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `syncToken`, `full`, `results`, `expenses`, `budgets`, `categories`, `notifications`, `deleted`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn sync_changes(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SyncSchema>,
+) -> ApiResult<serde_json::Value> {
+    if body.mutations.len() > MAX_SYNC_MUTATIONS {
+        return Err(ApiResponse::error(
+            &format!("A sync can carry at most {} mutations", MAX_SYNC_MUTATIONS),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let since = parse_sync_token(body.sync_token.as_deref())?;
+
+    let mut results = Vec::with_capacity(body.mutations.len());
+    for mutation in body.mutations {
+        results.push(apply_sync_mutation(&state, user_id, mutation).await?);
+    }
+
+    // The token is read before the data, so whatever commits in between is
+    // sent again next time rather than missed.
+    let sync_token = current_sync_seq(&state.db, user_id).await?;
+    let since = since.filter(|since| *since <= sync_token);
+
+    let (expenses, budgets, categories, notifications, deleted) = match since {
+        Some(since) => {
+            let changes = get_sync_changes(&state.db, user_id, since, sync_token).await?;
+            (
+                get_sync_expenses(&state.db, user_id, Some(&changes.expense_ids)).await?,
+                get_sync_budgets(&state.db, user_id, Some(&changes.budget_ids)).await?,
+                get_sync_categories(&state.db, user_id, Some(&changes.category_ids)).await?,
+                get_sync_notifications(&state.db, user_id, Some(&changes.notification_ids)).await?,
+                changes.deleted,
+            )
+        }
+        None => (
+            get_sync_expenses(&state.db, user_id, None).await?,
+            get_sync_budgets(&state.db, user_id, None).await?,
+            get_sync_categories(&state.db, user_id, None).await?,
+            get_sync_notifications(&state.db, user_id, None).await?,
+            Vec::new(),
+        ),
+    };
+
+    let deleted: Vec<serde_json::Value> = deleted
+        .into_iter()
+        .map(|(entity_type, id)| json!({ "entityType": entity_type, "id": id }))
+        .collect();
+
+    Ok(ApiResponse::success(json!({
+        "syncToken": sync_token.to_string(),
+        "full": since.is_none(),
+        "results": results,
+        "expenses": expenses,
+        "budgets": budgets,
+        "categories": categories,
+        "notifications": notifications,
+        "deleted": deleted
+    })))
+}
+
+/// Applies one mutation and describes the outcome. Only server errors abort
+/// the sync; the client resends it, which the mutations applied so far
+/// tolerate as they're idempotent. Everything else is reported per mutation.
+async fn apply_sync_mutation(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    mutation: SyncMutationSchema,
+) -> Result<serde_json::Value, ApiResponse<serde_json::Value>> {
+    let entity_type = mutation.entity_type.trim().to_lowercase();
+
+    let outcome = match validate_sync_mutation(&entity_type, &mutation.op) {
+        Err(err) => err.into(),
+        Ok(()) => match Uuid::parse_str(&mutation.id) {
+            Err(err) => SyncOutcome::Rejected(err.to_string()),
+            Ok(id) if entity_type == "expense" => {
+                apply_expense_mutation(state, user_id, id, &mutation).await?
+            }
+            Ok(id) => apply_budget_mutation(state, user_id, id, &mutation).await?,
+        },
+    };
+
+    let mut result = json!({
+        "entityType": entity_type,
+        "id": mutation.id,
+    });
+
+    match outcome {
+        SyncOutcome::Applied => {
+            result["status"] = json!("applied");
+        }
+        SyncOutcome::Conflict(message) => {
+            result["status"] = json!("conflict");
+            result["message"] = json!(message);
+            result["current"] = match Uuid::parse_str(&mutation.id) {
+                Ok(id) if entity_type == "expense" => {
+                    json!(
+                        get_sync_expenses(&state.db, user_id, Some(&[id]))
+                            .await?
+                            .pop()
+                    )
+                }
+                Ok(id) => json!(
+                    get_sync_budgets(&state.db, user_id, Some(&[id]))
+                        .await?
+                        .pop()
+                ),
+                Err(_) => serde_json::Value::Null,
+            };
+        }
+        SyncOutcome::Rejected(message) => {
+            result["status"] = json!("rejected");
+            result["message"] = json!(message);
+        }
+    }
+
+    Ok(result)
+}
+
+async fn apply_expense_mutation(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    expense_id: Uuid,
+    mutation: &SyncMutationSchema,
+) -> Result<SyncOutcome, ApiResponse<serde_json::Value>> {
+    let current = get_row_state(state, "expenses", "expense_id", expense_id, user_id).await?;
+    let headers = if_match_headers(mutation.base_updated_at);
+
+    let result = match (mutation.op.as_str(), current) {
+        ("upsert", None) => match parse_data::<CreateExpenseSchema>(mutation) {
+            Ok(mut body) => {
+                body.expense_id = Some(expense_id.to_string());
+                create_expense(Extension(user_id), State(Arc::clone(state)), Json(body))
+                    .await
+                    .map(|_| ())
+            }
+            Err(outcome) => return Ok(outcome),
+        },
+        ("upsert", Some(Some(_))) => {
+            return Ok(SyncOutcome::Conflict(
+                "The expense was deleted on the server".to_string(),
+            ));
+        }
+        ("upsert", Some(None)) => match parse_data::<UpdateExpenseSchema>(mutation) {
+            Ok(body) => update_expense(
+                Path(expense_id.to_string()),
+                Extension(user_id),
+                State(Arc::clone(state)),
+                headers,
+                Json(body),
+            )
+            .await
+            .map(|_| ()),
+            Err(outcome) => return Ok(outcome),
+        },
+        (_, None | Some(Some(_))) => Ok(()),
+        (_, Some(None)) => delete_expense(
+            Path(expense_id.to_string()),
+            Extension(user_id),
+            State(Arc::clone(state)),
+            headers,
+        )
+        .await
+        .map(|_| ()),
+    };
+
+    SyncOutcome::from_result(result)
+}
+
+async fn apply_budget_mutation(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    budget_id: Uuid,
+    mutation: &SyncMutationSchema,
+) -> Result<SyncOutcome, ApiResponse<serde_json::Value>> {
+    let current = get_row_state(state, "budgets", "budget_id", budget_id, user_id).await?;
+    let headers = if_match_headers(mutation.base_updated_at);
+
+    let result = match (mutation.op.as_str(), current) {
+        ("upsert", None) => match parse_data::<CreateBudgetSchema>(mutation) {
+            Ok(mut body) => {
+                body.budget_id = Some(budget_id.to_string());
+                create_budget(Extension(user_id), State(Arc::clone(state)), Json(body))
+                    .await
+                    .map(|_| ())
+            }
+            Err(outcome) => return Ok(outcome),
+        },
+        ("upsert", Some(Some(_))) => {
+            return Ok(SyncOutcome::Conflict(
+                "The budget was deleted on the server".to_string(),
+            ));
+        }
+        ("upsert", Some(None)) => match parse_data::<UpdateBudgetSchema>(mutation) {
+            Ok(body) => update_budget(
+                Path(budget_id.to_string()),
+                Extension(user_id),
+                State(Arc::clone(state)),
+                headers,
+                Json(body),
+            )
+            .await
+            .map(|_| ()),
+            Err(outcome) => return Ok(outcome),
+        },
+        (_, None | Some(Some(_))) => Ok(()),
+        (_, Some(None)) => delete_budget(
+            Path(budget_id.to_string()),
+            Extension(user_id),
+            State(Arc::clone(state)),
+            headers,
+        )
+        .await
+        .map(|_| ()),
+    };
+
+    SyncOutcome::from_result(result)
+}
+
+/// Whether a row the user can see exists, and when it went to the trash if it
+/// did: `None` for no row, `Some(None)` for a live one.
+async fn get_row_state(
+    state: &AppState,
+    table_name: &str,
+    id_column: &str,
+    id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<Option<DateTime<Utc>>>, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT deleted_at FROM {} WHERE {} = $1 AND can_view_resource(user_id, household_id, $2)",
+        table_name, id_column
+    ))
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+}
+
+/// `If-Match` for the version a mutation was made on, so the handlers turn a
+/// changed server copy into a 412.
+fn if_match_headers(base_updated_at: Option<DateTime<Utc>>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Some(base_updated_at) = base_updated_at
+        && let Ok(value) = HeaderValue::from_str(&etag(Some(base_updated_at)))
+    {
+        headers.insert(header::IF_MATCH, value);
+    }
+    headers
+}
+
+fn parse_data<T: DeserializeOwned>(mutation: &SyncMutationSchema) -> Result<T, SyncOutcome> {
+    let data = mutation
+        .data
+        .clone()
+        .ok_or_else(|| SyncOutcome::Rejected("An upsert needs data".to_string()))?;
+    serde_json::from_value(data).map_err(|err| SyncOutcome::Rejected(err.to_string()))
+}
@@ -3,33 +3,115 @@ use std::sync::Arc;
 use axum::{
     Extension, Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::Response,
 };
 use serde_json::json;
+use sqlx::PgConnection;
 use uuid::Uuid;
 
 use crate::{
     AppState,
-    models::{BudgetModel, ExpenseModel},
-    schema::{ApiResponse, ApiResult, UpdateBudgetSchema, UpdateExpenseSchema},
-    utils::helper::validate_name,
+    models::{
+        AccountModel, BudgetModel, CategoryModel, ContactModel, ExpenseModel, ExpenseRuleModel,
+        HouseholdMemberModel, TagModel, UserModel,
+    },
+    schema::{
+        ApiResponse, ApiResult, CategoryOverrideSchema, MoveCategorySchema, SetExpenseSplitSchema,
+        UpdateAccountSchema, UpdateBillSchema, UpdateBudgetSchema, UpdateCategorySchema,
+        UpdateContactSchema, UpdateExpenseSchema, UpdateGoalSchema, UpdateHouseholdSchema,
+        UpdateMemberSchema, UpdateProfileSchema, UpdateRuleSchema, UpdateTagSchema,
+    },
+    utils::{
+        account::{get_account, validate_account_type},
+        audit::begin_audited,
+        bill::{get_bill, validate_recurrence, validate_remind_days},
+        budget::get_workspace_budget,
+        category::{
+            get_editable_category, get_visible_category, get_workspace_category, is_in_subtree,
+            merge_category_into, validate_category_name, validate_category_parent,
+            validate_category_style,
+        },
+        currency::{get_user_base_currency, parse_amount, rescale_amount, validate_currency},
+        etag::{check_if_match, etag, with_etag},
+        expense::{
+            fetch_expense, get_refundable_expense, get_refunded_amount, validate_refund,
+            validate_transaction_type,
+        },
+        goal::{get_goal, sync_goal_milestone, validate_goal_account},
+        helper::{validate_email, validate_name},
+        household::{
+            ensure_same_workspace, get_household, has_other_owner, lock_household_members,
+            require_edit_access, require_household_role, validate_household_role, validate_paid_by,
+        },
+        rule::{validate_rule_actions, validate_rule_conditions},
+        split::{
+            fetch_expense_splits, participant_weight, resolve_split_party, resplit_expense,
+            split_amounts, validate_split_method,
+        },
+        tag::{normalize_tag, normalize_tags, set_expense_tags},
+        trash::{restore_budget, restore_category, restore_expense, validate_trash_type},
+    },
 };
 
+/// Updates an expense. With an `If-Match` header the update only happens if the
+/// expense is still at that version; the row stays locked from the read to the
+/// write.
+#[utoipa::path(
+    put,
+    path = "/expense/{id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense id"),
+        ("If-Match" = Option<String>, Header, description = "Only apply while the `ETag` still matches"),
+    ),
+    request_body = UpdateExpenseSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `expense`", body = ApiResponse),
+        (status = 412, description = "The resource changed since the given `ETag`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn update_expense(
     Path(id): Path<String>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(body): Json<UpdateExpenseSchema>,
-) -> ApiResult<serde_json::Value> {
+) -> Result<Response, ApiResponse<serde_json::Value>> {
     let expense_id = Uuid::parse_str(&id)
         .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
 
+    let mut tx = begin_audited(&state.db, user_id).await?;
+    let updated_expense = change_expense(&mut tx, user_id, expense_id, body, &headers).await?;
+    tx.commit().await?;
+
+    Ok(with_etag(
+        ApiResponse::success(json!({
+            "expense": updated_expense
+        })),
+        &etag(updated_expense.updated_at),
+    ))
+}
+
+/// Validates and applies an update to an expense in `conn`, a transaction
+/// started with `begin_audited`, honouring `If-Match` in `headers`. Validation
+/// reads go through `conn` too.
+pub async fn change_expense(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    expense_id: Uuid,
+    body: UpdateExpenseSchema,
+    headers: &HeaderMap,
+) -> Result<ExpenseModel, ApiResponse<serde_json::Value>> {
     let existing_expense = sqlx::query_as::<_, ExpenseModel>(
-        "SELECT * FROM expenses WHERE expense_id = $1 AND user_id = $2",
+        "SELECT * FROM expenses
+         WHERE expense_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)
+         FOR UPDATE",
     )
     .bind(expense_id)
     .bind(user_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *conn)
     .await?;
 
     if existing_expense.is_none() {
@@ -40,67 +122,708 @@ pub async fn update_expense(
     }
 
     let existing_expense = existing_expense.unwrap();
+    let household_id = existing_expense.household_id;
+    require_edit_access(&mut *conn, user_id, household_id).await?;
+    check_if_match(headers, &etag(existing_expense.updated_at))?;
 
     if let Some(ref name) = body.name {
         validate_name(name)?;
     }
 
-    if let Some(amount) = body.amount {
-        if amount <= 0 {
+    let budget_id = match &body.budget_id {
+        Some(b) => Some(
+            get_workspace_budget(&mut *conn, user_id, household_id, Uuid::parse_str(b)?)
+                .await?
+                .budget_id,
+        ),
+        None => existing_expense.budget_id,
+    };
+
+    if let Some(category_id) = body.category_id {
+        get_workspace_category(&mut *conn, user_id, household_id, category_id).await?;
+    }
+
+    let paid_by = match &body.paid_by {
+        Some(paid_by) => {
+            let paid_by = Uuid::parse_str(paid_by)?;
+            validate_paid_by(&mut *conn, user_id, household_id, paid_by).await?;
+            Some(paid_by)
+        }
+        None => existing_expense.paid_by,
+    };
+
+    let tags = match &body.tags {
+        Some(tags) => Some(normalize_tags(tags)?),
+        None => None,
+    };
+
+    let transaction_type = match &body.transaction_type {
+        Some(transaction_type) => validate_transaction_type(transaction_type)?,
+        None => existing_expense.transaction_type.clone(),
+    };
+
+    let original_expense_id = match (&body.original_expense_id, transaction_type.as_str()) {
+        (Some(_), "expense" | "income") => {
             return Err(ApiResponse::error(
-                "Amount can't be less than zero",
+                "Only refunds can have an originalExpenseId",
                 StatusCode::BAD_REQUEST,
             ));
         }
+        (Some(original_id), _) => Some(Uuid::parse_str(original_id)?),
+        (None, "refund") => existing_expense.original_expense_id,
+        (None, _) => None,
+    };
+
+    if transaction_type == "income" && budget_id.is_some() {
+        return Err(ApiResponse::error(
+            "Income can't be linked to a budget",
+            StatusCode::BAD_REQUEST,
+        ));
     }
 
-    let budget_id = match &body.budget_id {
-        Some(b) => Some(Uuid::parse_str(&b)?),
-        None => existing_expense.budget_id,
+    let account_id = match &body.account_id {
+        Some(account_id) => Some(
+            get_account(&mut *conn, user_id, Uuid::parse_str(account_id)?)
+                .await?
+                .account_id,
+        ),
+        None => existing_expense.account_id,
     };
 
+    let currency = match &body.currency {
+        Some(currency) => validate_currency(currency)?,
+        None => existing_expense.currency.clone(),
+    };
+
+    let amount = match &body.amount {
+        Some(amount) => parse_amount(amount, &currency)?,
+        None => rescale_amount(
+            existing_expense.amount,
+            &existing_expense.currency,
+            &currency,
+        )?,
+    };
+    if !amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // An expense that has refunds must stay an expense covering all of them
+    if existing_expense.transaction_type == "expense" {
+        let refunded = get_refunded_amount(&mut *conn, expense_id, None).await?;
+        if refunded.is_positive() && transaction_type != "expense" {
+            return Err(ApiResponse::error(
+                "An expense with refunds can't change its transaction type",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        if refunded > amount {
+            return Err(ApiResponse::error(
+                "Amount can't be less than what has already been refunded",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+    }
+
+    if existing_expense.split_method.is_some() && transaction_type != "expense" {
+        return Err(ApiResponse::error(
+            "A split expense can't change its transaction type, remove the split first",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if transaction_type == "refund" {
+        let Some(original_id) = original_expense_id else {
+            return Err(ApiResponse::error(
+                "A refund needs the originalExpenseId it refunds",
+                StatusCode::BAD_REQUEST,
+            ));
+        };
+        if original_id == expense_id {
+            return Err(ApiResponse::error(
+                "An expense can't refund itself",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        let original = get_refundable_expense(&mut *conn, user_id, original_id).await?;
+        ensure_same_workspace("Original expense", original.household_id, household_id)?;
+        validate_refund(&mut *conn, &original, amount, &currency, Some(expense_id)).await?;
+    }
+
     let name = body.name.unwrap_or(existing_expense.name);
-    let amount = body.amount.unwrap_or(existing_expense.amount);
     let date = body.date.unwrap_or(existing_expense.date);
     let description = body.description.or(existing_expense.description);
     let category_id = body.category_id.or(existing_expense.category_id);
 
-    let updated_expense = sqlx::query_as::<_, ExpenseModel>(
-        "UPDATE expenses SET name = $1, amount = $2, date = $3, description = $4, category_id = $5, budget_id = $6
-         WHERE expense_id = $7 AND user_id = $8 
-         RETURNING *",
+    sqlx::query(
+        "UPDATE expenses SET name = $1, amount = $2, currency = $3, date = $4, description = $5, category_id = $6, budget_id = $7,
+            transaction_type = $8, original_expense_id = $9, account_id = $10, paid_by = $11
+         WHERE expense_id = $12",
     )
     .bind(name)
     .bind(amount)
+    .bind(currency)
     .bind(date)
     .bind(description)
     .bind(category_id)
     .bind(budget_id)
+    .bind(transaction_type)
+    .bind(original_expense_id)
+    .bind(account_id)
+    .bind(paid_by)
+    .bind(expense_id)
+    .execute(&mut *conn)
+    .await?;
+
+    if let Some(tags) = tags {
+        set_expense_tags(&mut *conn, user_id, expense_id, &tags).await?;
+    }
+    if amount != existing_expense.amount {
+        resplit_expense(&mut *conn, expense_id, amount).await?;
+    }
+    Ok(fetch_expense(conn, expense_id).await?)
+}
+
+/// Splits an expense between people, replacing any previous split. Every
+/// participant other than whoever paid then owes their share to the payer.
+#[utoipa::path(
+    put,
+    path = "/expense/{id}/split",
+    tag = "split",
+    params(("id" = String, Path, description = "Expense id")),
+    request_body = SetExpenseSplitSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `method`, `paidBy`, `currency`, `splits`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn set_expense_split(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SetExpenseSplitSchema>,
+) -> ApiResult<serde_json::Value> {
+    let expense_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    // Locked so the amount and currency the shares are computed from can't
+    // change before they are written
+    let expense = sqlx::query_as::<_, ExpenseModel>(
+        "SELECT * FROM expenses
+         WHERE expense_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)
+         FOR UPDATE",
+    )
     .bind(expense_id)
     .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| {
+        ApiResponse::error(
+            "Expense not found or you don't have permission to update it",
+            StatusCode::NOT_FOUND,
+        )
+    })?;
+    require_edit_access(&mut *tx, user_id, expense.household_id).await?;
+
+    if expense.transaction_type != "expense" {
+        return Err(ApiResponse::error(
+            "Only expenses can be split",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let method = validate_split_method(&body.method)?;
+    if body.participants.is_empty() {
+        return Err(ApiResponse::error(
+            "A split needs at least one participant",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let mut participant_user_ids: Vec<Option<Uuid>> = Vec::with_capacity(body.participants.len());
+    let mut participant_contact_ids: Vec<Option<Uuid>> =
+        Vec::with_capacity(body.participants.len());
+    let mut weights: Vec<i64> = Vec::with_capacity(body.participants.len());
+
+    for participant in &body.participants {
+        let (participant_user_id, contact_id) = resolve_split_party(
+            &mut *tx,
+            user_id,
+            expense.household_id,
+            participant.user_id.as_deref(),
+            participant.contact_id.as_deref(),
+        )
+        .await?;
+
+        if (participant_user_id.is_some() && participant_user_ids.contains(&participant_user_id))
+            || (contact_id.is_some() && participant_contact_ids.contains(&contact_id))
+        {
+            return Err(ApiResponse::error(
+                "Each participant can only appear once in a split",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        participant_user_ids.push(participant_user_id);
+        participant_contact_ids.push(contact_id);
+        weights.push(participant_weight(&method, participant, &expense.currency)?);
+    }
+
+    let amounts: Vec<i64> = split_amounts(&method, expense.amount, &weights)?
+        .into_iter()
+        .map(|amount| amount.minor_units())
+        .collect();
+
+    sqlx::query("DELETE FROM expense_splits WHERE expense_id = $1")
+        .bind(expense_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO expense_splits (expense_id, user_id, contact_id, position, weight, amount)
+         SELECT $1, s.user_id, s.contact_id, s.position, s.weight, s.amount
+         FROM UNNEST($2::UUID[], $3::UUID[], $4::BIGINT[], $5::BIGINT[])
+            WITH ORDINALITY AS s(user_id, contact_id, weight, amount, position)",
+    )
+    .bind(expense_id)
+    .bind(&participant_user_ids)
+    .bind(&participant_contact_ids)
+    .bind(&weights)
+    .bind(&amounts)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE expenses SET split_method = $1 WHERE expense_id = $2")
+        .bind(&method)
+        .bind(expense_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let splits = fetch_expense_splits(&mut tx, expense_id).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "method": method,
+        "paidBy": expense.paid_by.unwrap_or(expense.user_id),
+        "currency": expense.currency,
+        "splits": splits
+    })))
+}
+
+/// Updates the user's name and/or base currency. Changing the base currency only
+/// affects how amounts are converted; stored amounts keep their own currency.
+#[utoipa::path(
+    patch,
+    path = "/profile",
+    tag = "user",
+    request_body = UpdateProfileSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `user`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_profile(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateProfileSchema>,
+) -> ApiResult<serde_json::Value> {
+    if let Some(ref name) = body.name {
+        validate_name(name)?;
+    }
+
+    let base_currency = match &body.base_currency {
+        Some(currency) => Some(validate_currency(currency)?),
+        None => None,
+    };
+
+    let user = sqlx::query_as::<_, UserModel>(
+        "UPDATE users SET name = COALESCE($1, name), base_currency = COALESCE($2, base_currency)
+         WHERE user_id = $3
+         RETURNING *",
+    )
+    .bind(body.name)
+    .bind(base_currency)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "user": {
+            "userId": user.user_id,
+            "name": user.name,
+            "email": user.email,
+            "baseCurrency": user.base_currency,
+            "createdAt": user.created_at
+        }
+    })))
+}
+
+/// Updates an account's details. The currency can't change because existing
+/// transactions and the opening balance are stored in it.
+#[utoipa::path(
+    put,
+    path = "/account/{id}",
+    tag = "account",
+    params(("id" = String, Path, description = "Account id")),
+    request_body = UpdateAccountSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `account`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_account(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateAccountSchema>,
+) -> ApiResult<serde_json::Value> {
+    let account_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid account ID format", StatusCode::BAD_REQUEST))?;
+    let existing_account = get_account(&state.db, user_id, account_id).await?;
+
+    if let Some(ref name) = body.name {
+        validate_name(name)?;
+
+        let duplicate: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM accounts WHERE user_id = $1 AND name = $2 AND account_id <> $3)",
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(account_id)
+        .fetch_one(&state.db)
+        .await?;
+
+        if duplicate {
+            return Err(ApiResponse::error(
+                "Account with this name already exists",
+                StatusCode::CONFLICT,
+            ));
+        }
+    }
+
+    let account_type = match &body.account_type {
+        Some(account_type) => validate_account_type(account_type)?,
+        None => existing_account.account_type,
+    };
+
+    let opening_balance = match &body.opening_balance {
+        Some(amount) => parse_amount(amount, &existing_account.currency)?,
+        None => existing_account.opening_balance,
+    };
+
+    let name = body.name.unwrap_or(existing_account.name);
+    let opening_date = body.opening_date.unwrap_or(existing_account.opening_date);
+
+    let updated_account = sqlx::query_as::<_, AccountModel>(
+        "UPDATE accounts SET name = $1, account_type = $2, opening_balance = $3, opening_date = $4
+         WHERE account_id = $5 AND user_id = $6
+         RETURNING *, account_balance(account_id, NULL) AS balance",
+    )
+    .bind(name)
+    .bind(account_type)
+    .bind(opening_balance)
+    .bind(opening_date)
+    .bind(account_id)
+    .bind(user_id)
     .fetch_one(&state.db)
     .await?;
 
     Ok(ApiResponse::success(json!({
-        "expense": updated_expense
+        "account": updated_account
+    })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/contact/{id}",
+    tag = "split",
+    summary = "Update contact",
+    params(("id" = String, Path, description = "Contact id")),
+    request_body = UpdateContactSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `contact`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_contact(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateContactSchema>,
+) -> ApiResult<serde_json::Value> {
+    let contact_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid contact ID format", StatusCode::BAD_REQUEST))?;
+
+    if let Some(ref name) = body.name {
+        validate_name(name)?;
+
+        let duplicate: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM contacts WHERE user_id = $1 AND name = $2 AND contact_id <> $3)",
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(contact_id)
+        .fetch_one(&state.db)
+        .await?;
+
+        if duplicate {
+            return Err(ApiResponse::error(
+                "Contact with this name already exists",
+                StatusCode::CONFLICT,
+            ));
+        }
+    }
+
+    if let Some(ref email) = body.email {
+        validate_email(email)?;
+    }
+
+    let updated_contact = sqlx::query_as::<_, ContactModel>(
+        "UPDATE contacts SET name = COALESCE($1, name), email = COALESCE($2, email)
+         WHERE contact_id = $3 AND user_id = $4 RETURNING *",
+    )
+    .bind(body.name)
+    .bind(body.email.map(|email| email.trim().to_lowercase()))
+    .bind(contact_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match updated_contact {
+        Some(contact) => Ok(ApiResponse::success(json!({
+            "contact": contact
+        }))),
+        None => Err(ApiResponse::error(
+            "Contact not found or you don't have permission to update it",
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+/// Updates a goal. The currency can't change because contributions are stored in it.
+#[utoipa::path(
+    put,
+    path = "/goal/{id}",
+    tag = "goal",
+    params(("id" = String, Path, description = "Goal id")),
+    request_body = UpdateGoalSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `goal`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_goal(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateGoalSchema>,
+) -> ApiResult<serde_json::Value> {
+    let goal_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid goal ID format", StatusCode::BAD_REQUEST))?;
+
+    let mut tx = state.db.begin().await?;
+    let existing_goal = get_goal(&mut tx, user_id, goal_id).await?;
+
+    if let Some(ref name) = body.name {
+        validate_name(name)?;
+
+        let duplicate: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM savings_goals WHERE user_id = $1 AND name = $2 AND goal_id <> $3)",
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(goal_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if duplicate {
+            return Err(ApiResponse::error(
+                "Savings goal with this name already exists",
+                StatusCode::CONFLICT,
+            ));
+        }
+    }
+
+    let target_amount = match &body.target_amount {
+        Some(amount) => parse_amount(amount, &existing_goal.currency)?,
+        None => existing_goal.target_amount,
+    };
+    if !target_amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Target amount must be greater than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let account_id = match &body.account_id {
+        Some(account_id) => Some(
+            validate_goal_account(&state.db, user_id, account_id, &existing_goal.currency).await?,
+        ),
+        None => existing_goal.account_id,
+    };
+
+    sqlx::query(
+        "UPDATE savings_goals SET name = $1, target_amount = $2, target_date = $3, account_id = $4
+         WHERE goal_id = $5",
+    )
+    .bind(body.name.unwrap_or(existing_goal.name))
+    .bind(target_amount)
+    .bind(body.target_date.or(existing_goal.target_date))
+    .bind(account_id)
+    .bind(goal_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // A lower target can reach a milestone, a higher one can fall back below it
+    let goal = get_goal(&mut tx, user_id, goal_id).await?;
+    sync_goal_milestone(&mut tx, &goal).await?;
+    let updated_goal = get_goal(&mut tx, user_id, goal_id).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "goal": updated_goal
+    })))
+}
+
+/// Updates a bill. Changing the due date or recurrence counts later occurrences
+/// from the new due date; a paid one-off bill becomes active again with a new
+/// due date.
+#[utoipa::path(
+    put,
+    path = "/bill/{id}",
+    tag = "bill",
+    params(("id" = String, Path, description = "Bill id")),
+    request_body = UpdateBillSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `bill`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_bill(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateBillSchema>,
+) -> ApiResult<serde_json::Value> {
+    let bill_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid bill ID format", StatusCode::BAD_REQUEST))?;
+    let existing_bill = get_bill(&state.db, user_id, bill_id).await?;
+
+    if let Some(ref name) = body.name {
+        validate_name(name)?;
+    }
+
+    let amount = match &body.amount {
+        Some(amount) => parse_amount(amount, &existing_bill.currency)?,
+        None => existing_bill.amount,
+    };
+    if !amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let recurrence = match &body.recurrence {
+        Some(recurrence) => validate_recurrence(recurrence)?,
+        None => existing_bill.recurrence.clone(),
+    };
+
+    let remind_days_before = body
+        .remind_days_before
+        .unwrap_or(existing_bill.remind_days_before);
+    validate_remind_days(remind_days_before)?;
+
+    if let Some(category_id) = body.category_id {
+        get_workspace_category(&state.db, user_id, None, category_id).await?;
+    }
+
+    let account_id = match &body.account_id {
+        Some(account_id) => Some(
+            get_account(&state.db, user_id, Uuid::parse_str(account_id)?)
+                .await?
+                .account_id,
+        ),
+        None => existing_bill.account_id,
+    };
+
+    let due_date = body.due_date.unwrap_or(existing_bill.due_date);
+    let (start_date, is_active) =
+        if body.due_date.is_some() || recurrence != existing_bill.recurrence {
+            (due_date, existing_bill.is_active || body.due_date.is_some())
+        } else {
+            (existing_bill.start_date, existing_bill.is_active)
+        };
+
+    sqlx::query(
+        "UPDATE bills SET name = $1, amount = $2, start_date = $3, due_date = $4, recurrence = $5,
+            autopay = $6, remind_days_before = $7, category_id = $8, account_id = $9, is_active = $10
+         WHERE bill_id = $11 AND user_id = $12",
+    )
+    .bind(body.name.unwrap_or(existing_bill.name))
+    .bind(amount)
+    .bind(start_date)
+    .bind(due_date)
+    .bind(recurrence)
+    .bind(body.autopay.unwrap_or(existing_bill.autopay))
+    .bind(remind_days_before)
+    .bind(body.category_id.or(existing_bill.category_id))
+    .bind(account_id)
+    .bind(is_active)
+    .bind(bill_id)
+    .bind(user_id)
+    .execute(&state.db)
+    .await?;
+
+    let updated_bill = get_bill(&state.db, user_id, bill_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "bill": updated_bill
     })))
 }
 
+/// Updates a budget, conditionally on `If-Match` like `update_expense`.
+#[utoipa::path(
+    put,
+    path = "/budget/{id}",
+    tag = "budget",
+    params(
+        ("id" = String, Path, description = "Budget id"),
+        ("If-Match" = Option<String>, Header, description = "Only apply while the `ETag` still matches"),
+    ),
+    request_body = UpdateBudgetSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `budget`", body = ApiResponse),
+        (status = 412, description = "The resource changed since the given `ETag`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn update_budget(
     Path(id): Path<String>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(body): Json<UpdateBudgetSchema>,
-) -> ApiResult<serde_json::Value> {
+) -> Result<Response, ApiResponse<serde_json::Value>> {
     let budget_id = Uuid::parse_str(&id)
         .map_err(|_| ApiResponse::error("Invalid budget ID format", StatusCode::BAD_REQUEST))?;
 
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
     let existing_budget = sqlx::query_as::<_, BudgetModel>(
-        "SELECT * FROM budgets WHERE budget_id = $1 AND user_id = $2",
+        "SELECT * FROM budgets
+         WHERE budget_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)
+         FOR UPDATE",
     )
     .bind(budget_id)
     .bind(user_id)
-    .fetch_optional(&state.db)
+    .fetch_optional(&mut *tx)
     .await?;
 
     if existing_budget.is_none() {
@@ -111,24 +834,38 @@ pub async fn update_budget(
     }
 
     let existing_budget = existing_budget.unwrap();
+    require_edit_access(&mut *tx, user_id, existing_budget.household_id).await?;
+    check_if_match(&headers, &etag(existing_budget.updated_at))?;
 
     if let Some(ref name) = body.name {
         validate_name(name)?;
     }
 
-    if let Some(amount) = body.amount {
-        if amount <= 0 {
-            return Err(ApiResponse::error(
-                "Amount can't be less than zero",
-                StatusCode::BAD_REQUEST,
-            ));
-        }
+    if let Some(Some(category_id)) = body.category_id {
+        get_workspace_category(&mut *tx, user_id, existing_budget.household_id, category_id)
+            .await?;
+    }
+
+    let currency = match &body.currency {
+        Some(currency) => validate_currency(currency)?,
+        None => existing_budget.currency.clone(),
+    };
+
+    let amount = match &body.amount {
+        Some(amount) => parse_amount(amount, &currency)?,
+        None => rescale_amount(existing_budget.amount, &existing_budget.currency, &currency)?,
+    };
+    if !amount.is_positive() {
+        return Err(ApiResponse::error(
+            "Amount can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
     }
 
     let name = body.name.unwrap_or(existing_budget.name);
-    let amount = body.amount.unwrap_or(existing_budget.amount);
     let start_date = body.start_date.unwrap_or(existing_budget.start_date);
     let end_date = body.end_date.unwrap_or(existing_budget.end_date);
+    let category_id = body.category_id.unwrap_or(existing_budget.category_id);
 
     if end_date <= start_date {
         return Err(ApiResponse::error(
@@ -138,20 +875,576 @@ pub async fn update_budget(
     }
 
     let updated_budget = sqlx::query_as::<_, BudgetModel>(
-        "UPDATE budgets SET name = $1, amount = $2, start_date = $3, end_date = $4 
-         WHERE budget_id = $5 AND user_id = $6 
+        "UPDATE budgets SET name = $1, amount = $2, currency = $3, start_date = $4, end_date = $5, category_id = $6
+         WHERE budget_id = $7
          RETURNING *",
     )
     .bind(name)
     .bind(amount)
+    .bind(currency)
     .bind(start_date)
     .bind(end_date)
+    .bind(category_id)
     .bind(budget_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(with_etag(
+        ApiResponse::success(json!({
+            "budget": updated_budget
+        })),
+        &etag(updated_budget.updated_at),
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/tag/{id}",
+    tag = "tag",
+    summary = "Update tag",
+    params(("id" = String, Path, description = "Tag id")),
+    request_body = UpdateTagSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `tag`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_tag(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateTagSchema>,
+) -> ApiResult<serde_json::Value> {
+    let tag_id: i32 = id
+        .parse()
+        .map_err(|_| ApiResponse::error("Invalid tag ID format", StatusCode::BAD_REQUEST))?;
+
+    let name = normalize_tag(&body.name)?;
+
+    let duplicate: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM tags WHERE user_id = $1 AND name = $2 AND tag_id <> $3)",
+    )
     .bind(user_id)
+    .bind(&name)
+    .bind(tag_id)
     .fetch_one(&state.db)
     .await?;
 
+    if duplicate {
+        return Err(ApiResponse::error(
+            "Tag with this name already exists",
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let updated_tag = sqlx::query_as::<_, TagModel>(
+        "UPDATE tags SET name = $1 WHERE tag_id = $2 AND user_id = $3 RETURNING *",
+    )
+    .bind(name)
+    .bind(tag_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match updated_tag {
+        Some(tag) => Ok(ApiResponse::success(json!({
+            "tag": tag
+        }))),
+        None => Err(ApiResponse::error(
+            "Tag not found or you don't have permission to update it",
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/category/{id}",
+    tag = "category",
+    summary = "Update category",
+    params(("id" = String, Path, description = "Category id")),
+    request_body = UpdateCategorySchema,
+    responses(
+        (status = 200, description = "Success; `data` has `category`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_category(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateCategorySchema>,
+) -> ApiResult<serde_json::Value> {
+    let category_id: i32 = id
+        .parse()
+        .map_err(|_| ApiResponse::error("Invalid category ID format", StatusCode::BAD_REQUEST))?;
+
+    let existing_category = get_editable_category(&state.db, user_id, category_id).await?;
+
+    if let Some(ref category_name) = body.category_name {
+        validate_category_name(category_name)?;
+
+        let duplicate: bool = sqlx::query_scalar(
+            "SELECT EXISTS(
+                SELECT 1 FROM categories
+                WHERE in_workspace(user_id, household_id, $1, $4) AND category_name = $2 AND category_id <> $3
+                    AND deleted_at IS NULL
+            )",
+        )
+        .bind(user_id)
+        .bind(category_name)
+        .bind(category_id)
+        .bind(existing_category.household_id)
+        .fetch_one(&state.db)
+        .await?;
+
+        if duplicate {
+            return Err(ApiResponse::error(
+                "Category with this name already exists",
+                StatusCode::CONFLICT,
+            ));
+        }
+    }
+
+    validate_category_style(body.icon.as_deref(), body.color.as_deref())?;
+
+    let category_name = body
+        .category_name
+        .unwrap_or(existing_category.category_name);
+    let icon = body.icon.or(existing_category.icon);
+    let color = body.color.or(existing_category.color);
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    let updated_category = sqlx::query_as::<_, CategoryModel>(
+        "UPDATE categories SET category_name = $1, icon = $2, color = $3
+         WHERE category_id = $4
+         RETURNING *",
+    )
+    .bind(category_name)
+    .bind(icon)
+    .bind(color)
+    .bind(category_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "category": updated_category
+    })))
+}
+
+/// Moves everything in the source category to the target and deletes the source:
+/// expenses, category budgets, rules and bills are reassigned and its
+/// subcategories re-parented under the target, all in one transaction.
+#[utoipa::path(
+    post,
+    path = "/category/{id}/merge-into/{target}",
+    tag = "category",
+    params(
+        ("id" = String, Path, description = "Category id"),
+        ("target" = String, Path, description = "Id of the category to merge into"),
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `category`, `movedExpenses`, `movedBudgets`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn merge_category(
+    Path((id, target)): Path<(String, String)>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let source_id: i32 = id
+        .parse()
+        .map_err(|_| ApiResponse::error("Invalid category ID format", StatusCode::BAD_REQUEST))?;
+    let target_id: i32 = target.parse().map_err(|_| {
+        ApiResponse::error("Invalid target category ID format", StatusCode::BAD_REQUEST)
+    })?;
+
+    let source_category = get_editable_category(&state.db, user_id, source_id).await?;
+
+    let target_category =
+        get_workspace_category(&state.db, user_id, source_category.household_id, target_id)
+            .await
+            .map_err(|_| ApiResponse::error("Target category not found", StatusCode::NOT_FOUND))?;
+
+    if is_in_subtree(&state.db, source_id, target_id).await? {
+        return Err(ApiResponse::error(
+            "A category can't be merged into itself or one of its subcategories",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    let (moved_expenses, moved_budgets) =
+        merge_category_into(&mut tx, source_id, target_id).await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "category": target_category,
+        "movedExpenses": moved_expenses,
+        "movedBudgets": moved_budgets
+    })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/category/{id}/parent",
+    tag = "category",
+    summary = "Move category",
+    params(("id" = String, Path, description = "Category id")),
+    request_body = MoveCategorySchema,
+    responses(
+        (status = 200, description = "Success; `data` has `category`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn move_category(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<MoveCategorySchema>,
+) -> ApiResult<serde_json::Value> {
+    let category_id: i32 = id
+        .parse()
+        .map_err(|_| ApiResponse::error("Invalid category ID format", StatusCode::BAD_REQUEST))?;
+
+    let category = get_editable_category(&state.db, user_id, category_id).await?;
+
+    if let Some(parent_id) = body.parent_id {
+        validate_category_parent(
+            &state.db,
+            user_id,
+            category.household_id,
+            Some(category_id),
+            parent_id,
+        )
+        .await?;
+    }
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    let updated_category = sqlx::query_as::<_, CategoryModel>(
+        "UPDATE categories SET parent_id = $1 WHERE category_id = $2 RETURNING *",
+    )
+    .bind(body.parent_id)
+    .bind(category_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(ApiResponse::success(json!({
+        "category": updated_category
+    })))
+}
+
+/// Per-user settings for a global category, e.g. hiding one the user never uses.
+#[utoipa::path(
+    put,
+    path = "/category/{id}/override",
+    tag = "category",
+    params(("id" = String, Path, description = "Category id")),
+    request_body = CategoryOverrideSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `category`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn set_category_override(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<CategoryOverrideSchema>,
+) -> ApiResult<serde_json::Value> {
+    let category_id: i32 = id
+        .parse()
+        .map_err(|_| ApiResponse::error("Invalid category ID format", StatusCode::BAD_REQUEST))?;
+
+    let category = get_visible_category(&state.db, user_id, category_id).await?;
+
+    if category.user_id.is_some() {
+        return Err(ApiResponse::error(
+            "Only global categories can be overridden, update your own category instead",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    sqlx::query(
+        "INSERT INTO user_category_overrides (user_id, category_id, is_hidden) VALUES ($1, $2, $3)
+         ON CONFLICT (user_id, category_id) DO UPDATE SET is_hidden = EXCLUDED.is_hidden",
+    )
+    .bind(user_id)
+    .bind(category_id)
+    .bind(body.hidden)
+    .execute(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "category": CategoryModel {
+            hidden: body.hidden,
+            ..category
+        }
+    })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/rule/{id}",
+    tag = "rule",
+    summary = "Update rule",
+    params(("id" = String, Path, description = "Rule id")),
+    request_body = UpdateRuleSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `rule`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_rule(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateRuleSchema>,
+) -> ApiResult<serde_json::Value> {
+    let rule_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid rule ID format", StatusCode::BAD_REQUEST))?;
+
+    let existing_rule = sqlx::query_as::<_, ExpenseRuleModel>(
+        "SELECT * FROM expense_rules WHERE rule_id = $1 AND user_id = $2",
+    )
+    .bind(rule_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    if existing_rule.is_none() {
+        return Err(ApiResponse::error(
+            "Rule not found or you don't have permission to update it",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let existing_rule = existing_rule.unwrap();
+
+    if let Some(ref name) = body.name {
+        validate_name(name)?;
+    }
+
+    let budget_id = match &body.budget_id {
+        Some(b) => Some(Uuid::parse_str(b)?),
+        None => existing_rule.budget_id,
+    };
+
+    let tags = match &body.tags {
+        Some(tags) => normalize_tags(tags)?,
+        None => existing_rule.tags,
+    };
+
+    let name = body.name.unwrap_or(existing_rule.name);
+    let priority = body.priority.unwrap_or(existing_rule.priority);
+    let is_active = body.is_active.unwrap_or(existing_rule.is_active);
+    let name_contains = body.name_contains.or(existing_rule.name_contains);
+    let name_regex = body.name_regex.or(existing_rule.name_regex);
+    let base_currency = get_user_base_currency(&state.db, user_id).await?;
+    let min_amount = match &body.min_amount {
+        Some(amount) => Some(parse_amount(amount, &base_currency)?),
+        None => existing_rule.min_amount,
+    };
+    let max_amount = match &body.max_amount {
+        Some(amount) => Some(parse_amount(amount, &base_currency)?),
+        None => existing_rule.max_amount,
+    };
+    let category_id = body.category_id.or(existing_rule.category_id);
+
+    validate_rule_conditions(
+        name_contains.as_deref(),
+        name_regex.as_deref(),
+        min_amount,
+        max_amount,
+    )?;
+    validate_rule_actions(&state.db, user_id, category_id, &tags, budget_id).await?;
+
+    let updated_rule = sqlx::query_as::<_, ExpenseRuleModel>(
+        "UPDATE expense_rules SET name = $1, priority = $2, is_active = $3, name_contains = $4, name_regex = $5,
+            min_amount = $6, max_amount = $7, category_id = $8, tags = $9, budget_id = $10
+         WHERE rule_id = $11 AND user_id = $12
+         RETURNING *",
+    )
+    .bind(name)
+    .bind(priority)
+    .bind(is_active)
+    .bind(name_contains)
+    .bind(name_regex)
+    .bind(min_amount)
+    .bind(max_amount)
+    .bind(category_id)
+    .bind(tags)
+    .bind(budget_id)
+    .bind(rule_id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "rule": updated_rule
+    })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/household/{id}",
+    tag = "household",
+    summary = "Update household",
+    params(("id" = String, Path, description = "Household id")),
+    request_body = UpdateHouseholdSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `household`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_household(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateHouseholdSchema>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid household ID format", StatusCode::BAD_REQUEST))?;
+    require_household_role(&state.db, household_id, user_id, &["owner"]).await?;
+    validate_name(&body.name)?;
+
+    sqlx::query("UPDATE households SET name = $1 WHERE household_id = $2")
+        .bind(body.name.trim())
+        .bind(household_id)
+        .execute(&state.db)
+        .await?;
+
+    let household = get_household(&state.db, household_id, user_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "household": household
+    })))
+}
+
+/// Changes a member's role. Only owners can do this, and the last owner can't
+/// step down.
+#[utoipa::path(
+    put,
+    path = "/household/{id}/member/{member}",
+    tag = "household",
+    params(
+        ("id" = String, Path, description = "Household id"),
+        ("member" = String, Path, description = "User id of the member"),
+    ),
+    request_body = UpdateMemberSchema,
+    responses(
+        (status = 200, description = "Success; `data` has `member`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn update_member(
+    Path((id, member)): Path<(String, String)>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<UpdateMemberSchema>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid household ID format", StatusCode::BAD_REQUEST))?;
+    let member_id = Uuid::parse_str(&member)
+        .map_err(|_| ApiResponse::error("Invalid member ID format", StatusCode::BAD_REQUEST))?;
+    let role = validate_household_role(&body.role)?;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+    lock_household_members(&mut *tx, household_id).await?;
+    require_household_role(&mut *tx, household_id, user_id, &["owner"]).await?;
+
+    if role != "owner" && !has_other_owner(&mut *tx, household_id, member_id).await? {
+        return Err(ApiResponse::error(
+            "A household needs at least one owner, make another member owner first",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let updated_member = sqlx::query_as::<_, HouseholdMemberModel>(
+        "UPDATE household_members m SET role = $1
+         FROM users u
+         WHERE m.household_id = $2 AND m.user_id = $3 AND u.user_id = m.user_id
+         RETURNING m.household_id, m.user_id, u.name, u.email, m.role, m.joined_at",
+    )
+    .bind(role)
+    .bind(household_id)
+    .bind(member_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    match updated_member {
+        Some(member) => Ok(ApiResponse::success(json!({
+            "member": member
+        }))),
+        None => Err(ApiResponse::error(
+            "Member not found",
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+/// Restores an expense, budget or category from the trash, along with whatever
+/// was trashed together with it (refunds, subcategories).
+#[utoipa::path(
+    post,
+    path = "/trash/{type}/{id}/restore",
+    tag = "trash",
+    params(
+        ("type" = String, Path, description = "`expense`, `budget` or `category`"),
+        ("id" = String, Path, description = "{type} id"),
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `message`, `restored`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn restore_from_trash(
+    Path((kind, id)): Path<(String, String)>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let kind = validate_trash_type(&kind)?;
+
+    let mut tx = begin_audited(&state.db, user_id).await?;
+
+    let restored = match kind {
+        "expense" => {
+            let expense_id = Uuid::parse_str(&id).map_err(|_| {
+                ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST)
+            })?;
+            restore_expense(&mut tx, user_id, expense_id).await?
+        }
+        "budget" => {
+            let budget_id = Uuid::parse_str(&id).map_err(|_| {
+                ApiResponse::error("Invalid budget ID format", StatusCode::BAD_REQUEST)
+            })?;
+            restore_budget(&mut tx, user_id, budget_id).await?
+        }
+        _ => {
+            let category_id: i32 = id.parse().map_err(|_| {
+                ApiResponse::error("Invalid category ID format", StatusCode::BAD_REQUEST)
+            })?;
+            restore_category(&mut tx, user_id, category_id).await?
+        }
+    };
+
+    tx.commit().await?;
+
     Ok(ApiResponse::success(json!({
-        "budget": updated_budget
+        "message": format!("Restored {} {}", restored, if restored == 1 { "item" } else { "items" }),
+        "restored": restored
     })))
 }
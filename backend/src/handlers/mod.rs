@@ -3,4 +3,4 @@ pub mod commands;
 pub use commands::*;
 
 pub mod queries;
-pub use queries::*;
\ No newline at end of file
+pub use queries::*;
@@ -1,147 +1,1376 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use axum::{
     Extension, Json,
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
 };
 use serde_json::json;
+use sqlx::{Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::{
     AppState,
-    models::{BudgetModel, BudgetWithSpentModel, CategoryModel, ExpenseModel},
+    models::{
+        AccountEntryModel, AccountModel, AttachmentModel, AuditLogModel, BillModel,
+        BillPaymentModel, BudgetModel, BudgetWithSpentModel, CashFlowModel, CategoryModel,
+        CategorySuggestionModel, CategoryTotalModel, ContactModel, ExchangeRateModel, ExpenseModel,
+        ExpenseRuleModel, GoalContributionModel, HouseholdInvitationModel, HouseholdMemberModel,
+        HouseholdModel, Money, ReconciliationModel, SavingsGoalModel, SettlementModel,
+        SplitBalance, TagModel, TagTotalModel, TransferModel, UserModel,
+    },
     ok_or_err,
-    routes::expense::Params,
+    routes::{
+        audit::AuditParams,
+        bill::UpcomingBillsParams,
+        budget::BudgetParams,
+        category::{CategoryParams, SuggestParams},
+        exchange_rate::ExchangeRateParams,
+        expense::Params,
+        report::ReportParams,
+        split::SplitParams,
+        trash::TrashParams,
+    },
     schema::{ApiResponse, ApiResult, LoginUserSchema},
     utils::{
-        helper::{get_user_by_email, validate_email},
-        sign, verify_hash_password,
+        account::get_account,
+        audit::{AUDIT_COLUMNS, validate_audit_entity_type},
+        bill::{BILL_COLUMNS, bill_occurrences_until, get_bill},
+        budget::{BUDGET_SPENT_EXPR, BUDGET_UNCONVERTED_EXPR},
+        currency::{
+            EXCHANGE_RATE_COLUMNS, get_user_base_currency, parse_amount, validate_currency,
+        },
+        etag::{conditional_get, etag},
+        expense::{EXPENSE_COLUMNS, count_unconverted_expenses, validate_transaction_type},
+        goal::{GOAL_QUERY, get_goal, with_required_contribution},
+        helper::{get_user_by_email, validate_email, validate_name},
+        household::{get_household, resolve_workspace},
+        rule::find_matching_rule,
+        sign,
+        split::{fetch_expense_splits, get_split_debts, net_pair_balances, simplify_debts},
+        tag::normalize_tags,
+        verify_hash_password,
     },
 };
 
-pub async fn get_all_expenses(
-    Query(param): Query<Params>,
+#[utoipa::path(
+    get,
+    path = "/expense",
+    tag = "expense",
+    summary = "Get all expenses",
+    params(Params),
+    responses(
+        (status = 200, description = "Success", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_expenses(
+    Query(param): Query<Params>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let tags = match param.tags.as_deref() {
+        Some(tags) => {
+            let names: Vec<String> = tags
+                .split(',')
+                .filter(|t| !t.trim().is_empty())
+                .map(String::from)
+                .collect();
+            normalize_tags(&names)?
+        }
+        None => Vec::new(),
+    };
+
+    let match_all = match param.tag_match.as_deref() {
+        None | Some("any") => false,
+        Some("all") => true,
+        Some(_) => {
+            return Err(ApiResponse::error(
+                "tagMatch must be either 'any' or 'all'",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+    };
+
+    let transaction_type = match param.transaction_type.as_deref() {
+        Some(transaction_type) => Some(validate_transaction_type(transaction_type)?),
+        None => None,
+    };
+
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+
+    let push_filters = |query: &mut QueryBuilder<Postgres>| {
+        query
+            .push(" WHERE in_workspace(e.user_id, e.household_id, ")
+            .push_bind(user_id)
+            .push(", ")
+            .push_bind(household_id)
+            .push(") AND e.deleted_at IS NULL");
+
+        if let Some(ref transaction_type) = transaction_type {
+            query
+                .push(" AND e.transaction_type = ")
+                .push_bind(transaction_type.clone());
+        }
+
+        if tags.is_empty() {
+            return;
+        }
+
+        let tagged = " FROM expense_tags et
+            JOIN tags t ON t.tag_id = et.tag_id
+            WHERE et.expense_id = e.expense_id AND t.name = ANY(";
+
+        if match_all {
+            query
+                .push(" AND (SELECT COUNT(*)")
+                .push(tagged)
+                .push_bind(tags.clone())
+                .push(")) = ")
+                .push_bind(tags.len() as i64);
+        } else {
+            query
+                .push(" AND EXISTS (SELECT 1")
+                .push(tagged)
+                .push_bind(tags.clone())
+                .push("))");
+        }
+    };
+
+    let mut count_query = QueryBuilder::new("SELECT COUNT(*) FROM expenses e");
+    push_filters(&mut count_query);
+    let total_count: i64 = count_query
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await?;
+
+    let mut expenses_query =
+        QueryBuilder::new(format!("SELECT {} FROM expenses e", EXPENSE_COLUMNS));
+    push_filters(&mut expenses_query);
+    expenses_query.push(" ORDER BY e.date DESC");
+
+    if let (Some(limit), Some(offset)) = (param.limit, param.offset) {
+        expenses_query
+            .push(" LIMIT ")
+            .push_bind(limit as i64)
+            .push(" OFFSET ")
+            .push_bind(offset as i64);
+    }
+
+    let all_expenses = expenses_query
+        .build_query_as::<ExpenseModel>()
+        .fetch_all(&state.db)
+        .await?;
+
+    let response = if param.limit.is_some() || param.offset.is_some() {
+        json!({
+            "expenses": all_expenses,
+            "pagination": {
+                "total": total_count,
+                "limit": param.limit,
+                "offset": param.offset,
+                "count": all_expenses.len()
+            }
+        })
+    } else {
+        json!({
+            "expenses": all_expenses,
+            "total": total_count
+        })
+    };
+
+    Ok(ApiResponse::success(response))
+}
+
+/// Fetches an expense with its `ETag`; answers 304 when `If-None-Match` names
+/// the current version.
+#[utoipa::path(
+    get,
+    path = "/expense/{id}",
+    tag = "expense",
+    params(
+        ("id" = String, Path, description = "Expense id"),
+        ("If-None-Match" = Option<String>, Header, description = "`ETag` of the copy the client has"),
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `expense`", body = ApiResponse),
+        (status = 304, description = "The client's copy is current"),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_expense_by_id(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiResponse<serde_json::Value>> {
+    let expense_id = Uuid::parse_str(&id)?;
+
+    let expense = sqlx::query_as::<_, ExpenseModel>(&format!(
+        "SELECT {} FROM expenses e
+         WHERE e.expense_id = $1 AND e.deleted_at IS NULL
+            AND can_view_resource(e.user_id, e.household_id, $2)",
+        EXPENSE_COLUMNS
+    ))
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    match expense {
+        Some(expense) => Ok(conditional_get(&headers, &etag(expense.updated_at), || {
+            ApiResponse::success(json!({
+                "expense": expense
+            }))
+        })),
+        None => Err(ApiResponse::error(
+            "Expense not found",
+            StatusCode::NOT_FOUND,
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/expenses/budget/{budget_id}",
+    tag = "expense",
+    summary = "Get expenses by budget id",
+    params(("budget_id" = String, Path, description = "Budget id")),
+    responses(
+        (status = 200, description = "Success; `data` has `expenses`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_expenses_by_budget_id(
+    Path(budget_id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let budget_id = Uuid::parse_str(&budget_id)?;
+
+    let expenses = sqlx::query_as::<_, ExpenseModel>(&format!(
+        "SELECT {} FROM expenses e
+         JOIN budgets b ON b.budget_id = e.budget_id
+         WHERE e.budget_id = $1 AND e.deleted_at IS NULL AND b.deleted_at IS NULL
+            AND can_view_resource(b.user_id, b.household_id, $2)",
+        EXPENSE_COLUMNS
+    ))
+    .bind(budget_id)
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "expenses": expenses
+    })))
+}
+
+/// Every recorded change of an expense, oldest first. Also works for expenses
+/// in the trash.
+#[utoipa::path(
+    get,
+    path = "/expense/{id}/history",
+    tag = "expense",
+    params(("id" = String, Path, description = "Expense id")),
+    responses(
+        (status = 200, description = "Success; `data` has `history`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_expense_history(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let expense_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
+
+    let can_view: bool = sqlx::query_scalar(
+        "SELECT EXISTS(
+            SELECT 1 FROM expenses
+            WHERE expense_id = $1 AND can_view_resource(user_id, household_id, $2)
+        )",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    if !can_view {
+        return Err(ApiResponse::error(
+            "Expense not found",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+
+    let history = sqlx::query_as::<_, AuditLogModel>(&format!(
+        "SELECT {} FROM audit_log l
+         WHERE l.entity_type = 'expense' AND l.entity_id = $1
+         ORDER BY l.audit_id",
+        AUDIT_COLUMNS
+    ))
+    .bind(expense_id.to_string())
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "history": history
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/expense/{id}/attachments",
+    tag = "expense",
+    summary = "Get expense attachments",
+    params(("id" = String, Path, description = "Expense id")),
+    responses(
+        (status = 200, description = "Success; `data` has `attachments`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_expense_attachments(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let expense_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
+
+    let attachments = sqlx::query_as::<_, AttachmentModel>(
+        "SELECT a.* FROM attachments a
+         JOIN expenses e ON e.expense_id = a.expense_id
+         WHERE a.expense_id = $1 AND e.deleted_at IS NULL
+            AND can_view_resource(e.user_id, e.household_id, $2)
+         ORDER BY a.created_at",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "attachments": attachments
+    })))
+}
+
+async fn get_attachment(
+    state: &Arc<AppState>,
+    id: &str,
+    user_id: Uuid,
+) -> Result<AttachmentModel, ApiResponse<serde_json::Value>> {
+    let attachment_id = Uuid::parse_str(id)
+        .map_err(|_| ApiResponse::error("Invalid attachment ID format", StatusCode::BAD_REQUEST))?;
+
+    sqlx::query_as::<_, AttachmentModel>(
+        "SELECT a.* FROM attachments a
+         JOIN expenses e ON e.expense_id = a.expense_id
+         WHERE a.attachment_id = $1 AND e.deleted_at IS NULL
+            AND can_view_resource(e.user_id, e.household_id, $2)",
+    )
+    .bind(attachment_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Attachment not found", StatusCode::NOT_FOUND))
+}
+
+#[utoipa::path(
+    get,
+    path = "/attachments/{id}",
+    tag = "expense",
+    summary = "Download attachment",
+    params(("id" = String, Path, description = "Attachment id")),
+    responses(
+        (status = 200, description = "The file, with its content type"),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn download_attachment(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Response, ApiResponse<serde_json::Value>> {
+    let attachment = get_attachment(&state, &id, user_id).await?;
+
+    let data = state
+        .storage
+        .get(&attachment.storage_key)
+        .await
+        .map_err(|e| ApiResponse::error(&e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    let disposition = format!(
+        "inline; filename=\"{}\"",
+        attachment.file_name.replace('"', "")
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/attachments/{id}/thumbnail",
+    tag = "expense",
+    summary = "Download attachment thumbnail",
+    params(("id" = String, Path, description = "Attachment id")),
+    responses(
+        (status = 200, description = "JPEG thumbnail", content_type = "image/jpeg"),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn download_attachment_thumbnail(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Response, ApiResponse<serde_json::Value>> {
+    let attachment = get_attachment(&state, &id, user_id).await?;
+
+    let thumbnail_key = attachment
+        .thumbnail_key
+        .ok_or_else(|| ApiResponse::error("Attachment has no thumbnail", StatusCode::NOT_FOUND))?;
+
+    let data = state
+        .storage
+        .get(&thumbnail_key)
+        .await
+        .map_err(|e| ApiResponse::error(&e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    Ok(([(header::CONTENT_TYPE, "image/jpeg")], data).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/category",
+    tag = "category",
+    summary = "Get all categories",
+    params(CategoryParams),
+    responses(
+        (status = 200, description = "Success; `data` has `categories`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_categories(
+    Query(param): Query<CategoryParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+
+    let all_categories = sqlx::query_as::<_, CategoryModel>(
+        "SELECT c.*, COALESCE(o.is_hidden, FALSE) AS hidden
+        FROM categories c
+        LEFT JOIN user_category_overrides o ON o.category_id = c.category_id AND o.user_id = $1
+        WHERE (c.user_id IS NULL OR in_workspace(c.user_id, c.household_id, $1, $3))
+            AND c.deleted_at IS NULL
+            AND ($2 OR NOT COALESCE(o.is_hidden, FALSE))
+        ORDER BY c.category_id",
+    )
+    .bind(user_id)
+    .bind(param.include_hidden.unwrap_or(false))
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "categories": all_categories
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tag",
+    tag = "tag",
+    summary = "Get all tags",
+    responses(
+        (status = 200, description = "Success; `data` has `tags`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_tags(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_tags =
+        sqlx::query_as::<_, TagModel>("SELECT * FROM tags WHERE user_id = $1 ORDER BY name")
+            .bind(user_id)
+            .fetch_all(&state.db)
+            .await?;
+
+    Ok(ApiResponse::success(json!({
+        "tags": all_tags
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/exchange-rate",
+    tag = "exchange_rate",
+    summary = "Get exchange rates",
+    params(ExchangeRateParams),
+    responses(
+        (status = 200, description = "Success; `data` has `exchangeRates`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_exchange_rates(
+    Query(param): Query<ExchangeRateParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let currency = match &param.currency {
+        Some(currency) => Some(validate_currency(currency)?),
+        None => None,
+    };
+
+    let exchange_rates = sqlx::query_as::<_, ExchangeRateModel>(&format!(
+        "SELECT {} FROM exchange_rates
+        WHERE (user_id = $1 OR ($2 AND user_id IS NULL))
+            AND ($3::TEXT IS NULL OR base_currency = $3 OR quote_currency = $3)
+            AND ($4::DATE IS NULL OR rate_date >= $4)
+            AND ($5::DATE IS NULL OR rate_date <= $5)
+        ORDER BY rate_date DESC, base_currency, quote_currency, user_id NULLS LAST",
+        EXCHANGE_RATE_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(param.include_reference)
+    .bind(currency)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "exchangeRates": exchange_rates
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/profile",
+    tag = "user",
+    summary = "Get profile",
+    responses(
+        (status = 200, description = "Success; `data` has `user`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_profile(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let user = sqlx::query_as::<_, UserModel>("SELECT * FROM users WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(&state.db)
+        .await?;
+
+    Ok(ApiResponse::success(json!({
+        "user": {
+            "userId": user.user_id,
+            "name": user.name,
+            "email": user.email,
+            "baseCurrency": user.base_currency,
+            "createdAt": user.created_at
+        }
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/account",
+    tag = "account",
+    summary = "Get all accounts",
+    responses(
+        (status = 200, description = "Success; `data` has `accounts`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_accounts(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_accounts = sqlx::query_as::<_, AccountModel>(
+        "SELECT a.*, account_balance(a.account_id, NULL) AS balance
+         FROM accounts a WHERE a.user_id = $1 ORDER BY a.name",
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "accounts": all_accounts
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/account/{id}",
+    tag = "account",
+    summary = "Get account by id",
+    params(("id" = String, Path, description = "Account id")),
+    responses(
+        (status = 200, description = "Success; `data` has `account`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_account_by_id(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let account_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid account ID format", StatusCode::BAD_REQUEST))?;
+    let account = get_account(&state.db, user_id, account_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "account": account
+    })))
+}
+
+/// Transactions and transfers of an account in date order, each with the
+/// running balance after it. The running balance always starts from the
+/// opening balance, also when only a date range is returned.
+#[utoipa::path(
+    get,
+    path = "/account/{id}/ledger",
+    tag = "account",
+    params(
+        ("id" = String, Path, description = "Account id"),
+        ReportParams,
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `account`, `entries`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_account_ledger(
+    Path(id): Path<String>,
+    Query(param): Query<ReportParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let account_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid account ID format", StatusCode::BAD_REQUEST))?;
+    let account = get_account(&state.db, user_id, account_id).await?;
+
+    let entries = sqlx::query_as::<_, AccountEntryModel>(
+        "SELECT * FROM (
+            SELECT
+                en.entry_id,
+                en.entry_type,
+                en.date,
+                en.description,
+                en.amount,
+                ($2 + SUM(COALESCE(en.amount, 0)) OVER (
+                    ORDER BY en.date, en.created_at, en.entry_id
+                    ROWS UNBOUNDED PRECEDING
+                ))::BIGINT AS balance,
+                en.created_at
+            FROM account_entries($1) en
+        ) ledger
+        WHERE ($3::DATE IS NULL OR date >= $3)
+            AND ($4::DATE IS NULL OR date <= $4)
+        ORDER BY date, created_at, entry_id",
+    )
+    .bind(account.account_id)
+    .bind(account.opening_balance)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "account": account,
+        "entries": entries
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/account/{id}/reconciliations",
+    tag = "account",
+    summary = "Get account reconciliations",
+    params(("id" = String, Path, description = "Account id")),
+    responses(
+        (status = 200, description = "Success; `data` has `reconciliations`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_account_reconciliations(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let account_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid account ID format", StatusCode::BAD_REQUEST))?;
+    let account = get_account(&state.db, user_id, account_id).await?;
+
+    let reconciliations = sqlx::query_as::<_, ReconciliationModel>(
+        "SELECT *, statement_balance - computed_balance AS difference
+         FROM account_reconciliations
+         WHERE account_id = $1
+         ORDER BY statement_date DESC, created_at DESC",
+    )
+    .bind(account.account_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "reconciliations": reconciliations
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/transfer",
+    tag = "account",
+    summary = "Get all transfers",
+    params(ReportParams),
+    responses(
+        (status = 200, description = "Success; `data` has `transfers`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_transfers(
+    Query(param): Query<ReportParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_transfers = sqlx::query_as::<_, TransferModel>(
+        "SELECT * FROM transfers
+         WHERE user_id = $1
+            AND ($2::DATE IS NULL OR date >= $2)
+            AND ($3::DATE IS NULL OR date <= $3)
+         ORDER BY date DESC, created_at DESC",
+    )
+    .bind(user_id)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "transfers": all_transfers
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/household",
+    tag = "household",
+    summary = "Get all households",
+    responses(
+        (status = 200, description = "Success; `data` has `households`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_households(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_households = sqlx::query_as::<_, HouseholdModel>(
+        "SELECT h.*, m.role FROM households h
+         JOIN household_members m ON m.household_id = h.household_id
+         WHERE m.user_id = $1
+         ORDER BY h.name",
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "households": all_households
+    })))
+}
+
+/// A household with its members. Owners also see the pending invitations.
+#[utoipa::path(
+    get,
+    path = "/household/{id}",
+    tag = "household",
+    params(("id" = String, Path, description = "Household id")),
+    responses(
+        (status = 200, description = "Success; `data` has `household`, `members`, `invitations`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_household_by_id(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid household ID format", StatusCode::BAD_REQUEST))?;
+    let household = get_household(&state.db, household_id, user_id).await?;
+
+    let members = sqlx::query_as::<_, HouseholdMemberModel>(
+        "SELECT m.household_id, m.user_id, u.name, u.email, m.role, m.joined_at
+         FROM household_members m
+         JOIN users u ON u.user_id = m.user_id
+         WHERE m.household_id = $1
+         ORDER BY m.joined_at",
+    )
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let invitations = if household.role.as_deref() == Some("owner") {
+        sqlx::query_as::<_, HouseholdInvitationModel>(
+            "SELECT * FROM household_invitations WHERE household_id = $1 ORDER BY created_at",
+        )
+        .bind(household_id)
+        .fetch_all(&state.db)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(ApiResponse::success(json!({
+        "household": household,
+        "members": members,
+        "invitations": invitations
+    })))
+}
+
+/// Pending household invitations sent to the current user's email.
+#[utoipa::path(
+    get,
+    path = "/invitation",
+    tag = "household",
+    responses(
+        (status = 200, description = "Success; `data` has `invitations`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_my_invitations(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let invitations = sqlx::query_as::<_, HouseholdInvitationModel>(
+        "SELECT i.*, h.name AS household_name
+         FROM household_invitations i
+         JOIN households h ON h.household_id = i.household_id
+         WHERE LOWER(i.email) = (SELECT LOWER(email) FROM users WHERE user_id = $1)
+         ORDER BY i.created_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "invitations": invitations
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/contact",
+    tag = "split",
+    summary = "Get all contacts",
+    responses(
+        (status = 200, description = "Success; `data` has `contacts`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_contacts(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_contacts = sqlx::query_as::<_, ContactModel>(
+        "SELECT * FROM contacts WHERE user_id = $1 ORDER BY name",
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "contacts": all_contacts
+    })))
+}
+
+/// How an expense is split, with each participant's share.
+#[utoipa::path(
+    get,
+    path = "/expense/{id}/split",
+    tag = "split",
+    params(("id" = String, Path, description = "Expense id")),
+    responses(
+        (status = 200, description = "Success; `data` has `method`, `paidBy`, `currency`, `splits`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_expense_split(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let expense_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid expense ID format", StatusCode::BAD_REQUEST))?;
+
+    let expense = sqlx::query_as::<_, ExpenseModel>(
+        "SELECT * FROM expenses
+         WHERE expense_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Expense not found", StatusCode::NOT_FOUND))?;
+
+    let mut conn = state.db.acquire().await?;
+    let splits = fetch_expense_splits(&mut conn, expense_id).await?;
+
+    Ok(ApiResponse::success(json!({
+        "method": expense.split_method,
+        "paidBy": expense.paid_by.unwrap_or(expense.user_id),
+        "currency": expense.currency,
+        "splits": splits
+    })))
+}
+
+/// Who owes whom in the personal or household workspace, netted per pair of
+/// people and currency, with the current user's totals per currency.
+#[utoipa::path(
+    get,
+    path = "/split/balances",
+    tag = "split",
+    params(SplitParams),
+    responses(
+        (status = 200, description = "Success; `data` has `balances`, `summary`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_split_balances(
+    Query(param): Query<SplitParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let balances = get_workspace_balances(&state, user_id, &param).await?;
+
+    let mut totals: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    for balance in &balances {
+        let (you_owe, owed_to_you) = totals.entry(&balance.currency).or_default();
+        if balance.from.user_id == Some(user_id) {
+            *you_owe += balance.amount.minor_units();
+        } else if balance.to.user_id == Some(user_id) {
+            *owed_to_you += balance.amount.minor_units();
+        }
+    }
+    let summary: Vec<serde_json::Value> = totals
+        .into_iter()
+        .map(|(currency, (you_owe, owed_to_you))| {
+            json!({
+                "currency": currency,
+                "youOwe": Money::from_minor_units(you_owe),
+                "owedToYou": Money::from_minor_units(owed_to_you),
+                "net": Money::from_minor_units(owed_to_you - you_owe)
+            })
+        })
+        .collect();
+
+    Ok(ApiResponse::success(json!({
+        "balances": balances,
+        "summary": summary
+    })))
+}
+
+/// The fewest payments that settle every balance in the workspace.
+#[utoipa::path(
+    get,
+    path = "/split/settle-up",
+    tag = "split",
+    params(SplitParams),
+    responses(
+        (status = 200, description = "Success; `data` has `payments`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_settle_up(
+    Query(param): Query<SplitParams>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<serde_json::Value> {
-    let total_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM expenses WHERE user_id = $1")
-        .bind(user_id)
-        .fetch_one(&state.db)
-        .await?;
+    let balances = get_workspace_balances(&state, user_id, &param).await?;
 
-    let all_expenses = if let (Some(limit), Some(offset)) = (param.limit, param.offset) {
-        sqlx::query_as::<_, ExpenseModel>(
-            "SELECT * FROM expenses WHERE user_id = $1 ORDER BY date DESC LIMIT $2 OFFSET $3",
-        )
-        .bind(user_id)
-        .bind(limit as i64)
-        .bind(offset as i64)
-        .fetch_all(&state.db)
-        .await?
-    } else {
-        sqlx::query_as::<_, ExpenseModel>(
-            "SELECT * FROM expenses WHERE user_id = $1 ORDER BY date DESC",
-        )
-        .bind(user_id)
-        .fetch_all(&state.db)
-        .await?
-    };
+    Ok(ApiResponse::success(json!({
+        "payments": simplify_debts(&balances)
+    })))
+}
 
-    let response = if param.limit.is_some() || param.offset.is_some() {
-        json!({
-            "expenses": all_expenses,
-            "pagination": {
-                "total": total_count,
-                "limit": param.limit,
-                "offset": param.offset,
-                "count": all_expenses.len()
-            }
-        })
-    } else {
-        json!({
-            "expenses": all_expenses,
-            "total": total_count
-        })
+async fn get_workspace_balances(
+    state: &Arc<AppState>,
+    user_id: Uuid,
+    param: &SplitParams,
+) -> Result<Vec<SplitBalance>, ApiResponse<serde_json::Value>> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+    let currency = match &param.currency {
+        Some(currency) => Some(validate_currency(currency)?),
+        None => None,
     };
 
-    Ok(ApiResponse::success(response))
+    let debts = get_split_debts(&state.db, user_id, household_id)
+        .await?
+        .into_iter()
+        .filter(|debt| currency.as_ref().is_none_or(|c| *c == debt.currency))
+        .collect();
+
+    Ok(net_pair_balances(debts))
 }
 
-pub async fn get_expense_by_id(
+#[utoipa::path(
+    get,
+    path = "/settlement",
+    tag = "split",
+    summary = "Get all settlements",
+    params(SplitParams),
+    responses(
+        (status = 200, description = "Success; `data` has `settlements`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_settlements(
+    Query(param): Query<SplitParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+
+    let all_settlements = sqlx::query_as::<_, SettlementModel>(
+        "SELECT * FROM settlements
+         WHERE in_workspace(user_id, household_id, $1, $2) AND ($3::VARCHAR IS NULL OR currency = $3)
+         ORDER BY date DESC, created_at DESC",
+    )
+    .bind(user_id)
+    .bind(household_id)
+    .bind(param.currency.as_ref().map(|c| c.trim().to_uppercase()))
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "settlements": all_settlements
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/goal",
+    tag = "goal",
+    summary = "Get all goals",
+    responses(
+        (status = 200, description = "Success; `data` has `goals`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_goals(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_goals: Vec<SavingsGoalModel> = sqlx::query_as::<_, SavingsGoalModel>(&format!(
+        "{} WHERE g.user_id = $1 ORDER BY g.target_date NULLS LAST, g.name",
+        GOAL_QUERY
+    ))
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await?
+    .into_iter()
+    .map(with_required_contribution)
+    .collect();
+
+    Ok(ApiResponse::success(json!({
+        "goals": all_goals
+    })))
+}
+
+/// A savings goal with its progress and contributions, newest first.
+#[utoipa::path(
+    get,
+    path = "/goal/{id}",
+    tag = "goal",
+    params(("id" = String, Path, description = "Goal id")),
+    responses(
+        (status = 200, description = "Success; `data` has `goal`, `contributions`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_goal_by_id(
     Path(id): Path<String>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<serde_json::Value> {
-    let expense_id = Uuid::parse_str(&id)?;
+    let goal_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid goal ID format", StatusCode::BAD_REQUEST))?;
 
-    let expense = sqlx::query_as::<_, ExpenseModel>(
-        "SELECT * FROM expenses WHERE expense_id = $1 AND user_id = $2",
+    let mut conn = state.db.acquire().await?;
+    let goal = get_goal(&mut conn, user_id, goal_id).await?;
+
+    let contributions = sqlx::query_as::<_, GoalContributionModel>(
+        "SELECT * FROM goal_contributions WHERE goal_id = $1 ORDER BY date DESC, created_at DESC",
     )
-    .bind(expense_id)
+    .bind(goal_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "goal": goal,
+        "contributions": contributions
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/bill",
+    tag = "bill",
+    summary = "Get all bills",
+    responses(
+        (status = 200, description = "Success; `data` has `bills`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_bills(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_bills = sqlx::query_as::<_, BillModel>(&format!(
+        "SELECT {} FROM bills b WHERE b.user_id = $1 ORDER BY b.is_active DESC, b.due_date, b.name",
+        BILL_COLUMNS
+    ))
     .bind(user_id)
-    .fetch_optional(&state.db)
+    .fetch_all(&state.db)
     .await?;
 
-    match expense {
-        Some(expense) => Ok(ApiResponse::success(json!({
-            "expense": expense
-        }))),
-        None => Err(ApiResponse::error(
-            "Expense not found",
-            StatusCode::NOT_FOUND,
-        )),
-    }
+    Ok(ApiResponse::success(json!({
+        "bills": all_bills
+    })))
 }
 
-pub async fn get_expenses_by_budget_id(
-    Path(budget_id): Path<String>,
+/// A bill with its payment history, newest first.
+#[utoipa::path(
+    get,
+    path = "/bill/{id}",
+    tag = "bill",
+    params(("id" = String, Path, description = "Bill id")),
+    responses(
+        (status = 200, description = "Success; `data` has `bill`, `payments`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_bill_by_id(
+    Path(id): Path<String>,
+    Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<serde_json::Value> {
-    let budget_id = Uuid::parse_str(&budget_id)?;
+    let bill_id = Uuid::parse_str(&id)
+        .map_err(|_| ApiResponse::error("Invalid bill ID format", StatusCode::BAD_REQUEST))?;
+    let bill = get_bill(&state.db, user_id, bill_id).await?;
 
-    let expenses =
-        sqlx::query_as::<_, ExpenseModel>("SELECT e.* FROM expenses e WHERE e.budget_id = $1")
-            .bind(budget_id)
-            .fetch_all(&state.db)
-            .await?;
+    let payments = sqlx::query_as::<_, BillPaymentModel>(
+        "SELECT * FROM bill_payments WHERE bill_id = $1 ORDER BY due_date DESC",
+    )
+    .bind(bill_id)
+    .fetch_all(&state.db)
+    .await?;
 
     Ok(ApiResponse::success(json!({
-        "expenses": expenses
+        "bill": bill,
+        "payments": payments
     })))
 }
 
-pub async fn get_all_categories(
+/// Calendar of unpaid bill occurrences from today up to `days` ahead, grouped
+/// by due date. Overdue occurrences come first, under their original date.
+#[utoipa::path(
+    get,
+    path = "/bills/upcoming",
+    tag = "bill",
+    params(UpcomingBillsParams),
+    responses(
+        (status = 200, description = "Success; `data` has `from`, `to`, `overdueCount`, `calendar`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_upcoming_bills(
+    Query(param): Query<UpcomingBillsParams>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<serde_json::Value> {
-    let all_categories = sqlx::query_as::<_, CategoryModel>(
-        "SELECT * FROM categories WHERE user_id = $1 OR user_id IS NULL",
+    let days = param.days.unwrap_or(30);
+    if !(1..=366).contains(&days) {
+        return Err(ApiResponse::error(
+            "days must be between 1 and 366",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    let until = today + chrono::Duration::days(days);
+
+    let active_bills = sqlx::query_as::<_, BillModel>(&format!(
+        "SELECT {} FROM bills b WHERE b.user_id = $1 AND b.is_active AND b.due_date <= $2",
+        BILL_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(until)
+    .fetch_all(&state.db)
+    .await?;
+
+    let mut calendar: BTreeMap<chrono::NaiveDate, Vec<serde_json::Value>> = BTreeMap::new();
+    for bill in &active_bills {
+        for date in bill_occurrences_until(bill, until) {
+            calendar.entry(date).or_default().push(json!({
+                "billId": bill.bill_id,
+                "name": bill.name,
+                "amount": bill.amount,
+                "currency": bill.currency,
+                "autopay": bill.autopay,
+                "isOverdue": date < today
+            }));
+        }
+    }
+
+    let overdue_count = calendar
+        .range(..today)
+        .map(|(_, bills)| bills.len())
+        .sum::<usize>();
+    let calendar: Vec<serde_json::Value> = calendar
+        .into_iter()
+        .map(|(date, bills)| json!({ "date": date, "bills": bills }))
+        .collect();
+
+    Ok(ApiResponse::success(json!({
+        "from": today,
+        "to": until,
+        "overdueCount": overdue_count,
+        "calendar": calendar
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/rule",
+    tag = "rule",
+    summary = "Get all rules",
+    responses(
+        (status = 200, description = "Success; `data` has `rules`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_all_rules(
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let all_rules = sqlx::query_as::<_, ExpenseRuleModel>(
+        "SELECT * FROM expense_rules WHERE user_id = $1 ORDER BY priority, created_at",
     )
     .bind(user_id)
     .fetch_all(&state.db)
     .await?;
 
     Ok(ApiResponse::success(json!({
-        "categories": all_categories
+        "rules": all_rules
+    })))
+}
+
+/// Suggests categories for an expense name: the first matching rule, then the
+/// categories the user picked before for the same (or a similar) name.
+#[utoipa::path(
+    get,
+    path = "/category/suggest",
+    tag = "category",
+    params(SuggestParams),
+    responses(
+        (status = 200, description = "Success; `data` has `rule`, `suggestions`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn suggest_category(
+    Query(param): Query<SuggestParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let name = param.name.trim();
+    validate_name(name)?;
+
+    let amount = match &param.amount {
+        Some(amount) => {
+            let base_currency = get_user_base_currency(&state.db, user_id).await?;
+            Some(parse_amount(amount, &base_currency)?)
+        }
+        None => None,
+    };
+
+    let rule = find_matching_rule(&state.db, user_id, name, amount).await?;
+
+    // Exact (case-insensitive) name matches rank above names that merely
+    // contain one another, e.g. "Uber" vs "Uber Eats".
+    let history = sqlx::query_as::<_, CategorySuggestionModel>(
+        "SELECT
+            c.category_id,
+            c.category_name,
+            COUNT(*) AS uses,
+            MAX(e.date) AS last_used
+        FROM expenses e
+        JOIN categories c ON c.category_id = e.category_id AND c.deleted_at IS NULL
+        WHERE e.user_id = $1 AND e.deleted_at IS NULL
+            AND (
+                LOWER(e.name) = LOWER($2)
+                OR POSITION(LOWER($2) IN LOWER(e.name)) > 0
+                OR POSITION(LOWER(e.name) IN LOWER($2)) > 0
+            )
+        GROUP BY c.category_id, c.category_name
+        ORDER BY
+            BOOL_OR(LOWER(e.name) = LOWER($2)) DESC,
+            COUNT(*) DESC,
+            MAX(e.date) DESC
+        LIMIT 5",
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "rule": rule,
+        "suggestions": history
     })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/budget",
+    tag = "budget",
+    summary = "Get all budgets",
+    params(BudgetParams),
+    responses(
+        (status = 200, description = "Success; `data` has `budgets`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn get_all_budgets(
+    Query(param): Query<BudgetParams>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<serde_json::Value> {
-    let all_budgets = sqlx::query_as::<_, BudgetWithSpentModel>(
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+
+    let all_budgets = sqlx::query_as::<_, BudgetWithSpentModel>(&format!(
         "SELECT 
             b.*,
-            COALESCE(SUM(e.amount), 0)::BIGINT AS total_spent
+            {} AS total_spent,
+            {} AS unconverted_count
         FROM budgets b
-        LEFT JOIN expenses e ON e.budget_id = b.budget_id
-        WHERE b.user_id = $1
-        GROUP BY b.budget_id
+        WHERE in_workspace(b.user_id, b.household_id, $1, $2) AND b.deleted_at IS NULL
         ORDER BY b.created_at DESC",
-    )
+        BUDGET_SPENT_EXPR, BUDGET_UNCONVERTED_EXPR
+    ))
     .bind(user_id)
+    .bind(household_id)
     .fetch_all(&state.db)
     .await?;
 
@@ -150,16 +1379,33 @@ pub async fn get_all_budgets(
     })))
 }
 
+/// Fetches a budget with its `ETag`, like `get_expense_by_id`.
+#[utoipa::path(
+    get,
+    path = "/budget/{id}",
+    tag = "budget",
+    params(
+        ("id" = String, Path, description = "Budget id"),
+        ("If-None-Match" = Option<String>, Header, description = "`ETag` of the copy the client has"),
+    ),
+    responses(
+        (status = 200, description = "Success; `data` has `budget`", body = ApiResponse),
+        (status = 304, description = "The client's copy is current"),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn get_budget_by_id(
     Path(id): Path<String>,
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
-) -> ApiResult<serde_json::Value> {
+    headers: HeaderMap,
+) -> Result<Response, ApiResponse<serde_json::Value>> {
     let budget_id = Uuid::parse_str(&id)
         .map_err(|_| ApiResponse::error("Invalid budget ID format", StatusCode::BAD_REQUEST))?;
 
     let budget = sqlx::query_as::<_, BudgetModel>(
-        "SELECT * FROM budgets WHERE budget_id = $1 AND user_id = $2",
+        "SELECT * FROM budgets
+         WHERE budget_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)",
     )
     .bind(budget_id)
     .bind(user_id)
@@ -167,9 +1413,11 @@ pub async fn get_budget_by_id(
     .await?;
 
     match budget {
-        Some(budget) => Ok(ApiResponse::success(json!({
-            "budget": budget
-        }))),
+        Some(budget) => Ok(conditional_get(&headers, &etag(budget.updated_at), || {
+            ApiResponse::success(json!({
+                "budget": budget
+            }))
+        })),
         None => Err(ApiResponse::error(
             "Budget not found",
             StatusCode::NOT_FOUND,
@@ -177,6 +1425,325 @@ pub async fn get_budget_by_id(
     }
 }
 
+/// Spending per tag. An expense carrying several tags counts towards each of them,
+/// so the tag totals can add up to more than the overall total.
+#[utoipa::path(
+    get,
+    path = "/report/tags",
+    tag = "report",
+    params(ReportParams),
+    responses(
+        (status = 200, description = "Success; `data` has `tags`, `untagged`, `unconvertedCount`, `currency`, `startDate`, `endDate`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_tag_report(
+    Query(param): Query<ReportParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+    let base_currency = get_user_base_currency(&state.db, user_id).await?;
+
+    let tag_totals = sqlx::query_as::<_, TagTotalModel>(
+        "SELECT
+            t.tag_id,
+            t.name,
+            COALESCE(SUM(convert_amount(spent_amount(e.transaction_type, e.amount), e.currency, $4, e.date, $1)), 0)::BIGINT AS total_spent,
+            COUNT(e.expense_id) AS expense_count
+        FROM tags t
+        LEFT JOIN expense_tags et ON et.tag_id = t.tag_id
+        LEFT JOIN expenses e ON e.expense_id = et.expense_id
+            AND e.deleted_at IS NULL
+            AND e.transaction_type <> 'income'
+            AND ($2::DATE IS NULL OR e.date >= $2)
+            AND ($3::DATE IS NULL OR e.date <= $3)
+            AND in_workspace(e.user_id, e.household_id, $1, $5)
+        WHERE t.user_id = $1
+        GROUP BY t.tag_id
+        ORDER BY total_spent DESC, t.name",
+    )
+    .bind(user_id)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .bind(&base_currency)
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let untagged: (Money, i64) = sqlx::query_as(
+        "SELECT COALESCE(SUM(convert_amount(spent_amount(e.transaction_type, e.amount), e.currency, $4, e.date, $1)), 0)::BIGINT, COUNT(*)
+        FROM expenses e
+        WHERE in_workspace(e.user_id, e.household_id, $1, $5)
+            AND e.deleted_at IS NULL
+            AND e.transaction_type <> 'income'
+            AND ($2::DATE IS NULL OR e.date >= $2)
+            AND ($3::DATE IS NULL OR e.date <= $3)
+            AND NOT EXISTS (SELECT 1 FROM expense_tags et WHERE et.expense_id = e.expense_id)",
+    )
+    .bind(user_id)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .bind(&base_currency)
+    .bind(household_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let unconverted_count = count_unconverted_expenses(
+        &state.db,
+        user_id,
+        household_id,
+        &base_currency,
+        param.start_date,
+        param.end_date,
+        false,
+    )
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "tags": tag_totals,
+        "untagged": {
+            "totalSpent": untagged.0,
+            "expenseCount": untagged.1
+        },
+        "unconvertedCount": unconverted_count,
+        "currency": base_currency,
+        "startDate": param.start_date,
+        "endDate": param.end_date
+    })))
+}
+
+/// Income, expenses, refunds and savings rate per month, in the user's base
+/// currency. Months without transactions are included with zero totals.
+#[utoipa::path(
+    get,
+    path = "/report/cashflow",
+    tag = "report",
+    params(ReportParams),
+    responses(
+        (status = 200, description = "Success; `data` has `months`, `totals`, `unconvertedCount`, `currency`, `startDate`, `endDate`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_cashflow_report(
+    Query(param): Query<ReportParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+    let base_currency = get_user_base_currency(&state.db, user_id).await?;
+
+    let months = sqlx::query_as::<_, CashFlowModel>(
+        "WITH converted AS (
+            SELECT
+                date_trunc('month', date)::DATE AS month,
+                transaction_type,
+                convert_amount(amount, currency, $4, date, user_id) AS amount
+            FROM expenses
+            WHERE in_workspace(user_id, household_id, $1, $5)
+                AND deleted_at IS NULL
+                AND ($2::DATE IS NULL OR date >= $2)
+                AND ($3::DATE IS NULL OR date <= $3)
+        ),
+        months AS (
+            SELECT generate_series(
+                COALESCE(date_trunc('month', $2::DATE), (SELECT MIN(month) FROM converted)),
+                COALESCE(date_trunc('month', $3::DATE), (SELECT MAX(month) FROM converted)),
+                INTERVAL '1 month'
+            )::DATE AS month
+        ),
+        totals AS (
+            SELECT
+                m.month,
+                COALESCE(SUM(c.amount) FILTER (WHERE c.transaction_type = 'income'), 0) AS income,
+                COALESCE(SUM(c.amount) FILTER (WHERE c.transaction_type = 'expense'), 0) AS expenses,
+                COALESCE(SUM(c.amount) FILTER (WHERE c.transaction_type = 'refund'), 0) AS refunds
+            FROM months m
+            LEFT JOIN converted c ON c.month = m.month
+            GROUP BY m.month
+        )
+        SELECT
+            month,
+            income::BIGINT,
+            expenses::BIGINT,
+            refunds::BIGINT,
+            (expenses - refunds)::BIGINT AS net_expenses,
+            (income - expenses + refunds)::BIGINT AS net_cash_flow,
+            ROUND((income - expenses + refunds) * 100.0 / NULLIF(income, 0), 1)::FLOAT8 AS savings_rate
+        FROM totals
+        ORDER BY month",
+    )
+    .bind(user_id)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .bind(&base_currency)
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let unconverted_count = count_unconverted_expenses(
+        &state.db,
+        user_id,
+        household_id,
+        &base_currency,
+        param.start_date,
+        param.end_date,
+        true,
+    )
+    .await?;
+
+    let sum = |field: fn(&CashFlowModel) -> Money| {
+        Money::from_minor_units(months.iter().map(|m| field(m).minor_units()).sum())
+    };
+    let income = sum(|m| m.income);
+    let net_expenses = sum(|m| m.net_expenses);
+    let net_cash_flow = sum(|m| m.net_cash_flow);
+    let savings_rate = (income.is_positive()).then(|| {
+        (net_cash_flow.minor_units() as f64 * 1000.0 / income.minor_units() as f64).round() / 10.0
+    });
+
+    Ok(ApiResponse::success(json!({
+        "months": months,
+        "totals": {
+            "income": income,
+            "expenses": sum(|m| m.expenses),
+            "refunds": sum(|m| m.refunds),
+            "netExpenses": net_expenses,
+            "netCashFlow": net_cash_flow,
+            "savingsRate": savings_rate
+        },
+        "unconvertedCount": unconverted_count,
+        "currency": base_currency,
+        "startDate": param.start_date,
+        "endDate": param.end_date
+    })))
+}
+
+/// Spending per category, with subcategory totals rolled up into their parents.
+#[utoipa::path(
+    get,
+    path = "/report/categories",
+    tag = "report",
+    params(ReportParams),
+    responses(
+        (status = 200, description = "Success; `data` has `categories`, `uncategorized`, `unconvertedCount`, `currency`, `startDate`, `endDate`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_category_report(
+    Query(param): Query<ReportParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+    let base_currency = get_user_base_currency(&state.db, user_id).await?;
+
+    let category_totals = sqlx::query_as::<_, CategoryTotalModel>(
+        "WITH RECURSIVE visible AS (
+            SELECT category_id, parent_id, category_name FROM categories
+            WHERE (user_id IS NULL OR in_workspace(user_id, household_id, $1, $5))
+                AND deleted_at IS NULL
+        ),
+        tree AS (
+            SELECT category_id AS ancestor_id, category_id AS descendant_id FROM visible
+            UNION
+            SELECT t.ancestor_id, v.category_id FROM tree t
+            JOIN visible v ON v.parent_id = t.descendant_id
+        ),
+        spent AS (
+            SELECT
+                category_id,
+                SUM(convert_amount(spent_amount(transaction_type, amount), currency, $4, date, user_id)) AS total,
+                COUNT(*) AS expense_count
+            FROM expenses
+            WHERE in_workspace(user_id, household_id, $1, $5)
+                AND deleted_at IS NULL
+                AND transaction_type <> 'income'
+                AND category_id IS NOT NULL
+                AND ($2::DATE IS NULL OR date >= $2)
+                AND ($3::DATE IS NULL OR date <= $3)
+            GROUP BY category_id
+        )
+        SELECT
+            v.category_id,
+            v.parent_id,
+            v.category_name,
+            COALESCE(SUM(s.total) FILTER (WHERE t.descendant_id = v.category_id), 0)::BIGINT AS direct_spent,
+            COALESCE(SUM(s.total), 0)::BIGINT AS total_spent,
+            COALESCE(SUM(s.expense_count), 0)::BIGINT AS expense_count
+        FROM visible v
+        JOIN tree t ON t.ancestor_id = v.category_id
+        LEFT JOIN spent s ON s.category_id = t.descendant_id
+        GROUP BY v.category_id, v.parent_id, v.category_name
+        ORDER BY total_spent DESC, v.category_name",
+    )
+    .bind(user_id)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .bind(&base_currency)
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    // Expenses whose category is in the trash count as uncategorized
+    let uncategorized: (Money, i64) = sqlx::query_as(
+        "SELECT COALESCE(SUM(convert_amount(spent_amount(e.transaction_type, e.amount), e.currency, $4, e.date, e.user_id)), 0)::BIGINT, COUNT(*)
+        FROM expenses e
+        LEFT JOIN categories c ON c.category_id = e.category_id
+        WHERE in_workspace(e.user_id, e.household_id, $1, $5)
+            AND e.deleted_at IS NULL
+            AND e.transaction_type <> 'income'
+            AND (e.category_id IS NULL OR c.deleted_at IS NOT NULL)
+            AND ($2::DATE IS NULL OR e.date >= $2)
+            AND ($3::DATE IS NULL OR e.date <= $3)",
+    )
+    .bind(user_id)
+    .bind(param.start_date)
+    .bind(param.end_date)
+    .bind(&base_currency)
+    .bind(household_id)
+    .fetch_one(&state.db)
+    .await?;
+
+    let unconverted_count = count_unconverted_expenses(
+        &state.db,
+        user_id,
+        household_id,
+        &base_currency,
+        param.start_date,
+        param.end_date,
+        false,
+    )
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "categories": category_totals,
+        "uncategorized": {
+            "totalSpent": uncategorized.0,
+            "expenseCount": uncategorized.1
+        },
+        "unconvertedCount": unconverted_count,
+        "currency": base_currency,
+        "startDate": param.start_date,
+        "endDate": param.end_date
+    })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/user/{email}",
+    tag = "user",
+    summary = "Login user",
+    params(
+        ("email" = String, Path, description = "Email address of the account"),
+    ),
+    request_body = LoginUserSchema,
+    security(()),
+    responses(
+        (status = 200, description = "Success; `data` has `token`, `user`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn login_user(
     Path(email): Path<String>,
     State(state): State<Arc<AppState>>,
@@ -208,11 +1775,132 @@ pub async fn login_user(
         ));
     }
 
-    let token = sign(&user.user_id.to_string())
-        .map_err(|e| ApiResponse::error(&e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+    let token = sign(
+        &user.user_id.to_string(),
+        &state.config.jwt_secret,
+        state.config.token_lifetime_days,
+    )
+    .map_err(|e| ApiResponse::error(&e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
 
     Ok(ApiResponse::success(json!({
         "token": token,
         "user": user
     })))
 }
+
+/// Trashed expenses, budgets and categories of a workspace, most recently
+/// deleted first. They can be restored until the retention period ends.
+#[utoipa::path(
+    get,
+    path = "/trash",
+    tag = "trash",
+    params(TrashParams),
+    responses(
+        (status = 200, description = "Success; `data` has `expenses`, `budgets`, `categories`, `retentionDays`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_trash(
+    Query(param): Query<TrashParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+
+    let expenses = sqlx::query_as::<_, ExpenseModel>(&format!(
+        "SELECT {} FROM expenses e
+         WHERE in_workspace(e.user_id, e.household_id, $1, $2) AND e.deleted_at IS NOT NULL
+         ORDER BY e.deleted_at DESC, e.date DESC",
+        EXPENSE_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let budgets = sqlx::query_as::<_, BudgetModel>(
+        "SELECT * FROM budgets
+         WHERE in_workspace(user_id, household_id, $1, $2) AND deleted_at IS NOT NULL
+         ORDER BY deleted_at DESC",
+    )
+    .bind(user_id)
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let categories = sqlx::query_as::<_, CategoryModel>(
+        "SELECT * FROM categories
+         WHERE in_workspace(user_id, household_id, $1, $2) AND deleted_at IS NOT NULL
+         ORDER BY deleted_at DESC, category_id",
+    )
+    .bind(user_id)
+    .bind(household_id)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "expenses": expenses,
+        "budgets": budgets,
+        "categories": categories,
+        "retentionDays": state.config.trash_retention_days
+    })))
+}
+
+/// Changes to the expenses, budgets and categories of a workspace, newest
+/// first, optionally narrowed down to one entity type or entity.
+#[utoipa::path(
+    get,
+    path = "/audit",
+    tag = "audit",
+    params(AuditParams),
+    responses(
+        (status = 200, description = "Success; `data` has `entries`, `limit`, `offset`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_audit_log(
+    Query(param): Query<AuditParams>,
+    Extension(user_id): Extension<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    let household_id = resolve_workspace(&state.db, user_id, param.household_id).await?;
+
+    let entity_type = match param.entity_type.as_deref() {
+        Some(entity_type) => Some(validate_audit_entity_type(entity_type)?),
+        None => None,
+    };
+
+    if param.entity_id.is_some() && entity_type.is_none() {
+        return Err(ApiResponse::error(
+            "entityId needs an entityType",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let limit = param.limit.unwrap_or(50).clamp(1, 200);
+    let offset = param.offset.unwrap_or(0);
+
+    let entries = sqlx::query_as::<_, AuditLogModel>(&format!(
+        "SELECT {} FROM audit_log l
+         WHERE in_workspace(l.owner_id, l.household_id, $1, $2)
+            AND ($3::VARCHAR IS NULL OR l.entity_type = $3)
+            AND ($4::VARCHAR IS NULL OR l.entity_id = $4)
+         ORDER BY l.audit_id DESC
+         LIMIT $5 OFFSET $6",
+        AUDIT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(household_id)
+    .bind(entity_type)
+    .bind(param.entity_id)
+    .bind(limit as i64)
+    .bind(offset as i64)
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(ApiResponse::success(json!({
+        "entries": entries,
+        "limit": limit,
+        "offset": offset
+    })))
+}
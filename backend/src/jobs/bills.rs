@@ -0,0 +1,198 @@
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::{
+    jobs::TaskSupervisor,
+    metrics::record_notifications,
+    models::BillModel,
+    utils::{
+        bill::{BILL_COLUMNS, record_bill_payment},
+        currency::format_amount,
+    },
+};
+
+/// What one run of the bill scheduler did.
+#[derive(Debug, Default)]
+pub struct BillRunSummary {
+    pub autopaid: usize,
+    pub overdue: usize,
+    pub reminded: usize,
+}
+
+/// Runs the bill scheduler under `tasks`, right away and then every
+/// `interval_secs` seconds.
+pub fn spawn_bill_scheduler(tasks: &TaskSupervisor, db: PgPool, interval_secs: u64) {
+    tasks.spawn_periodic(
+        "bill scheduler",
+        Duration::from_secs(interval_secs),
+        move || {
+            let db = db.clone();
+            async move {
+                let today = chrono::Utc::now().date_naive();
+                let summary = run_bill_scheduler(&db, today).await?;
+                tracing::info!(
+                    "bill scheduler: {} autopaid, {} overdue, {} reminded",
+                    summary.autopaid,
+                    summary.overdue,
+                    summary.reminded
+                );
+                Ok::<_, sqlx::Error>(())
+            }
+        },
+    );
+}
+
+/// Pays autopay bills that are due, then notifies users about overdue bills
+/// and bills coming up within their reminder window. Every notification is
+/// sent once per occurrence, so running it more often is harmless.
+pub async fn run_bill_scheduler(
+    db: &PgPool,
+    today: NaiveDate,
+) -> Result<BillRunSummary, sqlx::Error> {
+    Ok(BillRunSummary {
+        autopaid: autopay_due_bills(db, today).await?,
+        overdue: notify_overdue_bills(db, today).await?,
+        reminded: send_bill_reminders(db, today).await?,
+    })
+}
+
+async fn autopay_due_bills(db: &PgPool, today: NaiveDate) -> Result<usize, sqlx::Error> {
+    let due_bills: Vec<uuid::Uuid> = sqlx::query_scalar(
+        "SELECT bill_id FROM bills WHERE is_active AND autopay AND due_date <= $1",
+    )
+    .bind(today)
+    .fetch_all(db)
+    .await?;
+
+    let mut paid = 0;
+    for bill_id in due_bills {
+        let mut tx = db.begin().await?;
+        let mut occurrences = 0;
+
+        // Catch up on every occurrence missed while the scheduler wasn't running.
+        // Bills locked by another instance are left to it.
+        while let Some(bill) = sqlx::query_as::<_, BillModel>(&format!(
+            "SELECT {} FROM bills b
+             WHERE b.bill_id = $1 AND b.is_active AND b.autopay AND b.due_date <= $2
+             FOR UPDATE SKIP LOCKED",
+            BILL_COLUMNS
+        ))
+        .bind(bill_id)
+        .bind(today)
+        .fetch_optional(&mut *tx)
+        .await?
+        {
+            record_bill_payment(
+                &mut tx,
+                &bill,
+                bill.amount,
+                bill.due_date,
+                bill.account_id,
+                true,
+            )
+            .await?;
+
+            sqlx::query(
+                "INSERT INTO notifications (user_id, category, message) VALUES ($1, $2, $3)",
+            )
+            .bind(bill.user_id)
+            .bind("BILL_AUTOPAID")
+            .bind(format!(
+                "💳 BILL PAID: '{}' {} due on {} was paid automatically",
+                bill.name,
+                format_amount(bill.amount, &bill.currency),
+                bill.due_date
+            ))
+            .execute(&mut *tx)
+            .await?;
+
+            occurrences += 1;
+        }
+
+        tx.commit().await?;
+        record_notifications("BILL_AUTOPAID", occurrences as u64);
+        paid += occurrences;
+    }
+
+    Ok(paid)
+}
+
+async fn notify_overdue_bills(db: &PgPool, today: NaiveDate) -> Result<usize, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let overdue_bills = sqlx::query_as::<_, BillModel>(&format!(
+        "UPDATE bills b SET last_overdue_for = b.due_date
+         WHERE b.is_active AND NOT b.autopay AND b.due_date < $1
+            AND b.last_overdue_for IS DISTINCT FROM b.due_date
+         RETURNING {}",
+        BILL_COLUMNS
+    ))
+    .bind(today)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for bill in &overdue_bills {
+        sqlx::query("INSERT INTO notifications (user_id, category, message) VALUES ($1, $2, $3)")
+            .bind(bill.user_id)
+            .bind("BILL_OVERDUE")
+            .bind(format!(
+                "⏰ BILL OVERDUE: '{}' {} was due on {}",
+                bill.name,
+                format_amount(bill.amount, &bill.currency),
+                bill.due_date
+            ))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    record_notifications("BILL_OVERDUE", overdue_bills.len() as u64);
+    Ok(overdue_bills.len())
+}
+
+async fn send_bill_reminders(db: &PgPool, today: NaiveDate) -> Result<usize, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let upcoming_bills = sqlx::query_as::<_, BillModel>(&format!(
+        "UPDATE bills b SET last_reminded_for = b.due_date
+         WHERE b.is_active AND b.due_date >= $1 AND b.due_date - b.remind_days_before <= $1
+            AND b.last_reminded_for IS DISTINCT FROM b.due_date
+         RETURNING {}",
+        BILL_COLUMNS
+    ))
+    .bind(today)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for bill in &upcoming_bills {
+        let when = match (bill.due_date - today).num_days() {
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            days => format!("in {} days", days),
+        };
+        let how = if bill.autopay {
+            " and will be paid automatically"
+        } else {
+            ""
+        };
+
+        sqlx::query("INSERT INTO notifications (user_id, category, message) VALUES ($1, $2, $3)")
+            .bind(bill.user_id)
+            .bind("BILL_REMINDER")
+            .bind(format!(
+                "🔔 BILL REMINDER: '{}' {} is due {} ({}){}",
+                bill.name,
+                format_amount(bill.amount, &bill.currency),
+                when,
+                bill.due_date,
+                how
+            ))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    record_notifications("BILL_REMINDER", upcoming_bills.len() as u64);
+    Ok(upcoming_bills.len())
+}
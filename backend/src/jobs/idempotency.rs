@@ -0,0 +1,32 @@
+use sqlx::PgPool;
+use std::time::Duration;
+
+use crate::jobs::TaskSupervisor;
+
+/// How often expired idempotency keys are deleted.
+const PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// Deletes expired idempotency keys under `tasks`, every hour.
+pub fn spawn_idempotency_key_purger(tasks: &TaskSupervisor, db: PgPool) {
+    tasks.spawn_periodic(
+        "idempotency key purge",
+        Duration::from_secs(PURGE_INTERVAL_SECS),
+        move || {
+            let db = db.clone();
+            async move {
+                let purged = purge_idempotency_keys(&db).await?;
+                tracing::info!("idempotency key purge: {} keys", purged);
+                Ok::<_, sqlx::Error>(())
+            }
+        },
+    );
+}
+
+pub async fn purge_idempotency_keys(db: &PgPool) -> Result<u64, sqlx::Error> {
+    Ok(
+        sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= now()")
+            .execute(db)
+            .await?
+            .rows_affected(),
+    )
+}
@@ -0,0 +1,11 @@
+pub mod bills;
+pub mod idempotency;
+pub mod queue;
+pub mod supervisor;
+pub mod trash;
+
+pub use bills::*;
+pub use idempotency::*;
+pub use queue::*;
+pub use supervisor::*;
+pub use trash::*;
@@ -0,0 +1,325 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgExecutor, PgPool};
+use std::{convert::Infallible, time::Duration};
+use uuid::Uuid;
+
+use crate::{
+    jobs::TaskSupervisor,
+    models::JobModel,
+    utils::budget::{BudgetAlert, send_budget_alerts},
+};
+
+/// How often an idle worker looks for due jobs.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest an idle worker waits between polls while the database is failing.
+const MAX_POLL_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Wait before the first retry of a failed job, doubled for every further
+/// attempt up to [`MAX_RETRY_DELAY_SECS`].
+const RETRY_DELAY_SECS: u64 = 30;
+const MAX_RETRY_DELAY_SECS: u64 = 3600;
+
+/// A running job not finished after this long is assumed to belong to a
+/// worker that died, and is claimed again.
+const STALE_LOCK_SECS: f64 = 600.0;
+
+/// Matches job `$1` only while the run that made attempt `$2` still holds it.
+/// A later claim of a stale job counts another attempt, so a late worker
+/// can't overwrite the state of the run that took over.
+const STILL_CLAIMED: &str = "job_id = $1 AND status = 'running' AND attempts = $2";
+
+/// How often finished jobs are purged, and how long they're kept. Dead jobs
+/// stay until they're looked into.
+const PURGE_INTERVAL_SECS: u64 = 3600;
+const SUCCEEDED_JOB_RETENTION_DAYS: i32 = 7;
+
+/// Background work run by the job workers. The variant is stored in
+/// `jobs.job_type` and its fields in `jobs.payload`, so renaming either
+/// strands the jobs already queued.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "job_type", content = "payload", rename_all = "snake_case")]
+pub enum Job {
+    BudgetAlert(BudgetAlert),
+}
+
+impl Job {
+    /// Does the work. Jobs can run more than once (a retry after a partial
+    /// failure, or a worker dying before recording success), so they must be
+    /// safe to repeat.
+    async fn run(self, db: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            Job::BudgetAlert(alert) => send_budget_alerts(db, &alert).await?,
+        }
+        Ok(())
+    }
+}
+
+/// Queues `job` to run as soon as a worker is free. Pass the transaction of
+/// the change the job is for, so it's queued only if the change commits.
+pub async fn enqueue<'e>(conn: impl PgExecutor<'e>, job: &Job) -> Result<Uuid, sqlx::Error> {
+    enqueue_at(conn, job, Utc::now()).await
+}
+
+/// Queues `job` to run at `run_at` or later.
+pub async fn enqueue_at<'e>(
+    conn: impl PgExecutor<'e>,
+    job: &Job,
+    run_at: DateTime<Utc>,
+) -> Result<Uuid, sqlx::Error> {
+    let job = serde_json::to_value(job).map_err(|err| sqlx::Error::Encode(Box::new(err)))?;
+
+    sqlx::query_scalar(
+        "INSERT INTO jobs (job_type, payload, run_at) VALUES ($1, $2, $3) RETURNING job_id",
+    )
+    .bind(job["job_type"].as_str())
+    .bind(&job["payload"])
+    .bind(run_at)
+    .fetch_one(conn)
+    .await
+}
+
+/// Starts `workers` job workers under `tasks`, plus the hourly purge of
+/// succeeded jobs. On shutdown the workers stop claiming jobs and finish the
+/// ones they're running; everything else stays queued for the next start.
+pub fn spawn_job_workers(tasks: &TaskSupervisor, db: PgPool, workers: usize) {
+    for _ in 0..workers {
+        let supervisor = tasks.clone();
+        let db = db.clone();
+        tasks.spawn("job worker", async move {
+            work(&supervisor, &db).await;
+            Ok::<_, Infallible>(())
+        });
+    }
+
+    tasks.spawn_periodic(
+        "job purge",
+        Duration::from_secs(PURGE_INTERVAL_SECS),
+        move || {
+            let db = db.clone();
+            async move {
+                let purged = purge_succeeded_jobs(&db).await?;
+                tracing::info!("job purge: {} jobs", purged);
+                Ok::<_, sqlx::Error>(())
+            }
+        },
+    );
+}
+
+/// Runs due jobs one after another until shutdown, polling while the queue
+/// is empty.
+async fn work(tasks: &TaskSupervisor, db: &PgPool) {
+    let mut backoff = POLL_INTERVAL;
+
+    while !tasks.is_shutting_down() {
+        match run_next_job(db).await {
+            Ok(true) => {
+                backoff = POLL_INTERVAL;
+                continue;
+            }
+            Ok(false) => backoff = POLL_INTERVAL,
+            Err(err) => {
+                tracing::error!("job worker failed, retrying in {:?}: {}", backoff, err);
+                backoff = (backoff * 2).min(MAX_POLL_BACKOFF);
+            }
+        }
+
+        tokio::select! {
+            _ = tasks.cancelled() => break,
+            _ = tokio::time::sleep(backoff) => {}
+        }
+    }
+}
+
+/// Claims and runs the next due job, if there is one. Returns whether a job
+/// was run.
+pub async fn run_next_job(db: &PgPool) -> Result<bool, sqlx::Error> {
+    let Some(row) = claim_job(db).await? else {
+        return Ok(false);
+    };
+
+    let job = serde_json::from_value::<Job>(serde_json::json!({
+        "job_type": row.job_type,
+        "payload": row.payload,
+    }));
+
+    let result = match job {
+        // Each job runs in its own task, so a panic fails only that job
+        Ok(job) => {
+            let db = db.clone();
+            match tokio::spawn(async move { job.run(&db).await.map_err(|err| err.to_string()) })
+                .await
+            {
+                Ok(result) => result,
+                Err(err) => Err(err.to_string()),
+            }
+        }
+        // Retrying won't make an unknown job type or payload readable
+        Err(err) => {
+            mark_dead(db, &row, &format!("Invalid job: {}", err)).await?;
+            return Ok(true);
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            let updated = sqlx::query(&format!(
+                "UPDATE jobs SET status = 'succeeded', locked_at = NULL, last_error = NULL,
+                    finished_at = now(), updated_at = now()
+                WHERE {}",
+                STILL_CLAIMED
+            ))
+            .bind(row.job_id)
+            .bind(row.attempts)
+            .execute(db)
+            .await?;
+            record_outcome(&row, "succeeded", updated.rows_affected());
+        }
+        Err(error) if row.attempts >= row.max_attempts => {
+            mark_dead(db, &row, &error).await?;
+        }
+        Err(error) => {
+            let delay = retry_delay(row.attempts);
+            tracing::warn!(
+                "job {} ({}) failed on attempt {} of {}, retrying in {:?}: {}",
+                row.job_id,
+                row.job_type,
+                row.attempts,
+                row.max_attempts,
+                delay,
+                error
+            );
+            let updated = sqlx::query(&format!(
+                "UPDATE jobs SET status = 'pending', locked_at = NULL, last_error = $3,
+                    run_at = now() + make_interval(secs => $4), updated_at = now()
+                WHERE {}",
+                STILL_CLAIMED
+            ))
+            .bind(row.job_id)
+            .bind(row.attempts)
+            .bind(&error)
+            .bind(delay.as_secs_f64())
+            .execute(db)
+            .await?;
+            record_outcome(&row, "retried", updated.rows_affected());
+        }
+    }
+
+    Ok(true)
+}
+
+/// Takes the job that has been due the longest, skipping jobs other workers
+/// are claiming, and counts the attempt.
+async fn claim_job(db: &PgPool) -> Result<Option<JobModel>, sqlx::Error> {
+    sqlx::query_as::<_, JobModel>(
+        "UPDATE jobs SET status = 'running', attempts = attempts + 1, locked_at = now(),
+            updated_at = now()
+        WHERE job_id = (
+            SELECT job_id FROM jobs
+            WHERE (status = 'pending' AND run_at <= now())
+                OR (status = 'running' AND locked_at < now() - make_interval(secs => $1))
+            ORDER BY run_at
+            FOR UPDATE SKIP LOCKED
+            LIMIT 1
+        )
+        RETURNING *",
+    )
+    .bind(STALE_LOCK_SECS)
+    .fetch_optional(db)
+    .await
+}
+
+async fn mark_dead(db: &PgPool, row: &JobModel, error: &str) -> Result<(), sqlx::Error> {
+    tracing::error!(
+        "job {} ({}) is dead after {} attempts: {}",
+        row.job_id,
+        row.job_type,
+        row.attempts,
+        error
+    );
+    let updated = sqlx::query(&format!(
+        "UPDATE jobs SET status = 'dead', locked_at = NULL, last_error = $3,
+            finished_at = now(), updated_at = now()
+        WHERE {}",
+        STILL_CLAIMED
+    ))
+    .bind(row.job_id)
+    .bind(row.attempts)
+    .bind(error)
+    .execute(db)
+    .await?;
+    record_outcome(row, "dead", updated.rows_affected());
+    Ok(())
+}
+
+/// Counts the outcome of a run, unless recording it changed no row: the job
+/// was claimed again meanwhile, as its lock had gone stale, and that run's
+/// state is left alone.
+fn record_outcome(row: &JobModel, outcome: &'static str, updated: u64) {
+    if updated == 0 {
+        tracing::warn!(
+            "job {} ({}) was claimed again while attempt {} ran, dropping its outcome ({})",
+            row.job_id,
+            row.job_type,
+            row.attempts,
+            outcome
+        );
+        return;
+    }
+
+    metrics::counter!(
+        "jobs_processed_total",
+        "job_type" => row.job_type.clone(),
+        "outcome" => outcome
+    )
+    .increment(1);
+}
+
+/// Wait before retrying a job that failed its `attempts`th attempt.
+fn retry_delay(attempts: i32) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    Duration::from_secs((RETRY_DELAY_SECS << doublings).min(MAX_RETRY_DELAY_SECS))
+}
+
+pub async fn purge_succeeded_jobs(db: &PgPool) -> Result<u64, sqlx::Error> {
+    Ok(sqlx::query(
+        "DELETE FROM jobs
+        WHERE status = 'succeeded' AND finished_at < now() - make_interval(days => $1)",
+    )
+    .bind(SUCCEEDED_JOB_RETENTION_DAYS)
+    .execute(db)
+    .await?
+    .rows_affected())
+}
+
+/// Most jobs [`list_jobs`] returns at once.
+pub const MAX_LISTED_JOBS: i64 = 200;
+
+/// The latest jobs, optionally only those with `status` or `job_type`, newest
+/// first, for looking into what the workers did.
+pub async fn list_jobs(
+    db: &PgPool,
+    status: Option<&str>,
+    job_type: Option<&str>,
+    limit: i64,
+) -> Result<Vec<JobModel>, sqlx::Error> {
+    sqlx::query_as::<_, JobModel>(
+        "SELECT * FROM jobs
+        WHERE ($1::VARCHAR IS NULL OR status = $1) AND ($2::VARCHAR IS NULL OR job_type = $2)
+        ORDER BY created_at DESC
+        LIMIT $3",
+    )
+    .bind(status)
+    .bind(job_type)
+    .bind(limit.clamp(1, MAX_LISTED_JOBS))
+    .fetch_all(db)
+    .await
+}
+
+/// How many jobs there are in each status.
+pub async fn count_jobs(db: &PgPool) -> Result<Vec<(String, i64)>, sqlx::Error> {
+    sqlx::query_as("SELECT status, COUNT(*) FROM jobs GROUP BY status ORDER BY status")
+        .fetch_all(db)
+        .await
+}
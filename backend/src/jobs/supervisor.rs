@@ -0,0 +1,111 @@
+use std::{fmt::Display, future::Future, time::Duration};
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+/// First wait before a failed job is retried; doubled after every further
+/// failure, up to the job's interval.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Keeps track of the work the server does in the background, so it can be
+/// stopped and waited for on shutdown instead of being cut off mid-way.
+///
+/// - [`spawn`](Self::spawn) runs a one-off task, like a budget alert after
+///   an expense is created.
+/// - [`spawn_periodic`](Self::spawn_periodic) runs a job on an interval until
+///   shutdown, retrying failed runs with exponential backoff.
+///
+/// Cloning is cheap and every clone tracks the same tasks.
+#[derive(Clone, Default)]
+pub struct TaskSupervisor {
+    tracker: TaskTracker,
+    shutdown: CancellationToken,
+}
+
+impl TaskSupervisor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs a task in the background; a failure is logged under `name`.
+    pub fn spawn<F, E>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.tracker.spawn(async move {
+            if let Err(err) = task.await {
+                tracing::error!("{} failed: {}", name, err);
+            }
+        });
+    }
+
+    /// Runs `job` right away and then every `interval` until shutdown. Each
+    /// run is its own task, so a run that fails or panics doesn't take the
+    /// job down: it is retried after a backoff that starts at a second and
+    /// doubles up to `interval`, and the job is back on its schedule after
+    /// the next successful run. On shutdown a run in progress is finished.
+    pub fn spawn_periodic<F, Fut, E>(&self, name: &'static str, interval: Duration, job: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display + Send + 'static,
+    {
+        let shutdown = self.shutdown.clone();
+
+        self.tracker.spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            let mut backoff = INITIAL_BACKOFF;
+
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = ticks.tick() => {}
+                }
+
+                loop {
+                    let error = match tokio::spawn(job()).await {
+                        Ok(Ok(())) => break,
+                        Ok(Err(err)) => err.to_string(),
+                        Err(err) => err.to_string(),
+                    };
+
+                    tracing::error!("{} failed, retrying in {:?}: {}", name, backoff, error);
+                    tokio::select! {
+                        _ = shutdown.cancelled() => return,
+                        _ = tokio::time::sleep(backoff) => {}
+                    }
+                    backoff = (backoff * 2).min(interval);
+                }
+
+                backoff = INITIAL_BACKOFF;
+                ticks.reset_at(Instant::now() + interval);
+            }
+        });
+    }
+
+    /// Resolves once shutdown has begun.
+    pub async fn cancelled(&self) {
+        self.shutdown.cancelled().await
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// Stops the periodic jobs and waits up to `timeout` for every task to
+    /// finish. Returns whether they all did.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.shutdown.cancel();
+        self.tracker.close();
+        tokio::time::timeout(timeout, self.tracker.wait())
+            .await
+            .is_ok()
+    }
+
+    /// Starts the shutdown without waiting, so periodic jobs stop scheduling
+    /// new runs while open connections drain.
+    pub fn begin_shutdown(&self) {
+        self.shutdown.cancel();
+    }
+}
@@ -0,0 +1,90 @@
+use sqlx::PgPool;
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    jobs::TaskSupervisor,
+    models::AttachmentModel,
+    storage::{Storage, delete_keys},
+    utils::attachment::attachment_keys,
+};
+
+/// How often the trash is checked for rows past their retention period.
+const PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// What one purge of the trash deleted for good.
+#[derive(Debug, Default)]
+pub struct TrashPurgeSummary {
+    pub expenses: u64,
+    pub budgets: u64,
+    pub categories: u64,
+}
+
+/// Purges the trash under `tasks`, right away and then every hour, deleting
+/// rows trashed more than `retention_days` ago.
+pub fn spawn_trash_purger(
+    tasks: &TaskSupervisor,
+    db: PgPool,
+    storage: Arc<dyn Storage>,
+    retention_days: i64,
+) {
+    tasks.spawn_periodic(
+        "trash purge",
+        Duration::from_secs(PURGE_INTERVAL_SECS),
+        move || {
+            let db = db.clone();
+            let storage = storage.clone();
+            async move {
+                let summary = purge_trash(&db, storage.as_ref(), retention_days).await?;
+                tracing::info!(
+                    "trash purge: {} expenses, {} budgets, {} categories",
+                    summary.expenses,
+                    summary.budgets,
+                    summary.categories
+                );
+                Ok::<_, sqlx::Error>(())
+            }
+        },
+    );
+}
+
+/// Deletes everything that has been in the trash for more than
+/// `retention_days`, including the files of trashed expenses' attachments.
+/// Links to purged budgets and categories are cleared by their foreign keys.
+pub async fn purge_trash(
+    db: &PgPool,
+    storage: &dyn Storage,
+    retention_days: i64,
+) -> Result<TrashPurgeSummary, sqlx::Error> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days);
+    let mut tx = db.begin().await?;
+
+    // Attachment rows go away with their expense (ON DELETE CASCADE), so
+    // collect the storage keys first and remove the files after the commit.
+    let attachments = sqlx::query_as::<_, AttachmentModel>(
+        "SELECT a.* FROM attachments a
+         JOIN expenses e ON e.expense_id = a.expense_id
+         WHERE e.deleted_at < $1",
+    )
+    .bind(cutoff)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut summary = TrashPurgeSummary::default();
+    for (table, count) in [
+        ("expenses", &mut summary.expenses),
+        ("budgets", &mut summary.budgets),
+        ("categories", &mut summary.categories),
+    ] {
+        *count = sqlx::query(&format!("DELETE FROM {} WHERE deleted_at < $1", table))
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+    }
+
+    tx.commit().await?;
+
+    delete_keys(storage, attachment_keys(attachments)).await;
+
+    Ok(summary)
+}
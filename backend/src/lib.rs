@@ -1,14 +1,24 @@
 use sqlx::{Pool, Postgres};
+use std::sync::Arc;
 
+pub mod config;
 #[macro_use]
 pub mod handlers;
+pub mod jobs;
+pub mod metrics;
 pub mod middleware;
 pub mod models;
+pub mod openapi;
 pub mod routes;
 pub mod schema;
+pub mod storage;
 pub mod utils;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Pool<Postgres>,
+    pub config: Arc<config::Config>,
+    pub storage: Arc<dyn storage::Storage>,
+    pub tasks: jobs::TaskSupervisor,
+    pub metrics: metrics_exporter_prometheus::PrometheusHandle,
 }
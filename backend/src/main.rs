@@ -1,57 +1,136 @@
 use axum::http::{
     HeaderValue, Method,
-    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
+    header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH, LINK},
 };
 
-use backend::{AppState, routes::create_router};
+use backend::{
+    AppState,
+    config::Config,
+    jobs::TaskSupervisor,
+    metrics::QueryMetricsLayer,
+    middleware::{
+        idempotency::{IDEMPOTENCY_KEY, IDEMPOTENT_REPLAYED},
+        version::{CLIENT_VERSION, DEPRECATION, SUNSET},
+    },
+    routes::create_router,
+};
 use dotenv::dotenv;
 use sqlx::postgres::PgPoolOptions;
-use std::sync::Arc;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use std::{sync::Arc, time::Duration};
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    trace::TraceLayer,
+};
+use tracing_subscriber::{
+    EnvFilter, Layer, filter::Targets, layer::SubscriberExt, util::SubscriberInitExt,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
 
+    // Query timings come from sqlx's debug logs, so they're let through to
+    // the metrics layer whatever RUST_LOG says
     tracing_subscriber::registry()
         .with(
-            EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| EnvFilter::new("info,tower_http=debug")),
+            tracing_subscriber::fmt::layer().with_filter(
+                EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| EnvFilter::new("info,tower_http=debug")),
+            ),
+        )
+        .with(
+            QueryMetricsLayer
+                .with_filter(Targets::new().with_target("sqlx::query", tracing::Level::DEBUG)),
         )
-        .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let db_connection_str =
-        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set in environment variables");
+    let metrics = backend::metrics::install().map_err(|err| {
+        eprintln!("Failed to install the metrics recorder: {}", err);
+        err
+    })?;
+
+    let config = Config::load().map_err(|err| {
+        eprintln!("{}", err);
+        err
+    })?;
 
     let pool = PgPoolOptions::new()
-        .max_connections(10)
+        .max_connections(config.database_max_connections)
         .acquire_timeout(std::time::Duration::from_secs(5))
-        .connect(&db_connection_str)
+        .connect(&config.database_url)
         .await
         .map_err(|err| {
             eprintln!("Failed to connect to the database: {}", err);
             err
         })?;
 
-    let cors = CorsLayer::new()
-        .allow_origin(
-            "*"
-                .parse::<HeaderValue>()
-                .expect("Invalid CORS origin"),
+    let allow_origin = if config.cors_allowed_origins == ["*"] {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            config
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|origin| origin.parse::<HeaderValue>().ok()),
         )
-        .allow_headers([CONTENT_TYPE, AUTHORIZATION, ACCEPT])
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+    };
+
+    let cors = CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_headers([
+            CONTENT_TYPE,
+            AUTHORIZATION,
+            ACCEPT,
+            IF_MATCH,
+            IF_NONE_MATCH,
+            IDEMPOTENCY_KEY,
+            CLIENT_VERSION,
+        ])
+        .expose_headers([ETAG, IDEMPOTENT_REPLAYED, DEPRECATION, SUNSET, LINK])
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
         .allow_credentials(false);
 
-    let app_state = Arc::new(AppState { db: pool.clone() });
+    let storage = backend::storage::from_config(&config.storage).map_err(|err| {
+        eprintln!("Failed to initialise attachment storage: {}", err);
+        err
+    })?;
+
+    let bind_addr = config.bind_addr;
+    let shutdown_timeout = Duration::from_secs(config.shutdown_timeout_secs);
+    let tasks = TaskSupervisor::new();
+    let app_state = Arc::new(AppState {
+        db: pool.clone(),
+        config: Arc::new(config),
+        storage,
+        tasks: tasks.clone(),
+        metrics: metrics.clone(),
+    });
+
+    backend::jobs::spawn_bill_scheduler(
+        &tasks,
+        pool.clone(),
+        app_state.config.bill_scheduler_interval_secs,
+    );
+    backend::jobs::spawn_trash_purger(
+        &tasks,
+        pool.clone(),
+        app_state.storage.clone(),
+        app_state.config.trash_retention_days,
+    );
+    backend::jobs::spawn_idempotency_key_purger(&tasks, pool.clone());
+    backend::jobs::spawn_job_workers(&tasks, pool.clone(), app_state.config.job_workers);
+    backend::metrics::spawn_metrics_upkeep(&tasks, metrics);
 
     let app = create_router(app_state)
         .layer(TraceLayer::new_for_http())
         .layer(cors);
 
-    let bind_addr = "0.0.0.0:8080";
     let listener = tokio::net::TcpListener::bind(bind_addr)
         .await
         .map_err(|err| {
@@ -60,10 +139,70 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         })?;
 
     tracing::info!("listening on {}", listener.local_addr().unwrap());
-    axum::serve(listener, app).await.map_err(|err| {
-        eprintln!("Server error: {}", err);
-        err
-    })?;
+    let server = axum::serve(listener, app).with_graceful_shutdown(shutdown_signal(tasks.clone()));
+
+    // Connections still open `shutdown_timeout` after the signal are dropped
+    let drain_deadline = async {
+        tasks.cancelled().await;
+        tokio::time::sleep(shutdown_timeout).await;
+    };
+
+    tokio::select! {
+        result = server => result.map_err(|err| {
+            eprintln!("Server error: {}", err);
+            err
+        })?,
+        _ = drain_deadline => tracing::warn!(
+            "connections still open after {:?}, closing them",
+            shutdown_timeout
+        ),
+    }
+
+    if tasks.shutdown(shutdown_timeout).await {
+        tracing::info!("background tasks finished");
+    } else {
+        tracing::warn!(
+            "background tasks still running after {:?}, exiting anyway",
+            shutdown_timeout
+        );
+    }
+    pool.close().await;
 
     Ok(())
 }
+
+/// Resolves on Ctrl-C or SIGTERM, and tells the background tasks to stop
+/// scheduling new work while the server stops accepting connections and
+/// finishes the requests in flight.
+async fn shutdown_signal(tasks: TaskSupervisor) {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("failed to listen for Ctrl-C: {}", err);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::error!("failed to listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("shutting down, finishing open requests");
+    tasks.begin_shutdown();
+}
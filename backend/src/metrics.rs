@@ -0,0 +1,186 @@
+use metrics::{Unit, describe_counter, describe_gauge, describe_histogram};
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder, PrometheusHandle};
+use std::{convert::Infallible, time::Duration};
+use tracing::{
+    Event, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+use crate::{AppState, jobs::TaskSupervisor};
+
+/// Histogram buckets in seconds, for request and query durations.
+const DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// How often histograms are compacted between scrapes.
+const UPKEEP_INTERVAL_SECS: u64 = 5;
+
+/// Installs the global Prometheus recorder every metric is recorded into,
+/// and describes the metrics. Call once at startup; the handle renders them
+/// for `/metrics`.
+pub fn install() -> Result<PrometheusHandle, BuildError> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Suffix("_duration_seconds".into()),
+            DURATION_BUCKETS,
+        )?
+        .install_recorder()?;
+
+    describe_counter!(
+        "http_requests_total",
+        "Requests answered, by method, route and status"
+    );
+    describe_histogram!(
+        "http_request_duration_seconds",
+        Unit::Seconds,
+        "Time to answer a request, by method, route and status"
+    );
+    describe_histogram!(
+        "db_query_duration_seconds",
+        Unit::Seconds,
+        "Time to run a database query, by operation (select, insert, ...)"
+    );
+    describe_gauge!(
+        "db_pool_connections",
+        "Database connections, by state (idle or in_use)"
+    );
+    describe_gauge!(
+        "db_pool_max_connections",
+        "Most database connections the pool opens"
+    );
+    describe_counter!(
+        "jobs_processed_total",
+        "Queued jobs run by this server, by job type and outcome (succeeded, retried or dead)"
+    );
+    describe_gauge!("jobs", "Jobs in the queue, by job type and status");
+    describe_counter!(
+        "notifications_created_total",
+        "Notifications created, by category"
+    );
+    describe_gauge!(
+        "active_users",
+        "Users who made an authenticated request within the window (1d, 7d or 30d)"
+    );
+
+    Ok(handle)
+}
+
+/// Keeps the recorder's histograms compact; runs until shutdown.
+pub fn spawn_metrics_upkeep(tasks: &TaskSupervisor, handle: PrometheusHandle) {
+    tasks.spawn_periodic(
+        "metrics upkeep",
+        Duration::from_secs(UPKEEP_INTERVAL_SECS),
+        move || {
+            let handle = handle.clone();
+            async move {
+                handle.run_upkeep();
+                Ok::<_, Infallible>(())
+            }
+        },
+    );
+}
+
+/// Counts `count` new notifications of `category`.
+pub fn record_notifications(category: &'static str, count: u64) {
+    metrics::counter!("notifications_created_total", "category" => category).increment(count);
+}
+
+/// Renders every metric in the Prometheus text format. Gauges read from the
+/// database are refreshed first; if that fails they keep their last values.
+pub async fn render(state: &AppState) -> String {
+    let pool = &state.db;
+    let idle = pool.num_idle() as f64;
+    metrics::gauge!("db_pool_connections", "state" => "idle").set(idle);
+    metrics::gauge!("db_pool_connections", "state" => "in_use").set(pool.size() as f64 - idle);
+    metrics::gauge!("db_pool_max_connections").set(pool.options().get_max_connections() as f64);
+
+    if let Err(err) = refresh_database_gauges(pool).await {
+        tracing::error!("failed to refresh metrics from the database: {}", err);
+    }
+
+    state.metrics.render()
+}
+
+async fn refresh_database_gauges(db: &sqlx::PgPool) -> Result<(), sqlx::Error> {
+    // Every status of every job type, so counts that drop to 0 are reported
+    let jobs: Vec<(String, String, i64)> = sqlx::query_as(
+        "SELECT t.job_type, s.status, COUNT(j.job_id)
+        FROM (SELECT DISTINCT job_type FROM jobs) t
+        CROSS JOIN unnest(ARRAY['pending', 'running', 'succeeded', 'dead']) s(status)
+        LEFT JOIN jobs j ON j.job_type = t.job_type AND j.status = s.status
+        GROUP BY t.job_type, s.status",
+    )
+    .fetch_all(db)
+    .await?;
+    for (job_type, status, count) in jobs {
+        metrics::gauge!("jobs", "job_type" => job_type, "status" => status).set(count as f64);
+    }
+
+    let (day, week, month): (i64, i64, i64) = sqlx::query_as(
+        "SELECT
+            COUNT(*) FILTER (WHERE last_seen_at > now() - INTERVAL '1 day'),
+            COUNT(*) FILTER (WHERE last_seen_at > now() - INTERVAL '7 days'),
+            COUNT(*)
+        FROM users WHERE last_seen_at > now() - INTERVAL '30 days'",
+    )
+    .fetch_one(db)
+    .await?;
+    for (window, count) in [("1d", day), ("7d", week), ("30d", month)] {
+        metrics::gauge!("active_users", "window" => window).set(count as f64);
+    }
+
+    Ok(())
+}
+
+/// Records `db_query_duration_seconds` from the events sqlx logs for every
+/// query it runs (target `sqlx::query`, at debug level or warn for slow
+/// ones). Register it with a filter letting those events through.
+pub struct QueryMetricsLayer;
+
+impl<S: Subscriber> Layer<S> for QueryMetricsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != "sqlx::query" {
+            return;
+        }
+
+        let mut query = QueryEvent::default();
+        event.record(&mut query);
+        if let Some(elapsed_secs) = query.elapsed_secs {
+            metrics::histogram!("db_query_duration_seconds", "operation" => query.operation)
+                .record(elapsed_secs);
+        }
+    }
+}
+
+/// The fields of a sqlx query event this cares about. `summary` starts with
+/// the first words of the statement, e.g. `SELECT b.*, …`.
+#[derive(Default)]
+struct QueryEvent {
+    operation: &'static str,
+    elapsed_secs: Option<f64>,
+}
+
+impl Visit for QueryEvent {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = Some(value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "summary" {
+            let keyword = value.split_whitespace().next().unwrap_or_default();
+            // A fixed set of labels, so odd statements can't add new series
+            self.operation = [
+                "select", "insert", "update", "delete", "with", "begin", "commit",
+            ]
+            .into_iter()
+            .find(|operation| keyword.eq_ignore_ascii_case(operation))
+            .unwrap_or("other");
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
@@ -1,4 +1,8 @@
-use crate::{AppState, schema::ApiResponse, utils::{verify, helper::get_user_by_id}};
+use crate::{
+    AppState,
+    schema::ApiResponse,
+    utils::{helper::get_user_by_id, verify},
+};
 use axum::{
     body::Body,
     extract::{Request, State},
@@ -8,6 +12,9 @@ use axum::{
 };
 use std::sync::Arc;
 
+/// How stale `users.last_seen_at` may get before a request updates it.
+const LAST_SEEN_PRECISION_MINUTES: i64 = 5;
+
 pub async fn require_auth(
     State(state): State<Arc<AppState>>,
     mut req: Request<Body>,
@@ -29,7 +36,7 @@ pub async fn require_auth(
         }
     };
 
-    let user_id = match verify(&token) {
+    let user_id = match verify(&token, &state.config.jwt_secret) {
         Ok(c) => c,
         Err(err) => {
             return ApiResponse::<serde_json::Value>::error(&err, StatusCode::UNAUTHORIZED)
@@ -37,7 +44,7 @@ pub async fn require_auth(
         }
     };
 
-    let _user = match get_user_by_id(&state.db, &user_id).await {
+    let user = match get_user_by_id(&state.db, &user_id).await {
         Ok(Some(user)) => user,
         Ok(None) => {
             return ApiResponse::<serde_json::Value>::error(
@@ -51,6 +58,20 @@ pub async fn require_auth(
         }
     };
 
+    let seen_recently = user.last_seen_at.is_some_and(|last_seen| {
+        chrono::Utc::now() - last_seen < chrono::Duration::minutes(LAST_SEEN_PRECISION_MINUTES)
+    });
+    if !seen_recently {
+        let db = state.db.clone();
+        state.tasks.spawn("last seen update", async move {
+            sqlx::query("UPDATE users SET last_seen_at = now() WHERE user_id = $1")
+                .bind(user.user_id)
+                .execute(&db)
+                .await
+                .map(|_| ())
+        });
+    }
+
     req.extensions_mut().insert(user_id);
 
     next.run(req).await
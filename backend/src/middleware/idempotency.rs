@@ -0,0 +1,168 @@
+use crate::{
+    AppState,
+    schema::ApiResponse,
+    utils::{
+        attachment::MAX_ATTACHMENT_SIZE,
+        idempotency::{
+            IdempotencyClaim, MAX_IDEMPOTENCY_KEY_LENGTH, claim_idempotency_key,
+            release_idempotency_key, request_fingerprint, save_idempotent_response,
+        },
+    },
+};
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Header clients send to make a POST safe to retry.
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// Set on a response that was saved for an earlier request with the same key.
+pub const IDEMPOTENT_REPLAYED: HeaderName = HeaderName::from_static("idempotent-replayed");
+
+/// Largest request body that can be made idempotent, enough for an attachment
+/// upload.
+const MAX_BODY_SIZE: usize = MAX_ATTACHMENT_SIZE + 64 * 1024;
+
+/// Makes POST requests carrying an `Idempotency-Key` safe to retry. The first
+/// request with a key runs and its response is saved for
+/// `IDEMPOTENCY_KEY_TTL_HOURS`; a retry with the same key and the same request
+/// gets the saved response back (with `Idempotent-Replayed: true`) without
+/// running again. Reusing a key for a different request fails with 422, and a
+/// retry while the first request is still running with 409. Server errors
+/// aren't saved, so those requests can be retried for real.
+///
+/// Runs after `require_auth`; keys are per user.
+pub async fn idempotency(
+    State(state): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if req.method() != Method::POST {
+        return next.run(req).await;
+    }
+
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY) else {
+        return next.run(req).await;
+    };
+
+    let key = match key.to_str() {
+        Ok(key) if !key.trim().is_empty() && key.len() <= MAX_IDEMPOTENCY_KEY_LENGTH => {
+            key.trim().to_string()
+        }
+        _ => {
+            return ApiResponse::<serde_json::Value>::error(
+                "Idempotency-Key must be 1 to 255 visible ASCII characters",
+                StatusCode::BAD_REQUEST,
+            )
+            .into_response();
+        }
+    };
+
+    let Some(user_id) = req.extensions().get::<Uuid>().copied() else {
+        return next.run(req).await;
+    };
+
+    let (parts, body) = req.into_parts();
+    let body = match to_bytes(body, MAX_BODY_SIZE).await {
+        Ok(body) => body,
+        Err(_) => {
+            return ApiResponse::<serde_json::Value>::error(
+                "Request body is too large",
+                StatusCode::PAYLOAD_TOO_LARGE,
+            )
+            .into_response();
+        }
+    };
+
+    let fingerprint = request_fingerprint(&parts.method, &parts.uri, &body);
+    let claim = claim_idempotency_key(
+        &state.db,
+        user_id,
+        &key,
+        &fingerprint,
+        state.config.idempotency_key_ttl_hours,
+    )
+    .await;
+
+    let claimed_at = match claim {
+        Ok(IdempotencyClaim::Claimed { claimed_at }) => claimed_at,
+        Ok(IdempotencyClaim::Replay { status, body }) => {
+            return (
+                StatusCode::from_u16(status).unwrap_or(StatusCode::OK),
+                [
+                    (
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static("application/json"),
+                    ),
+                    (IDEMPOTENT_REPLAYED, HeaderValue::from_static("true")),
+                ],
+                body,
+            )
+                .into_response();
+        }
+        Ok(IdempotencyClaim::Mismatch) => {
+            return ApiResponse::<serde_json::Value>::error(
+                "This Idempotency-Key was already used for a different request",
+                StatusCode::UNPROCESSABLE_ENTITY,
+            )
+            .into_response();
+        }
+        Ok(IdempotencyClaim::InProgress) => {
+            return ApiResponse::<serde_json::Value>::error(
+                "A request with this Idempotency-Key is still being processed, retry later",
+                StatusCode::CONFLICT,
+            )
+            .into_response();
+        }
+        Err(err) => return ApiResponse::from(err).into_response(),
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    if response.status().is_server_error() {
+        if let Err(err) = release_idempotency_key(&state.db, user_id, &key, claimed_at).await {
+            tracing::error!("failed to release idempotency key: {}", err);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            if let Err(err) = release_idempotency_key(&state.db, user_id, &key, claimed_at).await {
+                tracing::error!("failed to release idempotency key: {}", err);
+            }
+            return ApiResponse::<serde_json::Value>::error(
+                &err.to_string(),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+            .into_response();
+        }
+    };
+
+    if let Err(err) = save_idempotent_response(
+        &state.db,
+        user_id,
+        &key,
+        claimed_at,
+        parts.status.as_u16(),
+        &body,
+    )
+    .await
+    {
+        // Without a saved response the key would look in progress until it expires
+        tracing::error!("failed to save idempotent response: {}", err);
+        if let Err(err) = release_idempotency_key(&state.db, user_id, &key, claimed_at).await {
+            tracing::error!("failed to release idempotency key: {}", err);
+        }
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
@@ -0,0 +1,70 @@
+use crate::{AppState, schema::ApiResponse};
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{sync::Arc, time::Instant};
+
+/// Counts and times every request by method, route and status. The route is
+/// the path pattern, like `/api/v1/expense/{id}`, so ids don't add series;
+/// requests matching no route share one label.
+pub async fn track_requests(req: Request<Body>, next: Next) -> Response {
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let start = Instant::now();
+    let response = next.run(req).await;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let labels = [
+        ("method", method),
+        ("route", route),
+        ("status", response.status().as_u16().to_string()),
+    ];
+    metrics::counter!("http_requests_total", &labels).increment(1);
+    metrics::histogram!("http_request_duration_seconds", &labels).record(elapsed);
+
+    response
+}
+
+/// Guards `/metrics` with `METRICS_TOKEN` when it's set: scrapers send it as
+/// `Authorization: Bearer <token>`. It's a separate secret from user logins.
+pub async fn require_metrics_token(
+    State(state): State<Arc<AppState>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(expected) = &state.config.metrics_token else {
+        return next.run(req).await;
+    };
+
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match token {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+            next.run(req).await
+        }
+        _ => ApiResponse::<serde_json::Value>::error(
+            "Invalid or missing metrics token",
+            StatusCode::UNAUTHORIZED,
+        )
+        .into_response(),
+    }
+}
+
+/// Compares without stopping at the first difference, so response times
+/// don't leak how much of a guessed token is right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
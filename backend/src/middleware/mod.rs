@@ -1,2 +1,4 @@
 pub mod auth;
-
+pub mod idempotency;
+pub mod metrics;
+pub mod version;
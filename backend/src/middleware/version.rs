@@ -0,0 +1,133 @@
+use crate::schema::ApiResponse;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::NaiveDate;
+use std::{fmt, str::FromStr};
+
+/// Header apps send their version in, e.g. `X-Client-Version: 1.4.0`.
+pub const CLIENT_VERSION: HeaderName = HeaderName::from_static("x-client-version");
+
+/// RFC 9745 header marking a route as deprecated.
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+/// RFC 8594 header with the date a route goes away.
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// A dotted client version like `1.4` or `1.4.2`; missing parts count as 0.
+/// Trailing zeros are dropped when parsing, so `1.4` and `1.4.0` compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ClientVersion(Vec<u64>);
+
+impl FromStr for ClientVersion {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value
+            .trim()
+            .trim_start_matches('v')
+            .split('.')
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Invalid client version '{}'", value))?;
+
+        while parts.len() > 1 && parts.last() == Some(&0) {
+            parts.pop();
+        }
+        Ok(ClientVersion(parts))
+    }
+}
+
+impl fmt::Display for ClientVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(u64::to_string).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+/// Turns away apps older than `MIN_CLIENT_VERSION` with 426, so they can ask
+/// the user to update instead of failing on changed responses. Requests
+/// without `X-Client-Version` (scripts, the web) are let through.
+pub async fn require_client_version(
+    State(min_version): State<Option<ClientVersion>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (Some(min_version), Some(version)) = (min_version, req.headers().get(CLIENT_VERSION))
+    else {
+        return next.run(req).await;
+    };
+
+    let version = match version.to_str().map(ClientVersion::from_str) {
+        Ok(Ok(version)) => version,
+        _ => {
+            return ApiResponse::<serde_json::Value>::error(
+                "X-Client-Version must be a version like 1.4.0",
+                StatusCode::BAD_REQUEST,
+            )
+            .into_response();
+        }
+    };
+
+    if version < min_version {
+        return ApiResponse::<serde_json::Value>::error(
+            &format!(
+                "App version {} is no longer supported, please update to {} or later",
+                version, min_version
+            ),
+            StatusCode::UPGRADE_REQUIRED,
+        )
+        .into_response();
+    }
+
+    next.run(req).await
+}
+
+/// When a set of routes was deprecated, when it stops working, and where its
+/// replacement lives.
+#[derive(Debug, Clone, Copy)]
+pub struct Deprecation {
+    pub deprecated_on: NaiveDate,
+    pub sunset_on: Option<NaiveDate>,
+    /// Prefix of the successor routes, e.g. `/api/v1`
+    pub successor: &'static str,
+}
+
+/// Adds `Deprecation`, `Sunset` and a `successor-version` link to every
+/// response of the routes it wraps. The routes keep working as before.
+pub async fn deprecated(
+    State(deprecation): State<Deprecation>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let successor = format!("{}{}", deprecation.successor, req.uri().path());
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+
+    let deprecated_at = deprecation
+        .deprecated_on
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc();
+    if let Ok(value) = HeaderValue::from_str(&format!("@{}", deprecated_at.timestamp())) {
+        headers.insert(DEPRECATION, value);
+    }
+
+    if let Some(sunset_on) = deprecation.sunset_on
+        && let Ok(value) =
+            HeaderValue::from_str(&sunset_on.format("%a, %d %b %Y 00:00:00 GMT").to_string())
+    {
+        headers.insert(SUNSET, value);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor))
+    {
+        headers.append(header::LINK, value);
+    }
+
+    response
+}
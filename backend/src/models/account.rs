@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct AccountModel {
+    pub account_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(rename = "accountType")]
+    pub account_type: String,
+    pub currency: String,
+    #[serde(rename = "openingBalance")]
+    pub opening_balance: Money,
+    #[serde(rename = "openingDate")]
+    pub opening_date: chrono::NaiveDate,
+    /// Current balance, when selected with `account_balance()`
+    #[sqlx(default)]
+    pub balance: Option<Money>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// One line of an account ledger, with the balance after it.
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct AccountEntryModel {
+    pub entry_id: Uuid,
+    /// `expense`, `income`, `refund`, `transfer_in` or `transfer_out`
+    #[serde(rename = "entryType")]
+    pub entry_type: String,
+    pub date: chrono::NaiveDate,
+    pub description: String,
+    /// Signed amount in the account currency; `None` if it couldn't be converted
+    pub amount: Option<Money>,
+    pub balance: Money,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct TransferModel {
+    pub transfer_id: Uuid,
+    pub user_id: Uuid,
+    pub from_account_id: Uuid,
+    pub to_account_id: Uuid,
+    pub amount: Money,
+    #[serde(rename = "toAmount")]
+    pub to_amount: Money,
+    pub date: chrono::NaiveDate,
+    pub description: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ReconciliationModel {
+    pub reconciliation_id: Uuid,
+    pub account_id: Uuid,
+    #[serde(rename = "statementDate")]
+    pub statement_date: chrono::NaiveDate,
+    #[serde(rename = "statementBalance")]
+    pub statement_balance: Money,
+    #[serde(rename = "computedBalance")]
+    pub computed_balance: Money,
+    /// Statement balance minus computed balance; zero when reconciled
+    #[sqlx(default)]
+    pub difference: Money,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
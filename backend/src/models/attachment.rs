@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct AttachmentModel {
+    pub attachment_id: Uuid,
+    pub expense_id: Uuid,
+    pub user_id: Uuid,
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    #[serde(rename = "contentType")]
+    pub content_type: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: i64,
+    #[serde(skip)]
+    pub storage_key: String,
+    #[serde(skip)]
+    pub thumbnail_key: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// One change to an expense, budget or category.
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct AuditLogModel {
+    pub audit_id: i64,
+    /// `expense`, `budget` or `category`
+    #[serde(rename = "entityType")]
+    pub entity_type: String,
+    #[serde(rename = "entityId")]
+    pub entity_id: String,
+    /// `create`, `update`, `delete`, `restore` or `purge`
+    pub action: String,
+    /// Who made the change; `None` for background jobs
+    pub user_id: Option<Uuid>,
+    #[sqlx(default)]
+    #[serde(rename = "userName")]
+    pub user_name: Option<String>,
+    /// Owner of the changed row
+    pub owner_id: Option<Uuid>,
+    pub household_id: Option<Uuid>,
+    /// Old values of the changed fields (the whole row for a delete)
+    #[serde(rename = "before")]
+    pub before_data: Option<serde_json::Value>,
+    /// New values of the changed fields (the whole row for a create)
+    #[serde(rename = "after")]
+    pub after_data: Option<serde_json::Value>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, Clone, ToSchema)]
+#[allow(non_snake_case)]
+pub struct BillModel {
+    pub bill_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub amount: Money,
+    pub currency: String,
+    /// First occurrence; later ones are counted from it
+    #[serde(rename = "startDate")]
+    pub start_date: chrono::NaiveDate,
+    /// Next unpaid occurrence
+    #[serde(rename = "dueDate")]
+    pub due_date: chrono::NaiveDate,
+    /// `none`, `weekly`, `monthly`, `quarterly` or `yearly`
+    pub recurrence: String,
+    pub autopay: bool,
+    #[serde(rename = "remindDaysBefore")]
+    pub remind_days_before: i32,
+    pub category_id: Option<i32>,
+    pub account_id: Option<Uuid>,
+    /// `false` once a one-off bill has been paid
+    #[serde(rename = "isActive")]
+    pub is_active: bool,
+    /// Active and past its due date, when selected with `BILL_COLUMNS`
+    #[sqlx(default)]
+    #[serde(rename = "isOverdue")]
+    pub is_overdue: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct BillPaymentModel {
+    pub payment_id: Uuid,
+    pub bill_id: Uuid,
+    /// Expense recorded for the payment
+    pub expense_id: Option<Uuid>,
+    /// Occurrence the payment was for
+    #[serde(rename = "dueDate")]
+    pub due_date: chrono::NaiveDate,
+    #[serde(rename = "paidOn")]
+    pub paid_on: chrono::NaiveDate,
+    pub amount: Money,
+    /// Paid by the scheduler rather than by hand
+    pub autopaid: bool,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -1,39 +1,59 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, FromRow, Debug)]
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
 #[allow(non_snake_case)]
 pub struct BudgetModel {
     pub budget_id: Uuid,
     pub name: String,
-    pub amount: i64,
+    pub amount: Money,
+    pub currency: String,
     #[serde(rename = "startDate")]
     pub start_date: chrono::NaiveDate,
     #[serde(rename = "endDate")]
     pub end_date: chrono::NaiveDate,
     pub user_id: Uuid,
+    pub category_id: Option<i32>,
+    pub household_id: Option<Uuid>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the row was moved to the trash; `None` while it's live
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(Serialize, Deserialize, FromRow, Debug)]
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
 #[allow(non_snake_case)]
 pub struct BudgetWithSpentModel {
     pub budget_id: Uuid,
     pub name: String,
-    pub amount: i64,
+    pub amount: Money,
+    pub currency: String,
     #[serde(rename = "startDate")]
     pub start_date: chrono::NaiveDate,
     #[serde(rename = "endDate")]
     pub end_date: chrono::NaiveDate,
     pub user_id: Uuid,
+    pub category_id: Option<i32>,
+    pub household_id: Option<Uuid>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the row was moved to the trash; `None` while it's live
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "totalSpent")]
-    pub total_spent: i64,
+    pub total_spent: Money,
+    /// Expenses left out of `total_spent` because no exchange rate to the
+    /// budget's currency is known for them
+    #[serde(rename = "unconvertedCount")]
+    #[sqlx(default)]
+    pub unconverted_count: i64,
 }
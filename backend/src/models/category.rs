@@ -1,16 +1,45 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, FromRow, Debug)]
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
 #[allow(non_snake_case)]
 pub struct CategoryModel {
     pub category_id: i32,
     pub user_id: Option<Uuid>,
+    pub household_id: Option<Uuid>,
     #[serde(rename = "categoryName")]
     pub category_name: String,
+    pub parent_id: Option<i32>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    #[sqlx(default)]
+    pub hidden: bool,
     #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the row was moved to the trash; `None` while it's live
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct CategoryTotalModel {
+    pub category_id: i32,
+    pub parent_id: Option<i32>,
+    #[serde(rename = "categoryName")]
+    pub category_name: String,
+    /// Spent on expenses assigned to this exact category
+    #[serde(rename = "directSpent")]
+    pub direct_spent: Money,
+    /// Spent on this category and all of its subcategories
+    #[serde(rename = "totalSpent")]
+    pub total_spent: Money,
+    #[serde(rename = "expenseCount")]
+    pub expense_count: i64,
 }
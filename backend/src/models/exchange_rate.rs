@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ExchangeRateModel {
+    pub rate_id: Uuid,
+    pub user_id: Option<Uuid>,
+    #[serde(rename = "baseCurrency")]
+    pub base_currency: String,
+    #[serde(rename = "quoteCurrency")]
+    pub quote_currency: String,
+    /// Decimal string, e.g. `"83.1245"`, to keep the stored precision
+    pub rate: String,
+    #[serde(rename = "rateDate")]
+    pub rate_date: chrono::NaiveDate,
+    pub source: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -1,20 +1,71 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, FromRow, Debug)]
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
 #[allow(non_snake_case)]
 pub struct ExpenseModel {
     pub expense_id: Uuid,
     pub name: String,
-    pub amount: i64,
+    pub amount: Money,
+    pub currency: String,
     pub date: chrono::NaiveDate,
     pub description: Option<String>,
     pub category_id: Option<i32>,
     pub user_id: Uuid,
     pub budget_id: Option<Uuid>,
+    /// `expense`, `income` or `refund`
+    #[serde(rename = "transactionType")]
+    pub transaction_type: String,
+    /// The expense a refund returns money for
+    pub original_expense_id: Option<Uuid>,
+    /// Account the money was paid from or received into
+    pub account_id: Option<Uuid>,
+    /// Household the expense is shared in; `None` for a personal expense
+    pub household_id: Option<Uuid>,
+    /// Member who paid; `user_id` is whoever recorded the expense
+    #[serde(rename = "paidBy")]
+    pub paid_by: Option<Uuid>,
+    /// How the expense is split between people; `None` when it isn't split
+    #[serde(rename = "splitMethod")]
+    pub split_method: Option<String>,
+    #[sqlx(default)]
+    pub tags: Vec<String>,
+    /// `amount` converted to the user's base currency at the expense date
+    #[sqlx(default)]
+    #[serde(rename = "convertedAmount")]
+    pub converted_amount: Option<Money>,
+    #[sqlx(default)]
+    #[serde(rename = "baseCurrency")]
+    pub base_currency: Option<String>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "updatedAt")]
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the row was moved to the trash; `None` while it's live
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Income and spending for one month of the cash-flow report, in the user's
+/// base currency.
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct CashFlowModel {
+    pub month: chrono::NaiveDate,
+    pub income: Money,
+    pub expenses: Money,
+    pub refunds: Money,
+    /// Expenses minus refunds
+    #[serde(rename = "netExpenses")]
+    pub net_expenses: Money,
+    /// Income minus net expenses
+    #[serde(rename = "netCashFlow")]
+    pub net_cash_flow: Money,
+    /// Net cash flow as a percentage of income; `None` for months without income
+    #[serde(rename = "savingsRate")]
+    pub savings_rate: Option<f64>,
 }
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct SavingsGoalModel {
+    pub goal_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(rename = "targetAmount")]
+    pub target_amount: Money,
+    pub currency: String,
+    #[serde(rename = "targetDate")]
+    pub target_date: Option<chrono::NaiveDate>,
+    /// Account the money is kept in
+    pub account_id: Option<Uuid>,
+    /// Highest milestone percentage (25, 50, 75 or 100) already notified
+    #[serde(rename = "lastMilestone")]
+    pub last_milestone: i16,
+    /// Sum of all contributions, when selected with `GOAL_QUERY`
+    #[sqlx(default)]
+    #[serde(rename = "savedAmount")]
+    pub saved_amount: Money,
+    /// Saved amount as a percentage of the target, when selected with `GOAL_QUERY`
+    #[sqlx(default)]
+    pub progress: f64,
+    /// What to put aside each month from now on to reach the target by its
+    /// date; `None` for goals without a target date
+    #[sqlx(skip)]
+    #[serde(rename = "requiredMonthlyContribution")]
+    pub required_monthly_contribution: Option<Money>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct GoalContributionModel {
+    pub contribution_id: Uuid,
+    pub goal_id: Uuid,
+    /// Negative for a withdrawal
+    pub amount: Money,
+    pub date: chrono::NaiveDate,
+    pub note: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
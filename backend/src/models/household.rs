@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct HouseholdModel {
+    pub household_id: Uuid,
+    pub name: String,
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<Uuid>,
+    /// Role of the requesting user in the household
+    #[sqlx(default)]
+    pub role: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct HouseholdMemberModel {
+    pub household_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    /// `owner`, `editor` or `viewer`
+    pub role: String,
+    #[serde(rename = "joinedAt")]
+    pub joined_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct HouseholdInvitationModel {
+    pub invitation_id: Uuid,
+    pub household_id: Uuid,
+    pub email: String,
+    pub role: String,
+    #[serde(rename = "invitedBy")]
+    pub invited_by: Option<Uuid>,
+    #[sqlx(default)]
+    #[serde(rename = "householdName")]
+    pub household_name: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A row of the `jobs` queue; see [`crate::jobs::Job`] for what it runs.
+#[derive(Serialize, Deserialize, FromRow, Debug)]
+#[allow(non_snake_case)]
+pub struct JobModel {
+    pub job_id: Uuid,
+    pub job_type: String,
+    pub payload: serde_json::Value,
+    /// `pending`, `running`, `succeeded` or `dead`
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub run_at: chrono::DateTime<chrono::Utc>,
+    pub locked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_error: Option<String>,
+    pub finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
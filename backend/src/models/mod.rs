@@ -1,11 +1,35 @@
+pub mod account;
+pub mod attachment;
+pub mod audit;
+pub mod bill;
 pub mod budget;
 pub mod category;
+pub mod exchange_rate;
 pub mod expense;
-pub mod user;
+pub mod goal;
+pub mod household;
+pub mod job;
+pub mod money;
 pub mod notification;
+pub mod rule;
+pub mod split;
+pub mod tag;
+pub mod user;
 
+pub use account::*;
+pub use attachment::*;
+pub use audit::*;
+pub use bill::*;
 pub use budget::*;
 pub use category::*;
+pub use exchange_rate::*;
 pub use expense::*;
-pub use user::*;
+pub use goal::*;
+pub use household::*;
+pub use job::*;
+pub use money::*;
 pub use notification::*;
+pub use rule::*;
+pub use split::*;
+pub use tag::*;
+pub use user::*;
@@ -0,0 +1,338 @@
+use serde::{Deserialize, Deserializer, Serialize, de};
+use std::fmt;
+use utoipa::{
+    PartialSchema, ToSchema,
+    openapi::{
+        KnownFormat, RefOr, SchemaFormat, Type,
+        schema::{ObjectBuilder, OneOfBuilder, Schema},
+    },
+};
+
+/// An amount of money in integer minor units of its currency (paise for INR,
+/// cents for USD, yen for JPY). The currency itself is stored next to it.
+///
+/// Serialized as a JSON integer of minor units, so `"amount": 1250` with
+/// `"currency": "INR"` is ₹12.50.
+#[derive(
+    Serialize,
+    Deserialize,
+    sqlx::Type,
+    ToSchema,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct Money(i64);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MoneyError {
+    InvalidFormat(String),
+    TooManyDecimals { value: String, exponent: u32 },
+    Overflow(String),
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::InvalidFormat(value) => write!(
+                f,
+                "Invalid amount '{}', expected a decimal string like \"12.50\"",
+                value
+            ),
+            MoneyError::TooManyDecimals { value, exponent } => write!(
+                f,
+                "Amount '{}' has more than {} decimal places for this currency",
+                value, exponent
+            ),
+            MoneyError::Overflow(value) => write!(f, "Amount '{}' is too large", value),
+        }
+    }
+}
+
+impl From<MoneyError> for String {
+    fn from(err: MoneyError) -> Self {
+        err.to_string()
+    }
+}
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    /// Largest accepted amount in minor units (just under 10^15), leaving room
+    /// to sum many amounts and rescale between currencies without overflowing.
+    pub const MAX_MINOR_UNITS: i64 = 999_999_999_999_999;
+
+    pub fn from_minor_units(minor_units: i64) -> Self {
+        Money(minor_units)
+    }
+
+    pub fn minor_units(self) -> i64 {
+        self.0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    /// Parses a decimal string in major units (`"12.5"`, `"-3"`, `"1000.00"`)
+    /// for a currency with the given minor unit exponent.
+    pub fn parse_decimal(value: &str, exponent: u32) -> Result<Self, MoneyError> {
+        let trimmed = value.trim();
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty()
+            || !is_digits(whole)
+            || !is_digits(fraction)
+            || (digits.contains('.') && fraction.is_empty())
+        {
+            return Err(MoneyError::InvalidFormat(value.to_string()));
+        }
+
+        // Trailing zeros never count against the exponent: "12.500" is fine for INR.
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > exponent as usize {
+            return Err(MoneyError::TooManyDecimals {
+                value: value.to_string(),
+                exponent,
+            });
+        }
+
+        let overflow = || MoneyError::Overflow(value.to_string());
+        let scale = 10i64.checked_pow(exponent).ok_or_else(overflow)?;
+        let whole: i64 = whole.parse().map_err(|_| overflow())?;
+        let fraction: i64 = if fraction.is_empty() {
+            0
+        } else {
+            let padding = 10i64.pow(exponent - fraction.len() as u32);
+            fraction.parse::<i64>().map_err(|_| overflow())? * padding
+        };
+
+        let minor_units = whole
+            .checked_mul(scale)
+            .and_then(|units| units.checked_add(fraction))
+            .filter(|units| *units <= Self::MAX_MINOR_UNITS)
+            .ok_or_else(overflow)?;
+
+        Ok(Money(if negative { -minor_units } else { minor_units }))
+    }
+
+    /// Formats as a decimal string in major units, e.g. `1250` with exponent 2
+    /// becomes `"12.50"`.
+    pub fn to_decimal(self, exponent: u32) -> String {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let units = self.0.unsigned_abs();
+        if exponent == 0 {
+            return format!("{}{}", sign, units);
+        }
+
+        let scale = 10u64.pow(exponent);
+        format!(
+            "{}{}.{:0width$}",
+            sign,
+            units / scale,
+            units % scale,
+            width = exponent as usize
+        )
+    }
+}
+
+/// An amount as sent by clients: either a decimal string in major units
+/// (`"12.50"`) or a JSON integer already in minor units (`1250`). Which one
+/// is meant is never guessed from the value itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoneyInput {
+    MinorUnits(i64),
+    Decimal(String),
+}
+
+impl MoneyInput {
+    /// Resolves the amount for a currency with the given minor unit exponent.
+    pub fn to_money(&self, exponent: u32) -> Result<Money, MoneyError> {
+        match self {
+            MoneyInput::MinorUnits(units)
+                if units.unsigned_abs() > Money::MAX_MINOR_UNITS as u64 =>
+            {
+                Err(MoneyError::Overflow(units.to_string()))
+            }
+            MoneyInput::MinorUnits(units) => Ok(Money(*units)),
+            MoneyInput::Decimal(value) => Money::parse_decimal(value, exponent),
+        }
+    }
+}
+
+impl PartialSchema for MoneyInput {
+    fn schema() -> RefOr<Schema> {
+        OneOfBuilder::new()
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .description(Some("Decimal amount in major units"))
+                    .examples(["12.50"]),
+            )
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    .format(Some(SchemaFormat::KnownFormat(KnownFormat::Int64)))
+                    .description(Some("Amount in minor units"))
+                    .examples([1250]),
+            )
+            .into()
+    }
+}
+
+impl ToSchema for MoneyInput {}
+
+impl<'de> Deserialize<'de> for MoneyInput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyInputVisitor;
+
+        impl de::Visitor<'_> for MoneyInputVisitor {
+            type Value = MoneyInput;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a decimal string like \"12.50\" or an integer amount in minor units")
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<MoneyInput, E> {
+                Ok(MoneyInput::MinorUnits(value))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<MoneyInput, E> {
+                i64::try_from(value)
+                    .map(MoneyInput::MinorUnits)
+                    .map_err(|_| E::custom(MoneyError::Overflow(value.to_string())))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<MoneyInput, E> {
+                Err(E::custom(format!(
+                    "Fractional amount {} must be sent as a decimal string, e.g. \"{}\"",
+                    value, value
+                )))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<MoneyInput, E> {
+                Ok(MoneyInput::Decimal(value.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(MoneyInputVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_strings() {
+        assert_eq!(Money::parse_decimal("12.5", 2), Ok(Money(1250)));
+        assert_eq!(Money::parse_decimal(" 1000.00 ", 2), Ok(Money(100000)));
+        assert_eq!(Money::parse_decimal("12.500", 2), Ok(Money(1250)));
+        assert_eq!(Money::parse_decimal("0.001", 3), Ok(Money(1)));
+    }
+
+    #[test]
+    fn parses_negative_amounts() {
+        assert_eq!(Money::parse_decimal("-3", 2), Ok(Money(-300)));
+        assert_eq!(Money::parse_decimal("-0.05", 2), Ok(Money(-5)));
+        assert!(matches!(
+            Money::parse_decimal("--3", 2),
+            Err(MoneyError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_non_numeric_input() {
+        for value in [
+            "", "abc", "12,50", "1e5", "12.", ".5", "+5", "1.2.3", "12 50",
+        ] {
+            assert!(
+                matches!(
+                    Money::parse_decimal(value, 2),
+                    Err(MoneyError::InvalidFormat(_))
+                ),
+                "{value:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_too_many_decimals() {
+        assert_eq!(
+            Money::parse_decimal("12.345", 2),
+            Err(MoneyError::TooManyDecimals {
+                value: "12.345".to_string(),
+                exponent: 2
+            })
+        );
+        assert!(matches!(
+            Money::parse_decimal("0.0001", 3),
+            Err(MoneyError::TooManyDecimals { .. })
+        ));
+    }
+
+    #[test]
+    fn handles_exponent_0_currencies() {
+        assert_eq!(Money::parse_decimal("1500", 0), Ok(Money(1500)));
+        assert_eq!(Money::parse_decimal("1500.00", 0), Ok(Money(1500)));
+        assert!(matches!(
+            Money::parse_decimal("1500.5", 0),
+            Err(MoneyError::TooManyDecimals { exponent: 0, .. })
+        ));
+        assert_eq!(Money(1500).to_decimal(0), "1500");
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        let max = Money::MAX_MINOR_UNITS;
+        assert_eq!(Money::parse_decimal(&max.to_string(), 0), Ok(Money(max)));
+        assert_eq!(Money::parse_decimal("9999999999999.99", 2), Ok(Money(max)));
+        for value in [
+            "10000000000000.00",
+            "99999999999999999999999",
+            "-10000000000000",
+        ] {
+            assert!(
+                matches!(Money::parse_decimal(value, 2), Err(MoneyError::Overflow(_))),
+                "{value:?} should overflow"
+            );
+        }
+        assert!(matches!(
+            MoneyInput::MinorUnits(max + 1).to_money(2),
+            Err(MoneyError::Overflow(_))
+        ));
+        assert!(matches!(
+            MoneyInput::MinorUnits(i64::MIN).to_money(2),
+            Err(MoneyError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn formats_decimals() {
+        assert_eq!(Money(1250).to_decimal(2), "12.50");
+        assert_eq!(Money(-5).to_decimal(2), "-0.05");
+        assert_eq!(Money(1).to_decimal(3), "0.001");
+    }
+
+    #[test]
+    fn deserializes_integers_as_minor_units_and_strings_as_decimals() {
+        let input: MoneyInput = serde_json::from_str("1250").unwrap();
+        assert_eq!(input, MoneyInput::MinorUnits(1250));
+        let input: MoneyInput = serde_json::from_str("\"12.50\"").unwrap();
+        assert_eq!(input.to_money(2), Ok(Money(1250)));
+        assert!(serde_json::from_str::<MoneyInput>("12.5").is_err());
+        assert!(serde_json::from_str::<MoneyInput>("18446744073709551615").is_err());
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ExpenseRuleModel {
+    pub rule_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub priority: i32,
+    #[serde(rename = "isActive")]
+    pub is_active: bool,
+    #[serde(rename = "nameContains")]
+    pub name_contains: Option<String>,
+    #[serde(rename = "nameRegex")]
+    pub name_regex: Option<String>,
+    #[serde(rename = "minAmount")]
+    pub min_amount: Option<Money>,
+    #[serde(rename = "maxAmount")]
+    pub max_amount: Option<Money>,
+    pub category_id: Option<i32>,
+    pub tags: Vec<String>,
+    pub budget_id: Option<Uuid>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct CategorySuggestionModel {
+    pub category_id: i32,
+    #[serde(rename = "categoryName")]
+    pub category_name: String,
+    /// Times the user picked this category for a matching expense name
+    pub uses: i64,
+    #[serde(rename = "lastUsed")]
+    pub last_used: chrono::NaiveDate,
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ContactModel {
+    pub contact_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// One participant's share of a split expense.
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct ExpenseSplitModel {
+    pub split_id: Uuid,
+    pub expense_id: Uuid,
+    /// Registered participant, or `None` for a contact
+    pub user_id: Option<Uuid>,
+    pub contact_id: Option<Uuid>,
+    /// User or contact name, when selected with a join
+    #[sqlx(default)]
+    pub name: Option<String>,
+    /// 1 for equal, the amount for exact, basis points for percent, the
+    /// number of shares for shares
+    pub weight: i64,
+    /// Share in minor units of the expense currency
+    pub amount: Money,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct SettlementModel {
+    pub settlement_id: Uuid,
+    pub user_id: Uuid,
+    pub household_id: Option<Uuid>,
+    #[serde(rename = "fromUserId")]
+    pub from_user_id: Option<Uuid>,
+    #[serde(rename = "fromContactId")]
+    pub from_contact_id: Option<Uuid>,
+    #[serde(rename = "toUserId")]
+    pub to_user_id: Option<Uuid>,
+    #[serde(rename = "toContactId")]
+    pub to_contact_id: Option<Uuid>,
+    pub amount: Money,
+    pub currency: String,
+    pub date: chrono::NaiveDate,
+    pub note: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Debts between two people in one currency, as returned by `split_debts()`
+/// with the participant names joined in.
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct SplitDebtModel {
+    pub debtor_user_id: Option<Uuid>,
+    pub debtor_contact_id: Option<Uuid>,
+    pub debtor_name: String,
+    pub creditor_user_id: Option<Uuid>,
+    pub creditor_contact_id: Option<Uuid>,
+    pub creditor_name: String,
+    pub currency: String,
+    pub amount: Money,
+}
+
+/// Someone taking part in a split: a registered user or a contact.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitParty {
+    pub user_id: Option<Uuid>,
+    pub contact_id: Option<Uuid>,
+    pub name: String,
+}
+
+/// `from` owes `to` the amount, in minor units of the currency.
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitBalance {
+    pub from: SplitParty,
+    pub to: SplitParty,
+    pub currency: String,
+    pub amount: Money,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::Money;
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct TagModel {
+    pub tag_id: i32,
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, Deserialize, FromRow, Debug, ToSchema)]
+#[allow(non_snake_case)]
+pub struct TagTotalModel {
+    pub tag_id: i32,
+    pub name: String,
+    #[serde(rename = "totalSpent")]
+    pub total_spent: Money,
+    #[serde(rename = "expenseCount")]
+    pub expense_count: i64,
+}
@@ -2,16 +2,19 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-
 #[derive(Serialize, Deserialize, FromRow, Debug)]
 #[allow(non_snake_case)]
 pub struct UserModel {
-  pub user_id: Uuid,
-  pub name: String,
-  pub email: String,
-  pub password_hash: String,
-  #[serde(rename = "createdAt")]
-  pub created_at: Option<chrono::DateTime<chrono::Utc>>,
-  #[serde(rename = "updatedAt")]
-  pub updated_at: Option<chrono::DateTime<chrono::Utc>>
-} 
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub password_hash: String,
+    #[serde(rename = "baseCurrency")]
+    pub base_currency: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(rename = "lastSeenAt")]
+    pub last_seen_at: Option<chrono::DateTime<chrono::Utc>>,
+}
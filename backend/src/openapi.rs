@@ -0,0 +1,199 @@
+use utoipa::{
+    Modify, OpenApi,
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+};
+
+use crate::{handlers::*, models::*, routes::*, schema::*};
+
+/// The OpenAPI document of the API, built from the `#[utoipa::path]`
+/// annotations on the handlers. Served at `/openapi.json`, with a browsable
+/// version at `/docs`.
+///
+/// Every handler mounted in `routes` has to be listed here; the `openapi`
+/// integration test fails for routes missing from the document.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "ExpTrack API",
+        description = "Every endpoint answers with the `ApiResponse` envelope: `success`, \
+            `status`, and `data` on success or `message` on errors. Amounts are integers \
+            in minor units of their currency; requests also take decimal strings like \
+            `\"12.50\"`.\n\n\
+            Apps send their version in `X-Client-Version`; versions older than the \
+            supported minimum get a 426. The same routes at the root, without \
+            `/api/v1`, are deprecated and answer with `Deprecation` and `Sunset` headers."
+    ),
+    servers((url = "/api/v1")),
+    paths(
+        health_check,
+        get_metrics,
+        get_jobs,
+        get_notifications,
+        // user
+        create_user,
+        login_user,
+        get_profile,
+        update_profile,
+        // expense
+        get_all_expenses,
+        create_expense,
+        batch_expenses,
+        bulk_update_expenses,
+        get_expense_by_id,
+        update_expense,
+        delete_expense,
+        get_expense_history,
+        get_expenses_by_budget_id,
+        get_expense_attachments,
+        upload_attachment,
+        download_attachment,
+        delete_attachment,
+        download_attachment_thumbnail,
+        // budget
+        get_all_budgets,
+        create_budget,
+        get_budget_by_id,
+        update_budget,
+        delete_budget,
+        // category
+        get_all_categories,
+        create_category,
+        suggest_category,
+        delete_category,
+        update_category,
+        merge_category,
+        move_category,
+        set_category_override,
+        // tag
+        get_all_tags,
+        create_tag,
+        update_tag,
+        delete_tag,
+        // report
+        get_category_report,
+        get_tag_report,
+        get_cashflow_report,
+        // rule
+        get_all_rules,
+        create_rule,
+        update_rule,
+        delete_rule,
+        // exchange rate
+        get_exchange_rates,
+        create_exchange_rate,
+        delete_exchange_rate,
+        // account
+        get_all_accounts,
+        create_account,
+        get_account_by_id,
+        update_account,
+        delete_account,
+        get_account_ledger,
+        reconcile_account,
+        get_account_reconciliations,
+        get_all_transfers,
+        create_transfer,
+        delete_transfer,
+        // household
+        get_all_households,
+        create_household,
+        get_household_by_id,
+        update_household,
+        delete_household,
+        invite_member,
+        update_member,
+        remove_member,
+        get_my_invitations,
+        delete_invitation,
+        accept_invitation,
+        // split
+        get_all_contacts,
+        create_contact,
+        update_contact,
+        delete_contact,
+        get_expense_split,
+        set_expense_split,
+        delete_expense_split,
+        get_split_balances,
+        get_settle_up,
+        get_all_settlements,
+        create_settlement,
+        delete_settlement,
+        // goal
+        get_all_goals,
+        create_goal,
+        get_goal_by_id,
+        update_goal,
+        delete_goal,
+        create_goal_contribution,
+        delete_goal_contribution,
+        // bill
+        get_all_bills,
+        create_bill,
+        get_bill_by_id,
+        update_bill,
+        delete_bill,
+        pay_bill,
+        get_upcoming_bills,
+        // trash, audit and sync
+        get_trash,
+        restore_from_trash,
+        get_audit_log,
+        sync_changes,
+    ),
+    components(schemas(
+        ApiResponse,
+        AccountModel,
+        AccountEntryModel,
+        TransferModel,
+        ReconciliationModel,
+        AttachmentModel,
+        AuditLogModel,
+        BillModel,
+        BillPaymentModel,
+        BudgetModel,
+        BudgetWithSpentModel,
+        CategoryModel,
+        CategoryTotalModel,
+        ExchangeRateModel,
+        ExpenseModel,
+        CashFlowModel,
+        SavingsGoalModel,
+        GoalContributionModel,
+        HouseholdModel,
+        HouseholdMemberModel,
+        HouseholdInvitationModel,
+        NotificationModel,
+        ExpenseRuleModel,
+        CategorySuggestionModel,
+        ContactModel,
+        ExpenseSplitModel,
+        SettlementModel,
+        SplitDebtModel,
+        SplitBalance,
+        TagModel,
+        TagTotalModel,
+    )),
+    modifiers(&BearerAuth),
+    security(("bearer_auth" = []))
+)]
+pub struct ApiDoc;
+
+/// Registers the `Authorization: Bearer <token>` scheme the login endpoint
+/// hands out tokens for.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
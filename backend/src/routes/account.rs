@@ -0,0 +1,33 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    handlers::{
+        create_account, create_transfer, delete_account, delete_transfer, get_account_by_id,
+        get_account_ledger, get_account_reconciliations, get_all_accounts, get_all_transfers,
+        reconcile_account, update_account,
+    },
+};
+
+pub fn get_account_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/account", get(get_all_accounts).post(create_account))
+        .route(
+            "/account/{id}",
+            get(get_account_by_id)
+                .put(update_account)
+                .delete(delete_account),
+        )
+        .route("/account/{id}/ledger", get(get_account_ledger))
+        .route("/account/{id}/reconcile", post(reconcile_account))
+        .route(
+            "/account/{id}/reconciliations",
+            get(get_account_reconciliations),
+        )
+        .route("/transfer", get(get_all_transfers).post(create_transfer))
+        .route("/transfer/{id}", delete(delete_transfer))
+}
@@ -0,0 +1,26 @@
+use axum::{Router, routing::get};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::{AppState, handlers::get_audit_log};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct AuditParams {
+    /// Only changes to `expense`, `budget` or `category` entities
+    pub entity_type: Option<String>,
+    /// Only changes to this entity; needs `entityType`
+    pub entity_id: Option<String>,
+    /// A household's changes instead of the user's personal ones
+    pub household_id: Option<Uuid>,
+    /// 50 by default, at most 200
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+pub fn get_audit_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/audit", get(get_audit_log))
+}
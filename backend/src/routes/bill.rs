@@ -0,0 +1,33 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::{
+    AppState,
+    handlers::{
+        create_bill, delete_bill, get_all_bills, get_bill_by_id, get_upcoming_bills, pay_bill,
+        update_bill,
+    },
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UpcomingBillsParams {
+    /// How many days ahead to look, 30 by default
+    pub days: Option<i64>,
+}
+
+pub fn get_bill_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/bill", get(get_all_bills).post(create_bill))
+        .route(
+            "/bill/{id}",
+            get(get_bill_by_id).put(update_bill).delete(delete_bill),
+        )
+        .route("/bill/{id}/pay", post(pay_bill))
+        .route("/bills/upcoming", get(get_upcoming_bills))
+}
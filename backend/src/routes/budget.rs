@@ -1,11 +1,22 @@
 use axum::{Router, routing::get};
+use serde::Deserialize;
 use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
 
 use crate::{
     AppState,
     handlers::{create_budget, delete_budget, get_all_budgets, get_budget_by_id, update_budget},
 };
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct BudgetParams {
+    /// A household's budgets instead of the user's personal ones
+    pub household_id: Option<Uuid>,
+}
+
 pub fn get_budget_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/budget", get(get_all_budgets).post(create_budget))
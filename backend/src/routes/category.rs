@@ -1,16 +1,64 @@
 use axum::{
     Router,
-    routing::{delete, get},
+    routing::{delete, get, post, put},
 };
+use serde::{Deserialize, Deserializer};
 use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
 
 use crate::{
     AppState,
-    handlers::{create_category, delete_category, get_all_categories},
+    handlers::{
+        create_category, delete_category, get_all_categories, merge_category, move_category,
+        set_category_override, suggest_category, update_category,
+    },
+    models::MoneyInput,
 };
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct CategoryParams {
+    /// Also return global categories the user has hidden
+    pub include_hidden: Option<bool>,
+    /// A household's categories instead of the user's personal ones
+    pub household_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SuggestParams {
+    pub name: String,
+    /// Amount in the user's base currency, read like JSON bodies read it:
+    /// an integer is minor units (`amount=1250`), a decimal is major units
+    /// (`amount=12.50`)
+    #[serde(default, deserialize_with = "query_amount")]
+    pub amount: Option<MoneyInput>,
+}
+
+/// Query strings carry no types, so an amount made only of digits counts as
+/// the JSON integer it would be in a body.
+fn query_amount<'de, D: Deserializer<'de>>(de: D) -> Result<Option<MoneyInput>, D::Error> {
+    let value = Option::<String>::deserialize(de)?;
+    Ok(match value.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(value) => Some(match value.parse::<i64>() {
+            Ok(units) => MoneyInput::MinorUnits(units),
+            Err(_) => MoneyInput::Decimal(value.to_string()),
+        }),
+    })
+}
+
 pub fn get_category_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/category", get(get_all_categories).post(create_category))
-        .route("/category/{id}", delete(delete_category))
+        .route("/category/suggest", get(suggest_category))
+        .route(
+            "/category/{id}",
+            delete(delete_category).patch(update_category),
+        )
+        .route("/category/{id}/merge-into/{target}", post(merge_category))
+        .route("/category/{id}/parent", put(move_category))
+        .route("/category/{id}/override", put(set_category_override))
 }
@@ -0,0 +1,34 @@
+use axum::{
+    Router,
+    routing::{delete, get},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::{
+    AppState,
+    handlers::{create_exchange_rate, delete_exchange_rate, get_exchange_rates},
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ExchangeRateParams {
+    /// Only rates where this currency is the base or the quote
+    pub currency: Option<String>,
+    pub start_date: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+    /// Also list the shared reference rates, not just the user's manual ones
+    #[serde(default)]
+    pub include_reference: bool,
+}
+
+pub fn get_exchange_rate_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route(
+            "/exchange-rate",
+            get(get_exchange_rates).post(create_exchange_rate),
+        )
+        .route("/exchange-rate/{id}", delete(delete_exchange_rate))
+}
@@ -1,20 +1,41 @@
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+};
 use serde::{Deserialize, Deserializer, de};
 use std::{fmt, str::FromStr, sync::Arc};
+use utoipa::IntoParams;
+use uuid::Uuid;
 
 use crate::{
     AppState,
     handlers::{
-        create_expense, delete_expense, get_all_expenses, get_expense_by_id,
-        get_expenses_by_budget_id, update_expense,
+        batch_expenses, bulk_update_expenses, create_expense, delete_attachment, delete_expense,
+        download_attachment, download_attachment_thumbnail, get_all_expenses,
+        get_expense_attachments, get_expense_by_id, get_expense_history, get_expenses_by_budget_id,
+        update_expense, upload_attachment,
     },
+    utils::attachment::MAX_ATTACHMENT_SIZE,
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct Params {
     #[serde(default, deserialize_with = "empty_string_as_none")]
     pub limit: Option<u32>,
     pub offset: Option<u32>,
+    /// Comma separated tag names, e.g. `work,reimbursable`
+    pub tags: Option<String>,
+    /// `any` (default) or `all` of the given tags
+    #[serde(rename = "tagMatch")]
+    pub tag_match: Option<String>,
+    /// Only transactions of this type: `expense`, `income` or `refund`
+    #[serde(rename = "type")]
+    pub transaction_type: Option<String>,
+    /// A household's expenses instead of the user's personal ones
+    #[serde(rename = "householdId")]
+    pub household_id: Option<Uuid>,
 }
 
 fn empty_string_as_none<'de, D, T>(de: D) -> Result<Option<T>, D::Error>
@@ -33,14 +54,32 @@ where
 pub fn get_expense_routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/expense", get(get_all_expenses).post(create_expense))
+        .route("/expense/batch", post(batch_expenses))
+        .route("/expense/bulk-update", post(bulk_update_expenses))
         .route(
             "/expense/{id}",
             get(get_expense_by_id)
                 .put(update_expense)
                 .delete(delete_expense),
         )
+        .route("/expense/{id}/history", get(get_expense_history))
         .route(
             "/expenses/budget/{budget_id}",
             get(get_expenses_by_budget_id),
         )
+        .route(
+            "/expense/{id}/attachments",
+            get(get_expense_attachments)
+                .post(upload_attachment)
+                // leave room for the multipart framing around the file itself
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_SIZE + 64 * 1024)),
+        )
+        .route(
+            "/attachments/{id}",
+            get(download_attachment).delete(delete_attachment),
+        )
+        .route(
+            "/attachments/{id}/thumbnail",
+            get(download_attachment_thumbnail),
+        )
 }
@@ -0,0 +1,27 @@
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    handlers::{
+        create_goal, create_goal_contribution, delete_goal, delete_goal_contribution,
+        get_all_goals, get_goal_by_id, update_goal,
+    },
+};
+
+pub fn get_goal_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/goal", get(get_all_goals).post(create_goal))
+        .route(
+            "/goal/{id}",
+            get(get_goal_by_id).put(update_goal).delete(delete_goal),
+        )
+        .route("/goal/{id}/contribution", post(create_goal_contribution))
+        .route(
+            "/goal/{id}/contribution/{contribution_id}",
+            delete(delete_goal_contribution),
+        )
+}
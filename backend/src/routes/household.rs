@@ -0,0 +1,33 @@
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    handlers::{
+        accept_invitation, create_household, delete_household, delete_invitation,
+        get_all_households, get_household_by_id, get_my_invitations, invite_member, remove_member,
+        update_household, update_member,
+    },
+};
+
+pub fn get_household_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/household", get(get_all_households).post(create_household))
+        .route(
+            "/household/{id}",
+            get(get_household_by_id)
+                .put(update_household)
+                .delete(delete_household),
+        )
+        .route("/household/{id}/invitation", post(invite_member))
+        .route(
+            "/household/{id}/member/{member}",
+            put(update_member).delete(remove_member),
+        )
+        .route("/invitation", get(get_my_invitations))
+        .route("/invitation/{id}", delete(delete_invitation))
+        .route("/invitation/{id}/accept", post(accept_invitation))
+}
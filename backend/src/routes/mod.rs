@@ -1,25 +1,67 @@
 use crate::{
     AppState,
+    jobs::{count_jobs, list_jobs},
     models::NotificationModel,
     schema::{ApiResponse, ApiResult},
 };
-use axum::{Extension, Json, extract::State, response::IntoResponse};
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use serde::Deserialize;
 use serde_json::json;
 use std::sync::Arc;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
+pub mod account;
+pub mod audit;
+pub mod bill;
 pub mod budget;
 pub mod category;
+pub mod exchange_rate;
 pub mod expense;
+pub mod goal;
+pub mod household;
+pub mod report;
 pub mod router;
+pub mod rule;
+pub mod split;
+pub mod sync;
+pub mod tag;
+pub mod trash;
 pub mod user;
 
+pub use account::get_account_routes;
+pub use audit::get_audit_routes;
+pub use bill::get_bill_routes;
 pub use budget::get_budget_routes;
 pub use category::get_category_routes;
+pub use exchange_rate::get_exchange_rate_routes;
 pub use expense::get_expense_routes;
+pub use goal::get_goal_routes;
+pub use household::get_household_routes;
+pub use report::get_report_routes;
 pub use router::create_router;
-pub use user::get_user_routes;
+pub use rule::get_rule_routes;
+pub use split::get_split_routes;
+pub use sync::get_sync_routes;
+pub use tag::get_tag_routes;
+pub use trash::get_trash_routes;
+pub use user::{get_profile_routes, get_user_routes};
 
+#[utoipa::path(
+    get,
+    path = "/health_check",
+    tag = "health",
+    summary = "Health check",
+    security(()),
+    responses(
+        (status = 200, description = "The server is up", body = serde_json::Value),
+    )
+)]
 pub async fn health_check() -> impl IntoResponse {
     const MESSAGE: &str = "Server is Working fine!";
 
@@ -31,6 +73,104 @@ pub async fn health_check() -> impl IntoResponse {
     Json(json_response)
 }
 
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    summary = "Prometheus metrics",
+    description = "Metrics in the Prometheus text format. When the server has `METRICS_TOKEN` \
+        set, it must be sent as `Authorization: Bearer <token>`; user tokens don't work here.",
+    security(()),
+    responses(
+        (status = 200, description = "The metrics", body = String, content_type = "text/plain"),
+        (status = 401, description = "Invalid or missing metrics token", body = ApiResponse),
+    )
+)]
+pub async fn get_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::render(&state).await,
+    )
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct JobParams {
+    /// `pending`, `running`, `succeeded` or `dead`
+    pub status: Option<String>,
+    /// e.g. `budget_alert`
+    pub job_type: Option<String>,
+    /// How many jobs to return, newest first; 50 by default, at most 200
+    pub limit: Option<i64>,
+}
+
+/// Background jobs and how they went, for operators looking into failed
+/// (`dead`) or stuck jobs. Jobs carry user data, so unlike `/metrics` this
+/// is only served when the server has `METRICS_TOKEN` set.
+#[utoipa::path(
+    get,
+    path = "/jobs",
+    tag = "health",
+    summary = "List background jobs",
+    description = "Needs the server's `METRICS_TOKEN` as `Authorization: Bearer <token>`; \
+        user tokens don't work here.",
+    security(()),
+    params(JobParams),
+    responses(
+        (status = 200, description = "Success; `data` has `jobs`, `counts`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
+pub async fn get_jobs(
+    Query(params): Query<JobParams>,
+    State(state): State<Arc<AppState>>,
+) -> ApiResult<serde_json::Value> {
+    if state.config.metrics_token.is_none() {
+        return Err(ApiResponse::error(
+            "Set METRICS_TOKEN on the server to inspect jobs",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    if let Some(ref status) = params.status
+        && !["pending", "running", "succeeded", "dead"].contains(&status.as_str())
+    {
+        return Err(ApiResponse::error(
+            "Status must be one of 'pending', 'running', 'succeeded' or 'dead'",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let jobs = list_jobs(
+        &state.db,
+        params.status.as_deref(),
+        params.job_type.as_deref(),
+        params.limit.unwrap_or(50),
+    )
+    .await?;
+    let counts: serde_json::Map<String, serde_json::Value> = count_jobs(&state.db)
+        .await?
+        .into_iter()
+        .map(|(status, count)| (status, json!(count)))
+        .collect();
+
+    Ok(ApiResponse::success(json!({
+        "jobs": jobs,
+        "counts": counts
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/notifcation",
+    tag = "notification",
+    summary = "Get notifications",
+    responses(
+        (status = 200, description = "Success; `data` has `notifications`, `count`", body = ApiResponse),
+        (status = "4XX", description = "Error; `message` says what went wrong", body = ApiResponse),
+    )
+)]
 pub async fn get_notifications(
     Extension(user_id): Extension<Uuid>,
     State(state): State<Arc<AppState>>,
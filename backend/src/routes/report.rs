@@ -0,0 +1,27 @@
+use axum::{Router, routing::get};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    handlers::{get_cashflow_report, get_category_report, get_tag_report},
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct ReportParams {
+    pub start_date: Option<chrono::NaiveDate>,
+    pub end_date: Option<chrono::NaiveDate>,
+    /// Report on a household instead of the user's personal data
+    pub household_id: Option<Uuid>,
+}
+
+pub fn get_report_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/report/categories", get(get_category_report))
+        .route("/report/tags", get(get_tag_report))
+        .route("/report/cashflow", get(get_cashflow_report))
+}
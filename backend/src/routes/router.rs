@@ -1,25 +1,94 @@
 use std::sync::Arc;
 
-use axum::{Router, middleware::from_fn_with_state, routing::get};
+use axum::{
+    Json, Router,
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
+};
+use chrono::NaiveDate;
+use utoipa::OpenApi;
+use utoipa_scalar::{Scalar, Servable};
 
-use super::health_check;
+use super::{get_jobs, get_metrics, health_check};
 use crate::{
     AppState,
-    middleware::auth::require_auth,
+    middleware::{
+        auth::require_auth,
+        idempotency::idempotency,
+        metrics::{require_metrics_token, track_requests},
+        version::{Deprecation, deprecated, require_client_version},
+    },
+    openapi::ApiDoc,
     routes::{
-        get_budget_routes, get_category_routes, get_expense_routes, get_notifications,
+        get_account_routes, get_audit_routes, get_bill_routes, get_budget_routes,
+        get_category_routes, get_exchange_rate_routes, get_expense_routes, get_goal_routes,
+        get_household_routes, get_notifications, get_profile_routes, get_report_routes,
+        get_rule_routes, get_split_routes, get_sync_routes, get_tag_routes, get_trash_routes,
         get_user_routes,
     },
 };
 
+/// The routes at the root, from before `/api/v1`. Shipped app versions still
+/// call them, so they keep working until the sunset.
+const UNVERSIONED_DEPRECATION: Deprecation = Deprecation {
+    deprecated_on: NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(),
+    sunset_on: NaiveDate::from_ymd_opt(2027, 4, 30),
+    successor: "/api/v1",
+};
+
+/// Mounts every version of the API side by side. A new version gets its own
+/// function like `api_v1` and is nested next to it under `/api/v2`; routes it
+/// doesn't change can be merged in from the same `get_*_routes` functions.
+/// Once it ships, `api_v1` gets a `deprecated` layer of its own.
 pub fn create_router(state: Arc<AppState>) -> Router {
+    Router::new()
+        .nest("/api/v1", api_v1(&state))
+        .merge(api_v1(&state).layer(from_fn_with_state(UNVERSIONED_DEPRECATION, deprecated)))
+        .layer(from_fn_with_state(
+            state.config.min_client_version.clone(),
+            require_client_version,
+        ))
+        .route("/health_check", get(health_check))
+        .route(
+            "/metrics",
+            get(get_metrics).route_layer(from_fn_with_state(
+                Arc::clone(&state),
+                require_metrics_token,
+            )),
+        )
+        .route(
+            "/jobs",
+            get(get_jobs).route_layer(from_fn_with_state(
+                Arc::clone(&state),
+                require_metrics_token,
+            )),
+        )
+        .route("/openapi.json", get(|| async { Json(ApiDoc::openapi()) }))
+        .merge(Scalar::with_url("/docs", ApiDoc::openapi()))
+        .layer(from_fn(track_requests))
+        .with_state(state)
+}
+
+fn api_v1(state: &Arc<AppState>) -> Router<Arc<AppState>> {
     Router::new()
         .route("/notifcation", get(get_notifications))
         .merge(get_expense_routes())
         .merge(get_budget_routes())
         .merge(get_category_routes())
-        .layer(from_fn_with_state(Arc::clone(&state), require_auth))
+        .merge(get_tag_routes())
+        .merge(get_report_routes())
+        .merge(get_rule_routes())
+        .merge(get_exchange_rate_routes())
+        .merge(get_profile_routes())
+        .merge(get_account_routes())
+        .merge(get_household_routes())
+        .merge(get_split_routes())
+        .merge(get_goal_routes())
+        .merge(get_bill_routes())
+        .merge(get_trash_routes())
+        .merge(get_audit_routes())
+        .merge(get_sync_routes())
+        .layer(from_fn_with_state(Arc::clone(state), idempotency))
+        .layer(from_fn_with_state(Arc::clone(state), require_auth))
         .merge(get_user_routes())
-        .route("/health_check", get(health_check))
-        .with_state(state)
 }
@@ -0,0 +1,16 @@
+use axum::{
+    Router,
+    routing::{get, put},
+};
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    handlers::{create_rule, delete_rule, get_all_rules, update_rule},
+};
+
+pub fn get_rule_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/rule", get(get_all_rules).post(create_rule))
+        .route("/rule/{id}", put(update_rule).delete(delete_rule))
+}
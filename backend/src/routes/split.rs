@@ -0,0 +1,46 @@
+use axum::{
+    Router,
+    routing::{delete, get, put},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    handlers::{
+        create_contact, create_settlement, delete_contact, delete_expense_split, delete_settlement,
+        get_all_contacts, get_all_settlements, get_expense_split, get_settle_up,
+        get_split_balances, set_expense_split, update_contact,
+    },
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct SplitParams {
+    /// A household's balances instead of the user's personal ones
+    pub household_id: Option<Uuid>,
+    /// Only balances in this currency
+    pub currency: Option<String>,
+}
+
+pub fn get_split_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/contact", get(get_all_contacts).post(create_contact))
+        .route("/contact/{id}", put(update_contact).delete(delete_contact))
+        .route(
+            "/expense/{id}/split",
+            get(get_expense_split)
+                .put(set_expense_split)
+                .delete(delete_expense_split),
+        )
+        .route("/split/balances", get(get_split_balances))
+        .route("/split/settle-up", get(get_settle_up))
+        .route(
+            "/settlement",
+            get(get_all_settlements).post(create_settlement),
+        )
+        .route("/settlement/{id}", delete(delete_settlement))
+}
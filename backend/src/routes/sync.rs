@@ -0,0 +1,8 @@
+use axum::{Router, routing::post};
+use std::sync::Arc;
+
+use crate::{AppState, handlers::sync_changes};
+
+pub fn get_sync_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/sync", post(sync_changes))
+}
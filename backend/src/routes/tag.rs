@@ -0,0 +1,16 @@
+use axum::{
+    Router,
+    routing::{get, put},
+};
+use std::sync::Arc;
+
+use crate::{
+    AppState,
+    handlers::{create_tag, delete_tag, get_all_tags, update_tag},
+};
+
+pub fn get_tag_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/tag", get(get_all_tags).post(create_tag))
+        .route("/tag/{id}", put(update_tag).delete(delete_tag))
+}
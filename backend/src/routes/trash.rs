@@ -0,0 +1,27 @@
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    handlers::{get_trash, restore_from_trash},
+};
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query)]
+pub struct TrashParams {
+    /// A household's trash instead of the user's personal one
+    pub household_id: Option<Uuid>,
+}
+
+pub fn get_trash_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/trash", get(get_trash))
+        .route("/trash/{type}/{id}/restore", post(restore_from_trash))
+}
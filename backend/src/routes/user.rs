@@ -1,9 +1,12 @@
-use axum::{Router, routing::post};
+use axum::{
+    Router,
+    routing::{get, post},
+};
 use std::sync::Arc;
 
 use crate::{
     AppState,
-    handlers::{create_user, login_user},
+    handlers::{create_user, get_profile, login_user, update_profile},
 };
 
 pub fn get_user_routes() -> Router<Arc<AppState>> {
@@ -11,3 +14,7 @@ pub fn get_user_routes() -> Router<Arc<AppState>> {
         .route("/user", post(create_user))
         .route("/user/{email}", post(login_user))
 }
+
+pub fn get_profile_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/profile", get(get_profile).patch(update_profile))
+}
@@ -0,0 +1,46 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::models::MoneyInput;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccountSchema {
+    pub name: String,
+    /// `cash`, `bank`, `debit_card`, `credit_card` or `wallet`
+    pub account_type: String,
+    pub currency: Option<String>,
+    pub opening_balance: Option<MoneyInput>,
+    pub opening_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAccountSchema {
+    pub name: Option<String>,
+    pub account_type: Option<String>,
+    pub opening_balance: Option<MoneyInput>,
+    pub opening_date: Option<chrono::NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTransferSchema {
+    pub from_account_id: String,
+    pub to_account_id: String,
+    /// In the source account currency
+    pub amount: MoneyInput,
+    /// In the destination account currency; only needed between currencies
+    /// when the stored exchange rate shouldn't be used
+    pub to_amount: Option<MoneyInput>,
+    pub date: chrono::NaiveDate,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileAccountSchema {
+    pub statement_date: chrono::NaiveDate,
+    /// Balance on the statement, in the account currency
+    pub statement_balance: MoneyInput,
+}
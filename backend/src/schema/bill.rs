@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::models::MoneyInput;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBillSchema {
+    pub name: String,
+    pub amount: MoneyInput,
+    pub currency: Option<String>,
+    pub due_date: chrono::NaiveDate,
+    /// `none` (default), `weekly`, `monthly`, `quarterly` or `yearly`
+    pub recurrence: Option<String>,
+    pub autopay: Option<bool>,
+    /// Days before the due date to send a reminder, 3 by default
+    pub remind_days_before: Option<i32>,
+    pub category_id: Option<i32>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBillSchema {
+    pub name: Option<String>,
+    pub amount: Option<MoneyInput>,
+    /// Moves the next occurrence; later ones are counted from it
+    pub due_date: Option<chrono::NaiveDate>,
+    pub recurrence: Option<String>,
+    pub autopay: Option<bool>,
+    pub remind_days_before: Option<i32>,
+    pub category_id: Option<i32>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PayBillSchema {
+    /// Defaults to the bill amount, e.g. for a utility bill that varies
+    pub amount: Option<MoneyInput>,
+    /// Defaults to today
+    pub paid_on: Option<chrono::NaiveDate>,
+    /// Defaults to the bill's account
+    pub account_id: Option<String>,
+}
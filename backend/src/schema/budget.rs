@@ -1,19 +1,33 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
+use crate::models::MoneyInput;
+
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateBudgetSchema {
+    /// Client-generated id, for clients that create budgets offline
+    pub budget_id: Option<String>,
     pub name: String,
-    pub amount: i64,
+    pub amount: MoneyInput,
+    pub currency: Option<String>,
     pub start_date: chrono::NaiveDate,
     pub end_date: chrono::NaiveDate,
+    pub category_id: Option<i32>,
+    /// Household to share the budget in; personal when left out
+    pub household_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateBudgetSchema {
     pub name: Option<String>,
-    pub amount: Option<i64>,
+    pub amount: Option<MoneyInput>,
+    pub currency: Option<String>,
     pub start_date: Option<chrono::NaiveDate>,
     pub end_date: Option<chrono::NaiveDate>,
+    /// `null` turns the budget into one only covering the expenses linked to it
+    #[serde(default, deserialize_with = "crate::schema::nullable")]
+    #[schema(value_type = Option<i32>)]
+    pub category_id: Option<Option<i32>>,
 }
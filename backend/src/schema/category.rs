@@ -1,7 +1,33 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateCategorySchema {
     pub category_name: String,
+    pub parent_id: Option<i32>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+    /// Household to share the category in; personal when left out
+    pub household_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCategorySchema {
+    pub category_name: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveCategorySchema {
+    pub parent_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryOverrideSchema {
+    pub hidden: bool,
 }
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateExchangeRateSchema {
+    pub base_currency: String,
+    pub quote_currency: String,
+    /// Units of quote currency per unit of base currency, as a decimal string
+    pub rate: String,
+    pub rate_date: chrono::NaiveDate,
+}
@@ -1,23 +1,98 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
 
-#[derive(Debug, Deserialize)]
+use crate::models::MoneyInput;
+
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateExpenseSchema {
+    /// Client-generated id, for clients that create expenses offline
+    pub expense_id: Option<String>,
     pub name: String,
-    pub amount: i64,
+    pub amount: MoneyInput,
+    pub currency: Option<String>,
     pub date: chrono::NaiveDate,
     pub description: Option<String>,
     pub category_id: Option<i32>,
     pub budget_id: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// `expense` (default), `income` or `refund`
+    pub transaction_type: Option<String>,
+    /// Required for refunds: the expense the money is returned for
+    pub original_expense_id: Option<String>,
+    pub account_id: Option<String>,
+    /// Household to share the expense in; personal when left out
+    pub household_id: Option<String>,
+    /// Member who paid, defaults to the current user
+    pub paid_by: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateExpenseSchema {
     pub name: Option<String>,
-    pub amount: Option<i64>,
+    pub amount: Option<MoneyInput>,
+    pub currency: Option<String>,
     pub date: Option<chrono::NaiveDate>,
     pub description: Option<String>,
     pub category_id: Option<i32>,
     pub budget_id: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub transaction_type: Option<String>,
+    pub original_expense_id: Option<String>,
+    pub account_id: Option<String>,
+    pub paid_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchExpenseSchema {
+    pub operations: Vec<BatchExpenseOperationSchema>,
+    /// Roll everything back when an operation fails (default); otherwise only
+    /// the failed operations are left out
+    pub atomic: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchExpenseOperationSchema {
+    /// `create`, `update` or `delete`
+    pub op: String,
+    /// Expense to update or delete
+    pub id: Option<String>,
+    /// Entity tag the expense must still have, like an `If-Match` header
+    pub if_match: Option<String>,
+    /// Fields as for `POST /expense` or `PUT /expense/{id}`
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkUpdateExpensesSchema {
+    pub filter: ExpenseFilterSchema,
+    /// Fields to change on every matching expense, as for `PUT /expense/{id}`
+    pub set: UpdateExpenseSchema,
+}
+
+/// Expenses of one workspace to change at once. Conditions are combined; at
+/// least one is needed.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseFilterSchema {
+    /// A household's expenses instead of the user's personal ones
+    pub household_id: Option<Uuid>,
+    pub category_id: Option<i32>,
+    /// Only expenses without a category
+    pub uncategorized: Option<bool>,
+    pub budget_id: Option<Uuid>,
+    /// `expense`, `income` or `refund`
+    pub transaction_type: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// `any` (default) or `all` of the given tags
+    pub tag_match: Option<String>,
+    pub from: Option<chrono::NaiveDate>,
+    pub to: Option<chrono::NaiveDate>,
+    /// Part of the name, ignoring case
+    pub search: Option<String>,
 }
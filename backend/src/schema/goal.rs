@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::models::MoneyInput;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGoalSchema {
+    pub name: String,
+    pub target_amount: MoneyInput,
+    /// Defaults to the linked account's currency, or the user's base currency
+    pub currency: Option<String>,
+    pub target_date: Option<chrono::NaiveDate>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateGoalSchema {
+    pub name: Option<String>,
+    pub target_amount: Option<MoneyInput>,
+    pub target_date: Option<chrono::NaiveDate>,
+    pub account_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateContributionSchema {
+    /// In the goal currency; negative to withdraw
+    pub amount: MoneyInput,
+    pub date: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+}
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateHouseholdSchema {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateHouseholdSchema {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InviteMemberSchema {
+    pub email: String,
+    /// `owner`, `editor` (default) or `viewer`
+    pub role: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMemberSchema {
+    pub role: String,
+}
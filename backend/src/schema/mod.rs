@@ -1,25 +1,61 @@
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 
+pub mod account;
+pub mod bill;
 pub mod budget;
 pub mod category;
+pub mod exchange_rate;
 pub mod expense;
-pub mod user;
+pub mod goal;
+pub mod household;
 pub mod notification;
+pub mod rule;
+pub mod split;
+pub mod sync;
+pub mod tag;
+pub mod user;
 
+pub use account::*;
+pub use bill::*;
 pub use budget::*;
 pub use category::*;
+pub use exchange_rate::*;
 pub use expense::*;
-pub use user::*;
+pub use goal::*;
+pub use household::*;
 pub use notification::*;
+pub use rule::*;
+pub use split::*;
+pub use sync::*;
+pub use tag::*;
+pub use user::*;
 
 use axum::{
     Json,
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use utoipa::{
+    PartialSchema, ToSchema,
+    openapi::{
+        RefOr, Type,
+        schema::{ObjectBuilder, Schema},
+    },
+};
+
+/// For nullable fields of partial updates, with `#[serde(default)]`: `None`
+/// when the field is left out, `Some(None)` when it is `null` (clear it),
+/// `Some(Some(value))` otherwise.
+pub fn nullable<'de, T, D>(de: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(de).map(Some)
+}
 
 #[derive(Serialize, Debug)]
-pub struct ApiResponse<T: Serialize> {
+pub struct ApiResponse<T: Serialize = serde_json::Value> {
     pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
@@ -103,4 +139,40 @@ impl From<uuid::Error> for ApiResponse<serde_json::Value> {
     }
 }
 
+// The envelope every endpoint answers with. `data` differs per endpoint and is
+// described in the endpoint's docs.
+impl<T: Serialize> PartialSchema for ApiResponse<T> {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .property("success", ObjectBuilder::new().schema_type(Type::Boolean))
+            .required("success")
+            .property(
+                "message",
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .description(Some("Set on errors")),
+            )
+            .property(
+                "data",
+                ObjectBuilder::new()
+                    .schema_type(Type::Object)
+                    .description(Some("Set on success")),
+            )
+            .property(
+                "status",
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    .description(Some("HTTP status code of the response")),
+            )
+            .required("status")
+            .into()
+    }
+}
+
+impl<T: Serialize> ToSchema for ApiResponse<T> {
+    fn name() -> std::borrow::Cow<'static, str> {
+        "ApiResponse".into()
+    }
+}
+
 pub type ApiResult<T> = Result<ApiResponse<T>, ApiResponse<serde_json::Value>>;
@@ -1,7 +1,8 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NotificationSchema {
-    pub category: String
-}
\ No newline at end of file
+    pub category: String,
+}
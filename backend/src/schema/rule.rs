@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::models::MoneyInput;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRuleSchema {
+    pub name: String,
+    pub priority: Option<i32>,
+    pub is_active: Option<bool>,
+    pub name_contains: Option<String>,
+    pub name_regex: Option<String>,
+    pub min_amount: Option<MoneyInput>,
+    pub max_amount: Option<MoneyInput>,
+    pub category_id: Option<i32>,
+    pub tags: Option<Vec<String>>,
+    pub budget_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRuleSchema {
+    pub name: Option<String>,
+    pub priority: Option<i32>,
+    pub is_active: Option<bool>,
+    pub name_contains: Option<String>,
+    pub name_regex: Option<String>,
+    pub min_amount: Option<MoneyInput>,
+    pub max_amount: Option<MoneyInput>,
+    pub category_id: Option<i32>,
+    pub tags: Option<Vec<String>>,
+    pub budget_id: Option<String>,
+}
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::models::MoneyInput;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateContactSchema {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateContactSchema {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// A participant of a split: set exactly one of `userId` and `contactId`, plus
+/// the value the split method needs.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitParticipantSchema {
+    pub user_id: Option<String>,
+    pub contact_id: Option<String>,
+    /// For `exact`: the participant's share in the expense currency
+    pub amount: Option<MoneyInput>,
+    /// For `percent`: e.g. `33.33`, at most two decimal places
+    pub percent: Option<f64>,
+    /// For `shares`: a whole number of shares, at most 1000000
+    pub shares: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SetExpenseSplitSchema {
+    /// `equal`, `exact`, `percent` or `shares`
+    pub method: String,
+    pub participants: Vec<SplitParticipantSchema>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSettlementSchema {
+    /// Household the debt belongs to; personal when left out
+    pub household_id: Option<String>,
+    /// Who paid: set exactly one of `fromUserId` and `fromContactId`
+    pub from_user_id: Option<String>,
+    pub from_contact_id: Option<String>,
+    /// Who was paid: set exactly one of `toUserId` and `toContactId`
+    pub to_user_id: Option<String>,
+    pub to_contact_id: Option<String>,
+    pub amount: MoneyInput,
+    /// Defaults to the user's base currency
+    pub currency: Option<String>,
+    pub date: Option<chrono::NaiveDate>,
+    pub note: Option<String>,
+}
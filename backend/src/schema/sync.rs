@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSchema {
+    /// `syncToken` of the previous sync; without one everything is sent
+    pub sync_token: Option<String>,
+    /// Changes made offline, applied in order
+    #[serde(default)]
+    pub mutations: Vec<SyncMutationSchema>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncMutationSchema {
+    /// `expense` or `budget`
+    pub entity_type: String,
+    /// Client-generated id of the entity
+    pub id: String,
+    /// `upsert` or `delete`
+    pub op: String,
+    /// `updatedAt` of the server copy the change was made on; left out for
+    /// entities created offline
+    pub base_updated_at: Option<DateTime<Utc>>,
+    /// Fields as for creating the entity, or the changed ones when it exists
+    pub data: Option<serde_json::Value>,
+}
@@ -1,13 +1,23 @@
 use serde::Deserialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateUserSchema {
     pub name: String,
     pub email: String,
     pub password: String,
+    #[serde(rename = "baseCurrency")]
+    pub base_currency: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginUserSchema {
     pub password: String,
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProfileSchema {
+    pub name: Option<String>,
+    pub base_currency: Option<String>,
+}
@@ -0,0 +1,100 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::{io::ErrorKind, path::PathBuf};
+
+use super::Storage;
+
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, String> {
+        if key.is_empty() || key.starts_with('/') || key.split('/').any(|part| part == "..") {
+            return Err(format!("Invalid storage key '{}'", key));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, data: Bytes, _content_type: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        tokio::fs::write(&path, &data)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, String> {
+        let path = self.path_for(key)?;
+        tokio::fs::read(&path)
+            .await
+            .map(Bytes::from)
+            .map_err(|e| e.to_string())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_storage() -> (LocalStorage, PathBuf) {
+        let root =
+            std::env::temp_dir().join(format!("backend-storage-test-{}", uuid::Uuid::new_v4()));
+        (LocalStorage::new(&root), root)
+    }
+
+    #[tokio::test]
+    async fn stores_files_under_the_root() {
+        let (storage, root) = temp_storage();
+        super::super::check_storage(&storage).await;
+
+        storage
+            .put(
+                "user/expense/file",
+                Bytes::from_static(b"data"),
+                "text/plain",
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read(root.join("user/expense/file")).unwrap(),
+            b"data"
+        );
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_keys_outside_the_root() {
+        let (storage, root) = temp_storage();
+        for key in ["", "/etc/passwd", "../escape", "user/../../escape"] {
+            assert!(
+                storage.put(key, Bytes::new(), "text/plain").await.is_err(),
+                "{key:?}"
+            );
+            assert!(storage.get(key).await.is_err(), "{key:?}");
+            assert!(storage.delete(key).await.is_err(), "{key:?}");
+        }
+        assert!(!root.exists());
+    }
+}
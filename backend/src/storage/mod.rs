@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use std::sync::Arc;
+
+use crate::config::StorageConfig;
+
+pub mod local;
+pub mod s3;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// Blob storage used for expense attachments.
+///
+/// Keys are generated by the server (`{user_id}/{expense_id}/{attachment_id}`),
+/// so implementations only need to map them onto their own namespace.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<(), String>;
+
+    async fn get(&self, key: &str) -> Result<Bytes, String>;
+
+    /// Deleting a key that doesn't exist is not an error.
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+/// Builds the storage backend selected by `STORAGE_BACKEND` (`local` or `s3`).
+///
+/// * `local` - files are written under `STORAGE_LOCAL_PATH` (default `./uploads`)
+/// * `s3` - any S3-compatible service, configured with `S3_BUCKET`, `S3_ENDPOINT`,
+///   `S3_REGION`, `S3_ACCESS_KEY_ID` and `S3_SECRET_ACCESS_KEY`
+pub fn from_config(config: &StorageConfig) -> Result<Arc<dyn Storage>, String> {
+    match config {
+        StorageConfig::Local { path } => Ok(Arc::new(LocalStorage::new(path.clone()))),
+        StorageConfig::S3 {
+            bucket,
+            region,
+            endpoint,
+            access_key_id,
+            secret_access_key,
+        } => Ok(Arc::new(S3Storage::new(
+            bucket,
+            region,
+            endpoint.clone(),
+            access_key_id.clone(),
+            secret_access_key.clone(),
+        )?)),
+    }
+}
+
+/// Best-effort removal of stored files, used after the owning rows are gone.
+pub async fn delete_keys(storage: &dyn Storage, keys: Vec<String>) {
+    for key in keys {
+        if let Err(err) = storage.delete(&key).await {
+            tracing::warn!("Failed to delete stored file {}: {}", key, err);
+        }
+    }
+}
+
+/// Behaviour every backend must have; each backend's tests run it.
+#[cfg(test)]
+async fn check_storage(storage: &dyn Storage) {
+    let prefix = uuid::Uuid::new_v4();
+    let key = format!("{}/expense/attachment", prefix);
+
+    storage
+        .put(&key, Bytes::from_static(b"first"), "text/plain")
+        .await
+        .unwrap();
+    assert_eq!(
+        storage.get(&key).await.unwrap(),
+        Bytes::from_static(b"first")
+    );
+
+    storage
+        .put(&key, Bytes::from_static(b"second"), "text/plain")
+        .await
+        .unwrap();
+    assert_eq!(
+        storage.get(&key).await.unwrap(),
+        Bytes::from_static(b"second")
+    );
+
+    let other = format!("{}/expense/other", prefix);
+    storage
+        .put(&other, Bytes::new(), "application/octet-stream")
+        .await
+        .unwrap();
+    assert!(storage.get(&other).await.unwrap().is_empty());
+
+    storage.delete(&key).await.unwrap();
+    assert!(storage.get(&key).await.is_err());
+    storage.delete(&key).await.unwrap();
+    assert!(storage.get(&other).await.is_ok());
+
+    delete_keys(storage, vec![other.clone(), other.clone()]).await;
+    assert!(storage.get(&other).await.is_err());
+}
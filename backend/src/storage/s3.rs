@@ -0,0 +1,104 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use object_store::{
+    Attribute, Attributes, ObjectStore, PutOptions,
+    aws::{AmazonS3, AmazonS3Builder},
+    path::Path,
+};
+
+use super::Storage;
+
+/// S3-compatible storage. Setting an `endpoint` points it at MinIO or any other
+/// self-hosted S3 API; plain `http://` endpoints are allowed for local development.
+pub struct S3Storage {
+    store: AmazonS3,
+}
+
+impl S3Storage {
+    pub fn new(
+        bucket: &str,
+        region: &str,
+        endpoint: Option<String>,
+        access_key_id: Option<String>,
+        secret_access_key: Option<String>,
+    ) -> Result<Self, String> {
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(bucket)
+            .with_region(region);
+
+        if let Some(endpoint) = endpoint {
+            builder = builder
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_endpoint(endpoint);
+        }
+        if let Some(access_key_id) = access_key_id {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Some(secret_access_key) = secret_access_key {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+
+        let store = builder.build().map_err(|e| e.to_string())?;
+        Ok(Self { store })
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, data: Bytes, content_type: &str) -> Result<(), String> {
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, content_type.to_string().into());
+
+        let opts = PutOptions {
+            attributes,
+            ..Default::default()
+        };
+
+        self.store
+            .put_opts(&Path::from(key), data.into(), opts)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, String> {
+        let result = self
+            .store
+            .get(&Path::from(key))
+            .await
+            .map_err(|e| e.to_string())?;
+        result.bytes().await.map_err(|e| e.to_string())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        match self.store.delete(&Path::from(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs against the MinIO of `docker-compose.yml` (or any S3 API named by
+    /// `S3_ENDPOINT`) with a bucket named by `S3_BUCKET`, `attachments` by
+    /// default, that must already exist:
+    /// `cargo test s3 -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a running MinIO"]
+    async fn stores_objects_in_minio() {
+        let var = |name: &str, default: &str| std::env::var(name).unwrap_or(default.to_string());
+        let storage = S3Storage::new(
+            &var("S3_BUCKET", "attachments"),
+            &var("S3_REGION", "us-east-1"),
+            Some(var("S3_ENDPOINT", "http://localhost:9000")),
+            Some(var("S3_ACCESS_KEY_ID", "minioadmin")),
+            Some(var("S3_SECRET_ACCESS_KEY", "minioadmin")),
+        )
+        .unwrap();
+
+        super::super::check_storage(&storage).await;
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{models::AccountModel, schema::ApiResponse};
+use axum::http::StatusCode;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+pub const ACCOUNT_TYPES: [&str; 5] = ["cash", "bank", "debit_card", "credit_card", "wallet"];
+
+pub fn validate_account_type(account_type: &str) -> Result<String, ApiResponse<serde_json::Value>> {
+    let account_type = account_type.trim().to_lowercase();
+    if !ACCOUNT_TYPES.contains(&account_type.as_str()) {
+        return Err(ApiResponse::error(
+            &format!("accountType must be one of {}", ACCOUNT_TYPES.join(", ")),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(account_type)
+}
+
+/// Fetches one of the user's accounts with its current balance.
+pub async fn get_account<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    account_id: Uuid,
+) -> Result<AccountModel, ApiResponse<serde_json::Value>> {
+    sqlx::query_as::<_, AccountModel>(
+        "SELECT a.*, account_balance(a.account_id, NULL) AS balance
+         FROM accounts a WHERE a.account_id = $1 AND a.user_id = $2",
+    )
+    .bind(account_id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Account not found", StatusCode::NOT_FOUND))
+}
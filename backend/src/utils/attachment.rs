@@ -0,0 +1,140 @@
+use crate::{models::AttachmentModel, schema::ApiResponse};
+use axum::http::StatusCode;
+use image::{ImageFormat, imageops::FilterType};
+use sqlx::PgExecutor;
+use std::io::Cursor;
+use uuid::Uuid;
+
+/// Largest single file accepted for an expense attachment (10 MiB).
+pub const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Total bytes of attachments a single user may keep stored (100 MiB).
+pub const USER_ATTACHMENT_QUOTA: i64 = 100 * 1024 * 1024;
+
+/// Longest edge, in pixels, of generated image thumbnails.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+pub const ALLOWED_CONTENT_TYPES: [&str; 4] =
+    ["image/jpeg", "image/png", "image/webp", "application/pdf"];
+
+/// Storage keys (file and thumbnail) of the given attachments.
+pub fn attachment_keys(attachments: Vec<AttachmentModel>) -> Vec<String> {
+    attachments
+        .into_iter()
+        .flat_map(|a| std::iter::once(a.storage_key).chain(a.thumbnail_key))
+        .collect()
+}
+
+/// Fails with 413 when `size_bytes` more would take the user over
+/// [`USER_ATTACHMENT_QUOTA`].
+pub async fn check_attachment_quota<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    size_bytes: i64,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    let used_bytes: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(size_bytes), 0)::BIGINT FROM attachments WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_one(conn)
+    .await?;
+
+    if used_bytes + size_bytes > USER_ATTACHMENT_QUOTA {
+        return Err(ApiResponse::error(
+            &format!(
+                "Attachment quota exceeded: {} of {} bytes used",
+                used_bytes, USER_ATTACHMENT_QUOTA
+            ),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        ));
+    }
+    Ok(())
+}
+
+/// Detects the content type from the file's magic bytes, so a client can't
+/// upload arbitrary data just by lying about the `Content-Type`.
+pub fn sniff_content_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if data.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else {
+        None
+    }
+}
+
+/// Validates an uploaded file and returns its real content type.
+pub fn validate_attachment(
+    declared_type: Option<&str>,
+    data: &[u8],
+) -> Result<&'static str, ApiResponse<serde_json::Value>> {
+    if data.is_empty() {
+        return Err(ApiResponse::error(
+            "Attachment file is empty",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if data.len() > MAX_ATTACHMENT_SIZE {
+        return Err(ApiResponse::error(
+            &format!(
+                "Attachment exceeds the maximum size of {} bytes",
+                MAX_ATTACHMENT_SIZE
+            ),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        ));
+    }
+
+    let content_type = sniff_content_type(data).ok_or_else(|| {
+        ApiResponse::error(
+            &format!(
+                "Unsupported file type, allowed types are: {}",
+                ALLOWED_CONTENT_TYPES.join(", ")
+            ),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        )
+    })?;
+
+    // Generic uploads (`application/octet-stream`) are accepted based on the sniffed type.
+    if let Some(declared) = declared_type
+        && declared != "application/octet-stream"
+        && declared != content_type
+    {
+        return Err(ApiResponse::error(
+            &format!(
+                "Declared content type '{}' doesn't match file contents ({})",
+                declared, content_type
+            ),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ));
+    }
+
+    Ok(content_type)
+}
+
+/// Renders a JPEG thumbnail for image attachments. Returns `Ok(None)` for
+/// content types that don't get a thumbnail (PDFs).
+pub fn generate_thumbnail(content_type: &str, data: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let format = match content_type {
+        "image/jpeg" => ImageFormat::Jpeg,
+        "image/png" => ImageFormat::Png,
+        "image/webp" => ImageFormat::WebP,
+        _ => return Ok(None),
+    };
+
+    let image = image::load_from_memory_with_format(data, format).map_err(|e| e.to_string())?;
+    let thumbnail = image
+        .resize(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Triangle)
+        .to_rgb8();
+
+    let mut buffer = Cursor::new(Vec::new());
+    thumbnail
+        .write_to(&mut buffer, ImageFormat::Jpeg)
+        .map_err(|e| e.to_string())?;
+
+    Ok(Some(buffer.into_inner()))
+}
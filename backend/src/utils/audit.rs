@@ -0,0 +1,43 @@
+use axum::http::StatusCode;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::schema::ApiResponse;
+
+/// Entities whose changes are recorded in the audit log.
+pub const AUDIT_ENTITY_TYPES: [&str; 3] = ["budget", "category", "expense"];
+
+/// Select-list for queries over `audit_log l` that fills `AuditLogModel`,
+/// including the name of the user who made the change.
+pub const AUDIT_COLUMNS: &str =
+    "l.*, (SELECT u.name FROM users u WHERE u.user_id = l.user_id) AS user_name";
+
+/// Starts a transaction whose changes to expenses, budgets and categories are
+/// recorded in the audit log as made by `user_id`. The log itself is written by
+/// triggers, so every write to those tables should go through one of these.
+pub async fn begin_audited(
+    db: &PgPool,
+    user_id: Uuid,
+) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("SELECT set_config('app.user_id', $1, TRUE)")
+        .bind(user_id.to_string())
+        .execute(&mut *tx)
+        .await?;
+
+    Ok(tx)
+}
+
+pub fn validate_audit_entity_type(
+    entity_type: &str,
+) -> Result<String, ApiResponse<serde_json::Value>> {
+    let entity_type = entity_type.trim().to_lowercase();
+    if !AUDIT_ENTITY_TYPES.contains(&entity_type.as_str()) {
+        return Err(ApiResponse::error(
+            "entityType must be one of 'expense', 'budget' or 'category'",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(entity_type)
+}
@@ -0,0 +1,144 @@
+use crate::{
+    models::{BillModel, BillPaymentModel, Money},
+    schema::ApiResponse,
+};
+use axum::http::StatusCode;
+use chrono::{Days, Months, NaiveDate};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+pub const BILL_RECURRENCES: [&str; 5] = ["none", "weekly", "monthly", "quarterly", "yearly"];
+
+/// Select-list for queries over `bills b` that fills `BillModel::is_overdue`.
+pub const BILL_COLUMNS: &str = "b.*, (b.is_active AND b.due_date < CURRENT_DATE) AS is_overdue";
+
+pub fn validate_recurrence(recurrence: &str) -> Result<String, ApiResponse<serde_json::Value>> {
+    let recurrence = recurrence.trim().to_lowercase();
+    if !BILL_RECURRENCES.contains(&recurrence.as_str()) {
+        return Err(ApiResponse::error(
+            &format!("recurrence must be one of {}", BILL_RECURRENCES.join(", ")),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(recurrence)
+}
+
+pub fn validate_remind_days(days: i32) -> Result<(), ApiResponse<serde_json::Value>> {
+    if !(0..=60).contains(&days) {
+        return Err(ApiResponse::error(
+            "remindDaysBefore must be between 0 and 60",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(())
+}
+
+/// The `n`th occurrence of a recurring bill (the 0th is `start`). Months are
+/// always added to `start`, so a bill on the 31st falls on the last day of
+/// shorter months and returns to the 31st afterwards.
+pub fn bill_occurrence(start: NaiveDate, recurrence: &str, n: u32) -> Option<NaiveDate> {
+    match recurrence {
+        "weekly" => start.checked_add_days(Days::new(7 * n as u64)),
+        "monthly" => start.checked_add_months(Months::new(n)),
+        "quarterly" => start.checked_add_months(Months::new(3 * n)),
+        "yearly" => start.checked_add_months(Months::new(12 * n)),
+        _ if n == 0 => Some(start),
+        _ => None,
+    }
+}
+
+/// First occurrence after `after`, or `None` for a one-off bill.
+pub fn next_bill_occurrence(
+    start: NaiveDate,
+    recurrence: &str,
+    after: NaiveDate,
+) -> Option<NaiveDate> {
+    (1..)
+        .map_while(|n| bill_occurrence(start, recurrence, n))
+        .find(|date| *date > after)
+}
+
+/// Unpaid occurrences of a bill from its due date up to and including `until`.
+pub fn bill_occurrences_until(bill: &BillModel, until: NaiveDate) -> Vec<NaiveDate> {
+    if !bill.is_active {
+        return Vec::new();
+    }
+
+    let mut dates = Vec::new();
+    let mut next = Some(bill.due_date);
+    while let Some(date) = next.filter(|date| *date <= until) {
+        dates.push(date);
+        next = next_bill_occurrence(bill.start_date, &bill.recurrence, date);
+    }
+    dates
+}
+
+pub async fn get_bill(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    bill_id: Uuid,
+) -> Result<BillModel, ApiResponse<serde_json::Value>> {
+    sqlx::query_as::<_, BillModel>(&format!(
+        "SELECT {} FROM bills b WHERE b.bill_id = $1 AND b.user_id = $2",
+        BILL_COLUMNS
+    ))
+    .bind(bill_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Bill not found", StatusCode::NOT_FOUND))
+}
+
+/// Pays the bill's current occurrence: records an expense for it, remembers
+/// the payment and moves the bill to its next occurrence (or deactivates a
+/// one-off bill).
+pub async fn record_bill_payment(
+    conn: &mut PgConnection,
+    bill: &BillModel,
+    amount: Money,
+    paid_on: NaiveDate,
+    account_id: Option<Uuid>,
+    autopaid: bool,
+) -> Result<BillPaymentModel, sqlx::Error> {
+    let expense_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO expenses (name, amount, currency, date, description, category_id, user_id, account_id, paid_by)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $7)
+         RETURNING expense_id",
+    )
+    .bind(&bill.name)
+    .bind(amount)
+    .bind(&bill.currency)
+    .bind(paid_on)
+    .bind(format!("Bill due {}", bill.due_date))
+    .bind(bill.category_id)
+    .bind(bill.user_id)
+    .bind(account_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let payment = sqlx::query_as::<_, BillPaymentModel>(
+        "INSERT INTO bill_payments (bill_id, expense_id, due_date, paid_on, amount, autopaid)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING *",
+    )
+    .bind(bill.bill_id)
+    .bind(expense_id)
+    .bind(bill.due_date)
+    .bind(paid_on)
+    .bind(amount)
+    .bind(autopaid)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let next_due_date = next_bill_occurrence(bill.start_date, &bill.recurrence, bill.due_date);
+    sqlx::query(
+        "UPDATE bills SET due_date = COALESCE($1, due_date), is_active = $1 IS NOT NULL
+         WHERE bill_id = $2",
+    )
+    .bind(next_due_date)
+    .bind(bill.bill_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(payment)
+}
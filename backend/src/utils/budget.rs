@@ -0,0 +1,211 @@
+use crate::{
+    jobs::{Job, enqueue},
+    metrics::record_notifications,
+    models::{BudgetModel, BudgetWithSpentModel, ExpenseModel},
+    schema::ApiResponse,
+    utils::{currency::format_amount, household::ensure_same_workspace},
+};
+use axum::http::StatusCode;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+
+/// `FROM`/`WHERE` over `expenses e` selecting the expenses that count against
+/// budget `b`.
+///
+/// Expenses linked to the budget always count. A budget with a `category_id` also
+/// covers the expenses of its workspace (the owner's personal expenses, or its
+/// household's) in that category and all of its subcategories within the budget
+/// period. Trashed expenses and subcategories are left out.
+macro_rules! budget_expenses {
+    () => {
+        "FROM expenses e
+    WHERE e.deleted_at IS NULL AND (
+        e.budget_id = b.budget_id
+        OR (
+            b.category_id IS NOT NULL
+            AND in_workspace(e.user_id, e.household_id, b.user_id, b.household_id)
+            AND e.date BETWEEN b.start_date AND b.end_date
+            AND e.category_id IN (
+                WITH RECURSIVE subtree AS (
+                    SELECT b.category_id AS category_id
+                    UNION
+                    SELECT c.category_id FROM categories c
+                    JOIN subtree s ON c.parent_id = s.category_id
+                    WHERE c.deleted_at IS NULL
+                )
+                SELECT category_id FROM subtree
+            )
+        )
+    )"
+    };
+}
+
+/// Amount spent against budget `b`, for queries over `budgets b`.
+///
+/// Refunds reduce the amount spent and income is ignored. Amounts are converted
+/// to the budget's currency at each expense date; expenses in a currency without
+/// a known exchange rate can't be and are left out, see
+/// [`BUDGET_UNCONVERTED_EXPR`].
+pub const BUDGET_SPENT_EXPR: &str = concat!(
+    "(SELECT COALESCE(SUM(convert_amount(spent_amount(e.transaction_type, e.amount), e.currency, b.currency, e.date, b.user_id)), 0)::BIGINT ",
+    budget_expenses!(),
+    ")"
+);
+
+/// Number of expenses left out of [`BUDGET_SPENT_EXPR`] because no exchange
+/// rate to the budget's currency is known for them.
+pub const BUDGET_UNCONVERTED_EXPR: &str = concat!(
+    "(SELECT COUNT(*) ",
+    budget_expenses!(),
+    " AND e.transaction_type <> 'income'",
+    " AND convert_amount(e.amount, e.currency, b.currency, e.date, b.user_id) IS NULL)"
+);
+
+/// Fetches a budget the user can see that belongs to the given workspace
+/// (`household_id`, or `None` for personal budgets).
+pub async fn get_workspace_budget<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+    budget_id: Uuid,
+) -> Result<BudgetModel, ApiResponse<serde_json::Value>> {
+    let budget = sqlx::query_as::<_, BudgetModel>(
+        "SELECT * FROM budgets
+         WHERE budget_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)",
+    )
+    .bind(budget_id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Budget not found", StatusCode::NOT_FOUND))?;
+
+    ensure_same_workspace("Budget", budget.household_id, household_id)?;
+    Ok(budget)
+}
+
+/// The budgets a new expense may have taken to 80% or more: the budget the
+/// expense is linked to, plus category budgets covering its category (or a
+/// parent of it) on the expense date.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BudgetAlert {
+    pub user_id: Uuid,
+    pub household_id: Option<Uuid>,
+    pub budget_id: Option<Uuid>,
+    pub category_id: Option<i32>,
+    pub date: NaiveDate,
+}
+
+/// Queues a [`BudgetAlert`] job for a new expense in `conn`, the transaction
+/// inserting it. Nothing is queued for expenses no budget can cover.
+pub async fn enqueue_budget_alert(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    expense: &ExpenseModel,
+) -> Result<(), sqlx::Error> {
+    if expense.transaction_type != "expense"
+        || (expense.budget_id.is_none() && expense.category_id.is_none())
+    {
+        return Ok(());
+    }
+
+    let alert = BudgetAlert {
+        user_id,
+        household_id: expense.household_id,
+        budget_id: expense.budget_id,
+        category_id: expense.category_id,
+        date: expense.date,
+    };
+    enqueue(conn, &Job::BudgetAlert(alert)).await?;
+    Ok(())
+}
+
+/// Warns the user (every member, for a shared budget) about the budgets of
+/// `alert` that are at 80% or more. All notifications are sent together or
+/// not at all, so a retried job doesn't repeat them.
+pub async fn send_budget_alerts(db: &PgPool, alert: &BudgetAlert) -> Result<(), sqlx::Error> {
+    let BudgetAlert {
+        user_id,
+        household_id,
+        budget_id,
+        category_id,
+        date: expense_date,
+    } = *alert;
+
+    let mut tx = db.begin().await?;
+    let mut notified = 0;
+    let budgets = sqlx::query_as::<_, BudgetWithSpentModel>(&format!(
+        "SELECT b.*, {} AS total_spent
+        FROM budgets b
+        WHERE in_workspace(b.user_id, b.household_id, $1, $5) AND b.deleted_at IS NULL
+            AND (
+                b.budget_id = $2
+                OR (
+                    $3 BETWEEN b.start_date AND b.end_date
+                    AND b.category_id IN (
+                        WITH RECURSIVE ancestors AS (
+                            SELECT category_id, parent_id FROM categories WHERE category_id = $4
+                            UNION
+                            SELECT c.category_id, c.parent_id FROM categories c
+                            JOIN ancestors a ON c.category_id = a.parent_id
+                        )
+                        SELECT category_id FROM ancestors
+                    )
+                )
+            )",
+        BUDGET_SPENT_EXPR
+    ))
+    .bind(user_id)
+    .bind(budget_id)
+    .bind(expense_date)
+    .bind(category_id)
+    .bind(household_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for budget in budgets {
+        let budget_id = budget.budget_id;
+        let usage_percentage =
+            (budget.total_spent.minor_units() as f64 / budget.amount.minor_units() as f64) * 100.0;
+        let total_spent = format_amount(budget.total_spent, &budget.currency);
+        let budget_amount = format_amount(budget.amount, &budget.currency);
+
+        let msg = if usage_percentage >= 100.0 {
+            Some(format!(
+                "🚨 BUDGET ALERT: Budget '{}' (ID: {}) has been EXCEEDED! \
+                Total spent: {} / Budget: {} ({:.1}%)",
+                budget.name, budget_id, total_spent, budget_amount, usage_percentage
+            ))
+        } else if usage_percentage >= 80.0 {
+            Some(format!(
+                "⚠️  BUDGET WARNING: Budget '{}' (ID: {}) is at {:.1}% usage. \
+                Total spent: {} / Budget: {}",
+                budget.name, budget_id, usage_percentage, total_spent, budget_amount
+            ))
+        } else {
+            None
+        };
+
+        // Every member hears about a shared budget
+        if msg.is_some() {
+            notified += sqlx::query(
+                "INSERT INTO notifications (user_id, category, message)
+                SELECT user_id, $2, $3 FROM household_members WHERE household_id = $4
+                UNION ALL
+                SELECT $1, $2, $3 WHERE $4::UUID IS NULL",
+            )
+            .bind(user_id)
+            .bind("BUDGET_ALERT")
+            .bind(msg)
+            .bind(budget.household_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+    }
+
+    tx.commit().await?;
+    record_notifications("BUDGET_ALERT", notified);
+    Ok(())
+}
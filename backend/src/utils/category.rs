@@ -0,0 +1,273 @@
+use crate::{
+    models::CategoryModel,
+    schema::ApiResponse,
+    utils::household::{ensure_same_workspace, require_edit_access},
+};
+use axum::http::StatusCode;
+use regex::Regex;
+use sqlx::{PgConnection, PgExecutor};
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+static COLOR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^#[0-9A-Fa-f]{6}$").expect("Failed to initalize the color regex")
+});
+
+pub fn validate_category_name(name: &str) -> Result<(), ApiResponse<serde_json::Value>> {
+    if name.trim().is_empty() {
+        return Err(ApiResponse::error(
+            "Category name cannot be empty",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(())
+}
+
+/// Validates the optional icon name and `#RRGGBB` color used by the mobile UI.
+pub fn validate_category_style(
+    icon: Option<&str>,
+    color: Option<&str>,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    if let Some(icon) = icon
+        && (icon.trim().is_empty() || icon.len() > 50)
+    {
+        return Err(ApiResponse::error(
+            "Icon must be between 1 and 50 characters",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if let Some(color) = color
+        && !COLOR_REGEX.is_match(color)
+    {
+        return Err(ApiResponse::error(
+            "Color must be a hex value like #1E88E5",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fetches a category the user can use: a global one, one of their own or one
+/// of a household they are a member of.
+pub async fn get_visible_category<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    category_id: i32,
+) -> Result<CategoryModel, ApiResponse<serde_json::Value>> {
+    sqlx::query_as::<_, CategoryModel>(
+        "SELECT * FROM categories
+         WHERE category_id = $1 AND deleted_at IS NULL
+            AND (user_id IS NULL OR can_view_resource(user_id, household_id, $2))",
+    )
+    .bind(category_id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Category not found", StatusCode::NOT_FOUND))
+}
+
+/// Fetches a personal or household category the user may change. Global
+/// categories can only be hidden, never changed.
+pub async fn get_editable_category(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    category_id: i32,
+) -> Result<CategoryModel, ApiResponse<serde_json::Value>> {
+    let category = sqlx::query_as::<_, CategoryModel>(
+        "SELECT * FROM categories
+         WHERE category_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)",
+    )
+    .bind(category_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| {
+        ApiResponse::error(
+            "Category not found or you don't have permission to update it",
+            StatusCode::NOT_FOUND,
+        )
+    })?;
+
+    require_edit_access(db, user_id, category.household_id).await?;
+    Ok(category)
+}
+
+/// Fetches a category that can be used in a workspace (`household_id`, or
+/// `None` for personal data): a global category or one of that workspace.
+pub async fn get_workspace_category<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+    category_id: i32,
+) -> Result<CategoryModel, ApiResponse<serde_json::Value>> {
+    let category = get_visible_category(conn, user_id, category_id).await?;
+    if category.user_id.is_some() {
+        ensure_same_workspace("Category", category.household_id, household_id)?;
+    }
+    Ok(category)
+}
+
+/// Checks that `parent_id` can become the parent of `category_id` (`None` for a
+/// category that doesn't exist yet) in the given workspace without creating a
+/// cycle.
+pub async fn validate_category_parent(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+    category_id: Option<i32>,
+    parent_id: i32,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    get_visible_category(db, user_id, parent_id)
+        .await
+        .map_err(|_| ApiResponse::error("Parent category not found", StatusCode::BAD_REQUEST))
+        .and_then(|parent| match parent.user_id {
+            Some(_) => ensure_same_workspace("Parent category", parent.household_id, household_id),
+            None => Ok(()),
+        })?;
+
+    let Some(category_id) = category_id else {
+        return Ok(());
+    };
+
+    if is_in_subtree(db, category_id, parent_id).await? {
+        return Err(ApiResponse::error(
+            "A category can't be moved under itself or one of its subcategories",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Whether `candidate_id` is `root_id` itself or one of its (nested) subcategories.
+pub async fn is_in_subtree(
+    db: &sqlx::PgPool,
+    root_id: i32,
+    candidate_id: i32,
+) -> Result<bool, sqlx::Error> {
+    // Walk up from the candidate; meeting the root means it lives in the subtree.
+    sqlx::query_scalar(
+        "WITH RECURSIVE ancestors AS (
+            SELECT category_id, parent_id FROM categories WHERE category_id = $1
+            UNION
+            SELECT c.category_id, c.parent_id FROM categories c
+            JOIN ancestors a ON c.category_id = a.parent_id
+        )
+        SELECT EXISTS(SELECT 1 FROM ancestors WHERE category_id = $2)",
+    )
+    .bind(candidate_id)
+    .bind(root_id)
+    .fetch_one(db)
+    .await
+}
+
+/// Points everything that refers to category `source_id` at `target_id`
+/// instead, then deletes the source. Returns how many expenses and budgets
+/// moved.
+pub async fn merge_category_into(
+    conn: &mut PgConnection,
+    source_id: i32,
+    target_id: i32,
+) -> Result<(u64, u64), sqlx::Error> {
+    // Only rows of the source's own workspace can refer to it, so everything
+    // pointing at it moves. Whatever is left over would lose its category when
+    // the source is deleted, through the foreign keys' ON DELETE SET NULL.
+    let moved_expenses = sqlx::query("UPDATE expenses SET category_id = $1 WHERE category_id = $2")
+        .bind(target_id)
+        .bind(source_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    let moved_budgets = sqlx::query("UPDATE budgets SET category_id = $1 WHERE category_id = $2")
+        .bind(target_id)
+        .bind(source_id)
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+    for query in [
+        "UPDATE expense_rules SET category_id = $1 WHERE category_id = $2",
+        "UPDATE bills SET category_id = $1 WHERE category_id = $2",
+        "UPDATE categories SET parent_id = $1 WHERE parent_id = $2",
+    ] {
+        sqlx::query(query)
+            .bind(target_id)
+            .bind(source_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    sqlx::query("DELETE FROM categories WHERE category_id = $1")
+        .bind(source_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok((moved_expenses, moved_budgets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::rule::find_matching_rule;
+    use sqlx::{Connection, Row};
+
+    /// Runs against the database of `DATABASE_URL` (read from `.env` too),
+    /// with the migrations applied, in a transaction that is rolled back:
+    /// `cargo test category -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a migrated Postgres"]
+    async fn rules_follow_a_merged_category() {
+        dotenv::dotenv().ok();
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        let mut conn = PgConnection::connect(&url).await.unwrap();
+        let mut tx = conn.begin().await.unwrap();
+
+        let user_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (name, email, password_hash)
+            VALUES ('Merge', 'merge-' || gen_random_uuid() || '@example.com', '-')
+            RETURNING user_id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+
+        let mut categories = Vec::new();
+        for name in ["Coffee", "Food"] {
+            let row = sqlx::query(
+                "INSERT INTO categories (user_id, category_name) VALUES ($1, $2) RETURNING category_id",
+            )
+            .bind(user_id)
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .unwrap();
+            categories.push(row.get::<i32, _>("category_id"));
+        }
+        let (source_id, target_id) = (categories[0], categories[1]);
+
+        sqlx::query(
+            "INSERT INTO expense_rules (user_id, name, name_contains, category_id)
+            VALUES ($1, 'Coffee shops', 'coffee', $2)",
+        )
+        .bind(user_id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+
+        merge_category_into(&mut tx, source_id, target_id)
+            .await
+            .unwrap();
+
+        let rule = find_matching_rule(&mut *tx, user_id, "Morning coffee", None)
+            .await
+            .unwrap()
+            .expect("the rule still matches");
+        assert_eq!(rule.category_id, Some(target_id));
+
+        tx.rollback().await.unwrap();
+    }
+}
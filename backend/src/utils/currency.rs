@@ -0,0 +1,429 @@
+use crate::{
+    models::{Money, MoneyError, MoneyInput},
+    schema::ApiResponse,
+};
+use axum::http::StatusCode;
+use regex::Regex;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+/// ISO 4217 codes accepted by the API with their minor unit exponent
+/// (number of decimal places). Covers every ECB reference currency.
+pub const CURRENCIES: [(&str, u32); 47] = [
+    ("AED", 2),
+    ("AUD", 2),
+    ("BDT", 2),
+    ("BGN", 2),
+    ("BHD", 3),
+    ("BRL", 2),
+    ("CAD", 2),
+    ("CHF", 2),
+    ("CLP", 0),
+    ("CNY", 2),
+    ("CZK", 2),
+    ("DKK", 2),
+    ("EGP", 2),
+    ("EUR", 2),
+    ("GBP", 2),
+    ("HKD", 2),
+    ("HUF", 2),
+    ("IDR", 2),
+    ("ILS", 2),
+    ("INR", 2),
+    ("ISK", 0),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KES", 2),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("LKR", 2),
+    ("MXN", 2),
+    ("MYR", 2),
+    ("NGN", 2),
+    ("NOK", 2),
+    ("NPR", 2),
+    ("NZD", 2),
+    ("OMR", 3),
+    ("PHP", 2),
+    ("PKR", 2),
+    ("PLN", 2),
+    ("RON", 2),
+    ("SAR", 2),
+    ("SEK", 2),
+    ("SGD", 2),
+    ("THB", 2),
+    ("TRY", 2),
+    ("TWD", 2),
+    ("USD", 2),
+    ("VND", 0),
+    ("ZAR", 2),
+];
+
+pub const DEFAULT_CURRENCY: &str = "INR";
+
+/// Minor unit exponent of a supported currency, e.g. 2 for INR, 0 for JPY.
+pub fn currency_exponent(code: &str) -> Option<u32> {
+    CURRENCIES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, exponent)| *exponent)
+}
+
+/// Upper-cases and checks a currency code against the supported list.
+pub fn validate_currency(code: &str) -> Result<String, ApiResponse<serde_json::Value>> {
+    let code = code.trim().to_uppercase();
+    match currency_exponent(&code) {
+        Some(_) => Ok(code),
+        None => Err(ApiResponse::error(
+            &format!("Unsupported currency '{}'", code),
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+/// Resolves a client-sent amount in a supported currency to minor units.
+pub fn parse_amount(
+    amount: &MoneyInput,
+    currency: &str,
+) -> Result<Money, ApiResponse<serde_json::Value>> {
+    let exponent = currency_exponent(currency).unwrap_or(2);
+    amount
+        .to_money(exponent)
+        .map_err(|e| ApiResponse::error(&e.to_string(), StatusCode::BAD_REQUEST))
+}
+
+/// Keeps the same decimal value when an amount moves to a currency with a
+/// different exponent, e.g. 12.50 INR (1250) as 12.500 KWD (12500). An
+/// amount the new currency can't hold, like 12.34 INR in JPY, isn't rounded:
+/// the client has to send the amount along with the currency.
+pub fn rescale_amount(
+    amount: Money,
+    from_currency: &str,
+    to_currency: &str,
+) -> Result<Money, ApiResponse<serde_json::Value>> {
+    let decimal = amount.to_decimal(currency_exponent(from_currency).unwrap_or(2));
+    match MoneyInput::Decimal(decimal.clone()).to_money(currency_exponent(to_currency).unwrap_or(2))
+    {
+        Ok(amount) => Ok(amount),
+        Err(MoneyError::TooManyDecimals { .. }) => Err(ApiResponse::error(
+            &format!(
+                "{} {} has more decimal places than {} allows; send the amount in {} along with the currency",
+                decimal, from_currency, to_currency, to_currency
+            ),
+            StatusCode::BAD_REQUEST,
+        )),
+        Err(e) => Err(ApiResponse::error(&e.to_string(), StatusCode::BAD_REQUEST)),
+    }
+}
+
+/// Human-readable amount for messages, e.g. `12.50 INR`.
+pub fn format_amount(amount: Money, currency: &str) -> String {
+    let exponent = currency_exponent(currency).unwrap_or(2);
+    format!("{} {}", amount.to_decimal(exponent), currency)
+}
+
+/// Column list for reading `ExchangeRateModel`, with the rate as a decimal string.
+pub const EXCHANGE_RATE_COLUMNS: &str = "rate_id, user_id, base_currency, quote_currency,
+    trim_scale(rate)::TEXT AS rate, rate_date, source, created_at, updated_at";
+
+/// Checks that a rate is a positive decimal string like `83.1245`.
+pub fn validate_exchange_rate(rate: &str) -> Result<(), ApiResponse<serde_json::Value>> {
+    let rate_regex = Regex::new(r"^[0-9]{1,14}(\.[0-9]{1,10})?$")
+        .expect("Failed to initalize the exchange rate regex");
+    let rate = rate.trim();
+
+    if !rate_regex.is_match(rate) || rate.chars().all(|c| c == '0' || c == '.') {
+        return Err(ApiResponse::error(
+            "Rate must be a positive decimal number with at most 10 decimal places",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn get_user_base_currency<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+) -> Result<String, sqlx::Error> {
+    sqlx::query_scalar("SELECT base_currency FROM users WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(conn)
+        .await
+}
+
+/// Converts an amount to the user's base currency at the rate of `date`, the
+/// same way reports and budgets do. `None` when no exchange rate is known.
+pub async fn convert_to_base_currency<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    amount: Money,
+    currency: &str,
+    date: chrono::NaiveDate,
+) -> Result<Option<Money>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT convert_amount($1, $2, base_currency, $3, user_id) FROM users WHERE user_id = $4",
+    )
+    .bind(amount)
+    .bind(currency)
+    .bind(date)
+    .bind(user_id)
+    .fetch_one(conn)
+    .await
+}
+
+/// One EUR-based reference rate: 1 EUR = `rate` units of `currency` on `date`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceRate {
+    pub date: chrono::NaiveDate,
+    pub currency: String,
+    pub rate: String,
+}
+
+/// Parses ECB euro foreign exchange reference rates, either the XML feed
+/// (`eurofxref-daily.xml`, `eurofxref-hist.xml`) or the CSV download
+/// (`eurofxref.csv`, `eurofxref-hist.csv`). Unknown currencies and `N/A`
+/// cells are skipped.
+pub fn parse_ecb_rates(content: &str) -> Result<Vec<ReferenceRate>, String> {
+    if content.trim_start().starts_with('<') {
+        parse_ecb_xml(content)
+    } else {
+        parse_ecb_csv(content)
+    }
+}
+
+fn parse_ecb_xml(content: &str) -> Result<Vec<ReferenceRate>, String> {
+    let cube_regex = Regex::new(
+        r#"<Cube\s+(?:time=['"](?P<time>[0-9-]+)['"]|currency=['"](?P<currency>[A-Z]{3})['"]\s+rate=['"](?P<rate>[0-9.]+)['"])"#,
+    )
+    .expect("Failed to initalize the ECB cube regex");
+
+    let mut rates = Vec::new();
+    let mut date = None;
+
+    for cube in cube_regex.captures_iter(content) {
+        if let Some(time) = cube.name("time") {
+            date = Some(
+                chrono::NaiveDate::parse_from_str(time.as_str(), "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid date '{}': {}", time.as_str(), e))?,
+            );
+            continue;
+        }
+
+        let (Some(currency), Some(rate)) = (cube.name("currency"), cube.name("rate")) else {
+            continue;
+        };
+        let date = date.ok_or("Rate found before any <Cube time=...> element")?;
+
+        if currency_exponent(currency.as_str()).is_some() {
+            rates.push(ReferenceRate {
+                date,
+                currency: currency.as_str().to_string(),
+                rate: rate.as_str().to_string(),
+            });
+        }
+    }
+
+    if date.is_none() {
+        return Err("No <Cube time=...> element found, is this an ECB rates file?".to_string());
+    }
+
+    Ok(rates)
+}
+
+fn parse_ecb_csv(content: &str) -> Result<Vec<ReferenceRate>, String> {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or("Rates file is empty")?
+        .split(',')
+        .map(str::trim)
+        .collect();
+
+    if !header
+        .first()
+        .is_some_and(|h| h.eq_ignore_ascii_case("date"))
+    {
+        return Err("Expected a CSV header starting with 'Date'".to_string());
+    }
+
+    let mut rates = Vec::new();
+    for line in lines {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        let date = parse_ecb_date(cells[0])?;
+
+        for (currency, rate) in header.iter().zip(cells.iter()).skip(1) {
+            if currency_exponent(currency).is_none() || rate.parse::<f64>().is_err() {
+                continue;
+            }
+            rates.push(ReferenceRate {
+                date,
+                currency: currency.to_string(),
+                rate: rate.to_string(),
+            });
+        }
+    }
+
+    Ok(rates)
+}
+
+/// The daily CSV uses `05 January 2024`, the historical one `2024-01-05`.
+fn parse_ecb_date(value: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| chrono::NaiveDate::parse_from_str(value, "%d %B %Y"))
+        .map_err(|e| format!("Invalid date '{}': {}", value, e))
+}
+
+/// Upserts EUR-based reference rates, returning how many rows were written.
+pub async fn store_reference_rates(
+    db: &sqlx::PgPool,
+    rates: &[ReferenceRate],
+    source: &str,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let mut written = 0;
+
+    for chunk in rates.chunks(1000) {
+        let dates: Vec<chrono::NaiveDate> = chunk.iter().map(|r| r.date).collect();
+        let currencies: Vec<&str> = chunk.iter().map(|r| r.currency.as_str()).collect();
+        let values: Vec<&str> = chunk.iter().map(|r| r.rate.as_str()).collect();
+
+        written += sqlx::query(
+            "INSERT INTO exchange_rates (base_currency, quote_currency, rate, rate_date, source)
+             SELECT 'EUR', currency, rate::NUMERIC, rate_date, $4
+             FROM UNNEST($1::DATE[], $2::TEXT[], $3::TEXT[]) AS r(rate_date, currency, rate)
+             ON CONFLICT (base_currency, quote_currency, rate_date) WHERE user_id IS NULL
+             DO UPDATE SET rate = EXCLUDED.rate, source = EXCLUDED.source",
+        )
+        .bind(&dates)
+        .bind(&currencies)
+        .bind(&values)
+        .bind(source)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(date: &str, currency: &str, rate: &str) -> ReferenceRate {
+        ReferenceRate {
+            date: date.parse().unwrap(),
+            currency: currency.to_string(),
+            rate: rate.to_string(),
+        }
+    }
+
+    const DAILY_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+    <gesmes:subject>Reference rates</gesmes:subject>
+    <gesmes:Sender>
+        <gesmes:name>European Central Bank</gesmes:name>
+    </gesmes:Sender>
+    <Cube>
+        <Cube time='2024-01-05'>
+            <Cube currency='USD' rate='1.0921'/>
+            <Cube currency='JPY' rate='158.23'/>
+            <Cube currency='INR' rate='90.8010'/>
+        </Cube>
+    </Cube>
+</gesmes:Envelope>"#;
+
+    #[test]
+    fn parses_the_daily_xml_feed() {
+        assert_eq!(
+            parse_ecb_rates(DAILY_XML).unwrap(),
+            vec![
+                rate("2024-01-05", "USD", "1.0921"),
+                rate("2024-01-05", "JPY", "158.23"),
+                rate("2024-01-05", "INR", "90.8010"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_every_day_of_the_historical_xml_feed() {
+        let xml = r#"<gesmes:Envelope><Cube>
+            <Cube time="2024-01-05"><Cube currency="USD" rate="1.0921"/></Cube>
+            <Cube time="2024-01-04"><Cube currency="USD" rate="1.0953"/></Cube>
+        </Cube></gesmes:Envelope>"#;
+
+        assert_eq!(
+            parse_ecb_rates(xml).unwrap(),
+            vec![
+                rate("2024-01-05", "USD", "1.0921"),
+                rate("2024-01-04", "USD", "1.0953"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_unknown_currencies() {
+        let xml = r#"<Cube><Cube time='2024-01-05'>
+            <Cube currency='XXX' rate='1.5'/>
+            <Cube currency='USD' rate='1.0921'/>
+        </Cube></Cube>"#;
+
+        assert_eq!(
+            parse_ecb_rates(xml).unwrap(),
+            vec![rate("2024-01-05", "USD", "1.0921")]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_xml() {
+        let bad_date =
+            "<Cube><Cube time='2024-13-45'><Cube currency='USD' rate='1.09'/></Cube></Cube>";
+        assert!(
+            parse_ecb_rates(bad_date)
+                .unwrap_err()
+                .contains("2024-13-45")
+        );
+
+        let undated = "<Cube><Cube currency='USD' rate='1.09'/></Cube>";
+        assert!(parse_ecb_rates(undated).is_err());
+
+        let not_ecb = "<html><body>Service unavailable</body></html>";
+        assert!(parse_ecb_rates(not_ecb).is_err());
+    }
+
+    #[test]
+    fn parses_the_daily_csv() {
+        let csv = "Date, USD, JPY, XXX, \n05 January 2024, 1.0921, 158.23, 2.0, \n";
+
+        assert_eq!(
+            parse_ecb_rates(csv).unwrap(),
+            vec![
+                rate("2024-01-05", "USD", "1.0921"),
+                rate("2024-01-05", "JPY", "158.23"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_the_historical_csv_skipping_missing_rates() {
+        let csv = "Date,USD,ISK,\n2024-01-05,1.0921,N/A,\n2024-01-04,1.0953,150.10,\n";
+
+        assert_eq!(
+            parse_ecb_rates(csv).unwrap(),
+            vec![
+                rate("2024-01-05", "USD", "1.0921"),
+                rate("2024-01-04", "USD", "1.0953"),
+                rate("2024-01-04", "ISK", "150.10"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_csv() {
+        assert!(parse_ecb_rates("").is_err());
+        assert!(parse_ecb_rates("USD,JPY\n1.09,158.2\n").is_err());
+        assert!(parse_ecb_rates("Date,USD\nyesterday,1.09\n").is_err());
+    }
+}
@@ -0,0 +1,100 @@
+use axum::{
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::schema::ApiResponse;
+
+/// Entity tag of a row version, derived from its `updated_at` (microseconds,
+/// as precise as Postgres stores it).
+pub fn etag(updated_at: Option<DateTime<Utc>>) -> String {
+    format!(
+        "\"{}\"",
+        updated_at.map(|t| t.timestamp_micros()).unwrap_or_default()
+    )
+}
+
+/// Entity tags listed in a conditional header; `None` when it isn't sent.
+fn header_tags(headers: &HeaderMap, name: header::HeaderName) -> Option<Vec<String>> {
+    let value = headers.get(name)?.to_str().ok()?;
+    Some(value.split(',').map(|tag| tag.trim().to_string()).collect())
+}
+
+/// Fails with 412 when the request carries an `If-Match` header that doesn't
+/// name the current version (`*` matches any). Weak tags never match.
+pub fn check_if_match(
+    headers: &HeaderMap,
+    current: &str,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    let Some(tags) = header_tags(headers, header::IF_MATCH) else {
+        return Ok(());
+    };
+
+    if tags.iter().any(|tag| tag == "*" || tag == current) {
+        return Ok(());
+    }
+
+    Err(ApiResponse::error(
+        "The resource was changed since you last fetched it, reload it and try again",
+        StatusCode::PRECONDITION_FAILED,
+    ))
+}
+
+/// Whether the client's copy named in `If-None-Match` is still the current
+/// version, compared weakly.
+pub fn is_not_modified(headers: &HeaderMap, current: &str) -> bool {
+    header_tags(headers, header::IF_NONE_MATCH).is_some_and(|tags| {
+        tags.iter()
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == current)
+    })
+}
+
+/// The response with an `ETag` header for the version it carries.
+pub fn with_etag(response: ApiResponse<serde_json::Value>, etag: &str) -> Response {
+    ([(header::ETAG, etag.to_string())], response).into_response()
+}
+
+/// Response to a GET the client already has the current version of: 304 if
+/// `If-None-Match` names it, otherwise the full response with its `ETag`.
+pub fn conditional_get(
+    headers: &HeaderMap,
+    etag: &str,
+    response: impl FnOnce() -> ApiResponse<serde_json::Value>,
+) -> Response {
+    if is_not_modified(headers, etag) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag.to_string())]).into_response();
+    }
+    with_etag(response(), etag)
+}
+
+/// Locks a row for a conditional write and checks `If-Match` against its
+/// current version. Only rows passing `access_filter` (SQL with the user as
+/// `$2`) are locked or compared, so the version of someone else's row is
+/// never revealed; a missing or inaccessible row is left for the caller to
+/// report as not found.
+pub async fn lock_if_match(
+    conn: &mut PgConnection,
+    table_name: &str,
+    id_column: &str,
+    id: Uuid,
+    access_filter: &str,
+    user_id: Uuid,
+    headers: &HeaderMap,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    let updated_at: Option<Option<DateTime<Utc>>> = sqlx::query_scalar(&format!(
+        "SELECT updated_at FROM {} WHERE {} = $1 AND {} FOR UPDATE",
+        table_name, id_column, access_filter
+    ))
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?;
+
+    match updated_at {
+        Some(updated_at) => check_if_match(headers, &etag(updated_at)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,267 @@
+use axum::http::StatusCode;
+use sqlx::{PgConnection, PgExecutor, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::{
+    models::{ExpenseModel, Money},
+    schema::{ApiResponse, ExpenseFilterSchema},
+    utils::{household::resolve_workspace, tag::normalize_tags},
+};
+
+pub const TRANSACTION_TYPES: [&str; 3] = ["expense", "income", "refund"];
+
+/// Select-list for queries over `expenses e` that fills the computed fields of
+/// `ExpenseModel`: tag names and the amount converted to the user's base currency
+/// at the expense date (NULL when no exchange rate is known).
+pub const EXPENSE_COLUMNS: &str = "e.*,
+    ARRAY(
+        SELECT t.name FROM expense_tags et
+        JOIN tags t ON t.tag_id = et.tag_id
+        WHERE et.expense_id = e.expense_id
+        ORDER BY t.name
+    ) AS tags,
+    (SELECT u.base_currency FROM users u WHERE u.user_id = e.user_id) AS base_currency,
+    convert_amount(
+        e.amount,
+        e.currency,
+        (SELECT u.base_currency FROM users u WHERE u.user_id = e.user_id),
+        e.date,
+        e.user_id
+    ) AS converted_amount";
+
+/// Re-reads an expense with all computed fields, e.g. after an insert or update.
+pub async fn fetch_expense(
+    conn: &mut PgConnection,
+    expense_id: Uuid,
+) -> Result<ExpenseModel, sqlx::Error> {
+    sqlx::query_as::<_, ExpenseModel>(&format!(
+        "SELECT {} FROM expenses e WHERE e.expense_id = $1",
+        EXPENSE_COLUMNS
+    ))
+    .bind(expense_id)
+    .fetch_one(conn)
+    .await
+}
+
+pub fn validate_transaction_type(
+    transaction_type: &str,
+) -> Result<String, ApiResponse<serde_json::Value>> {
+    let transaction_type = transaction_type.trim().to_lowercase();
+    if !TRANSACTION_TYPES.contains(&transaction_type.as_str()) {
+        return Err(ApiResponse::error(
+            "transactionType must be one of 'expense', 'income' or 'refund'",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(transaction_type)
+}
+
+/// Fetches the expense a refund points to. Only transactions of type `expense`
+/// the user can see can be refunded.
+pub async fn get_refundable_expense<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    expense_id: Uuid,
+) -> Result<ExpenseModel, ApiResponse<serde_json::Value>> {
+    let original = sqlx::query_as::<_, ExpenseModel>(
+        "SELECT * FROM expenses
+         WHERE expense_id = $1 AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $2)",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Original expense not found", StatusCode::BAD_REQUEST))?;
+
+    if original.transaction_type != "expense" {
+        return Err(ApiResponse::error(
+            "Only expenses can be refunded",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(original)
+}
+
+/// Total refunded so far for an expense, leaving out the refund `exclude_id`.
+pub async fn get_refunded_amount<'e>(
+    conn: impl PgExecutor<'e>,
+    expense_id: Uuid,
+    exclude_id: Option<Uuid>,
+) -> Result<Money, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount), 0)::BIGINT FROM expenses
+         WHERE original_expense_id = $1 AND deleted_at IS NULL
+            AND ($2::UUID IS NULL OR expense_id <> $2)",
+    )
+    .bind(expense_id)
+    .bind(exclude_id)
+    .fetch_one(conn)
+    .await
+}
+
+/// Checks that a refund is in the original expense's currency and that all of
+/// its refunds together don't exceed the original amount. `refund_id` is the
+/// refund being updated, if any, so its previous amount isn't counted twice.
+pub async fn validate_refund<'e>(
+    conn: impl PgExecutor<'e>,
+    original: &ExpenseModel,
+    amount: Money,
+    currency: &str,
+    refund_id: Option<Uuid>,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    if currency != original.currency {
+        return Err(ApiResponse::error(
+            &format!(
+                "A refund must be in the original expense's currency ({})",
+                original.currency
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let refunded = get_refunded_amount(conn, original.expense_id, refund_id).await?;
+    if refunded.minor_units() + amount.minor_units() > original.amount.minor_units() {
+        return Err(ApiResponse::error(
+            "Refunds can't add up to more than the original expense",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Number of live transactions of a workspace between `start_date` and
+/// `end_date` that can't be converted to `currency` for lack of an exchange
+/// rate, so reports can say how many their totals leave out.
+pub async fn count_unconverted_expenses(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+    currency: &str,
+    start_date: Option<chrono::NaiveDate>,
+    end_date: Option<chrono::NaiveDate>,
+    include_income: bool,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM expenses e
+        WHERE in_workspace(e.user_id, e.household_id, $1, $2)
+            AND e.deleted_at IS NULL
+            AND ($6 OR e.transaction_type <> 'income')
+            AND ($4::DATE IS NULL OR e.date >= $4)
+            AND ($5::DATE IS NULL OR e.date <= $5)
+            AND convert_amount(e.amount, e.currency, $3, e.date, $1) IS NULL",
+    )
+    .bind(user_id)
+    .bind(household_id)
+    .bind(currency)
+    .bind(start_date)
+    .bind(end_date)
+    .bind(include_income)
+    .fetch_one(db)
+    .await
+}
+
+/// Ids of the live expenses matching a bulk filter, oldest first, at most
+/// `limit` of them.
+pub async fn find_matching_expenses(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    filter: &ExpenseFilterSchema,
+    limit: i64,
+) -> Result<Vec<Uuid>, ApiResponse<serde_json::Value>> {
+    let has_condition = filter.category_id.is_some()
+        || filter.uncategorized.unwrap_or(false)
+        || filter.budget_id.is_some()
+        || filter.transaction_type.is_some()
+        || filter.tags.as_ref().is_some_and(|tags| !tags.is_empty())
+        || filter.from.is_some()
+        || filter.to.is_some()
+        || filter.search.as_ref().is_some_and(|s| !s.trim().is_empty());
+
+    if !has_condition {
+        return Err(ApiResponse::error(
+            "The filter needs at least one condition",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let match_all = match filter.tag_match.as_deref() {
+        None | Some("any") => false,
+        Some("all") => true,
+        Some(_) => {
+            return Err(ApiResponse::error(
+                "tagMatch must be either 'any' or 'all'",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+    };
+
+    let household_id = resolve_workspace(db, user_id, filter.household_id).await?;
+
+    let mut query = QueryBuilder::<Postgres>::new(
+        "SELECT e.expense_id FROM expenses e WHERE in_workspace(e.user_id, e.household_id, ",
+    );
+    query
+        .push_bind(user_id)
+        .push(", ")
+        .push_bind(household_id)
+        .push(") AND e.deleted_at IS NULL");
+
+    if let Some(category_id) = filter.category_id {
+        query.push(" AND e.category_id = ").push_bind(category_id);
+    }
+    if filter.uncategorized.unwrap_or(false) {
+        query.push(" AND e.category_id IS NULL");
+    }
+    if let Some(budget_id) = filter.budget_id {
+        query.push(" AND e.budget_id = ").push_bind(budget_id);
+    }
+    if let Some(ref transaction_type) = filter.transaction_type {
+        query
+            .push(" AND e.transaction_type = ")
+            .push_bind(validate_transaction_type(transaction_type)?);
+    }
+    if let Some(from) = filter.from {
+        query.push(" AND e.date >= ").push_bind(from);
+    }
+    if let Some(to) = filter.to {
+        query.push(" AND e.date <= ").push_bind(to);
+    }
+    if let Some(search) = filter.search.as_deref().map(str::trim)
+        && !search.is_empty()
+    {
+        query
+            .push(" AND e.name ILIKE '%' || ")
+            .push_bind(search.replace('%', "\\%").replace('_', "\\_"))
+            .push(" || '%'");
+    }
+
+    let tags = normalize_tags(filter.tags.as_deref().unwrap_or_default())?;
+    if !tags.is_empty() {
+        let tagged = " FROM expense_tags et
+            JOIN tags t ON t.tag_id = et.tag_id
+            WHERE et.expense_id = e.expense_id AND t.name = ANY(";
+
+        if match_all {
+            query
+                .push(" AND (SELECT COUNT(*)")
+                .push(tagged)
+                .push_bind(tags.clone())
+                .push(")) = ")
+                .push_bind(tags.len() as i64);
+        } else {
+            query
+                .push(" AND EXISTS (SELECT 1")
+                .push(tagged)
+                .push_bind(tags)
+                .push("))");
+        }
+    }
+
+    query
+        .push(" ORDER BY e.date, e.created_at LIMIT ")
+        .push_bind(limit);
+
+    Ok(query.build_query_scalar().fetch_all(db).await?)
+}
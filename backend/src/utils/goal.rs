@@ -0,0 +1,179 @@
+use crate::{
+    metrics::record_notifications,
+    models::{Money, SavingsGoalModel},
+    schema::ApiResponse,
+    utils::{account::get_account, currency::format_amount},
+};
+use axum::http::StatusCode;
+use chrono::Datelike;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Progress percentages that trigger a notification the first time a goal reaches them.
+pub const GOAL_MILESTONES: [i16; 4] = [25, 50, 75, 100];
+
+/// Query over `savings_goals g` that fills the saved amount and progress of
+/// `SavingsGoalModel`; append a `WHERE` clause. The progress is rounded for
+/// display, milestones come from the exact amounts.
+pub const GOAL_QUERY: &str = "SELECT g.*, s.saved_amount,
+        ROUND(s.saved_amount * 100.0 / g.target_amount, 1)::FLOAT8 AS progress
+    FROM savings_goals g
+    CROSS JOIN LATERAL (
+        SELECT COALESCE(SUM(c.amount), 0)::BIGINT AS saved_amount
+        FROM goal_contributions c WHERE c.goal_id = g.goal_id
+    ) s";
+
+/// Highest milestone a goal has reached with `saved` of `target`, 0 for
+/// none. Compared exactly, so 99.95% isn't taken for 100%.
+pub fn reached_milestone(saved: Money, target: Money) -> i16 {
+    let saved = saved.minor_units() as i128 * 100;
+    let target = target.minor_units() as i128;
+    GOAL_MILESTONES
+        .iter()
+        .rev()
+        .find(|milestone| saved >= target * **milestone as i128)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Monthly amount still needed to reach the goal by its target date, rounded up
+/// to the minor unit. The current month counts as one payment, so a goal due
+/// this month (or already overdue) needs everything that's left right away.
+pub fn required_monthly_contribution(
+    goal: &SavingsGoalModel,
+    today: chrono::NaiveDate,
+) -> Option<Money> {
+    let target_date = goal.target_date?;
+    let remaining = goal.target_amount.minor_units() - goal.saved_amount.minor_units();
+    if remaining <= 0 {
+        return Some(Money::ZERO);
+    }
+
+    let mut months = (target_date.year() - today.year()) as i64 * 12 + target_date.month() as i64
+        - today.month() as i64;
+    if target_date.day() < today.day() {
+        months -= 1;
+    }
+    let months = months.max(1);
+
+    Some(Money::from_minor_units((remaining + months - 1) / months))
+}
+
+/// Fills the fields of a goal that are computed in Rust.
+pub fn with_required_contribution(mut goal: SavingsGoalModel) -> SavingsGoalModel {
+    goal.required_monthly_contribution =
+        required_monthly_contribution(&goal, chrono::Utc::now().date_naive());
+    goal
+}
+
+/// Fetches one of the user's goals with its progress.
+pub async fn get_goal(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    goal_id: Uuid,
+) -> Result<SavingsGoalModel, ApiResponse<serde_json::Value>> {
+    sqlx::query_as::<_, SavingsGoalModel>(&format!(
+        "{} WHERE g.goal_id = $1 AND g.user_id = $2",
+        GOAL_QUERY
+    ))
+    .bind(goal_id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?
+    .map(with_required_contribution)
+    .ok_or_else(|| ApiResponse::error("Savings goal not found", StatusCode::NOT_FOUND))
+}
+
+/// Checks that an account can hold the money of a goal in `currency`.
+pub async fn validate_goal_account(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    account_id: &str,
+    currency: &str,
+) -> Result<Uuid, ApiResponse<serde_json::Value>> {
+    let account = get_account(db, user_id, Uuid::parse_str(account_id)?).await?;
+    if account.currency != currency {
+        return Err(ApiResponse::error(
+            &format!("The linked account must use the goal currency {}", currency),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(account.account_id)
+}
+
+/// Notifies the user when a goal reaches a milestone it hadn't reached before
+/// and remembers it, so each milestone is announced once. When progress drops
+/// (a withdrawal or a higher target) the milestone is lowered without a
+/// notification, so reaching it again is announced again.
+pub async fn sync_goal_milestone(
+    conn: &mut PgConnection,
+    goal: &SavingsGoalModel,
+) -> Result<(), sqlx::Error> {
+    let milestone = reached_milestone(goal.saved_amount, goal.target_amount);
+    if milestone == goal.last_milestone {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE savings_goals SET last_milestone = $1 WHERE goal_id = $2")
+        .bind(milestone)
+        .bind(goal.goal_id)
+        .execute(&mut *conn)
+        .await?;
+
+    if milestone < goal.last_milestone {
+        return Ok(());
+    }
+
+    let saved = format_amount(goal.saved_amount, &goal.currency);
+    let target = format_amount(goal.target_amount, &goal.currency);
+    let message = if milestone >= 100 {
+        format!(
+            "🎉 GOAL REACHED: You saved {} for '{}' (target: {})",
+            saved, goal.name, target
+        )
+    } else {
+        format!(
+            "🎯 GOAL MILESTONE: '{}' is {}% funded. Saved: {} / Target: {}",
+            goal.name, milestone, saved, target
+        )
+    };
+
+    sqlx::query("INSERT INTO notifications (user_id, category, message) VALUES ($1, $2, $3)")
+        .bind(goal.user_id)
+        .bind("GOAL_MILESTONE")
+        .bind(message)
+        .execute(&mut *conn)
+        .await?;
+    record_notifications("GOAL_MILESTONE", 1);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milestone(saved: i64, target: i64) -> i16 {
+        reached_milestone(
+            Money::from_minor_units(saved),
+            Money::from_minor_units(target),
+        )
+    }
+
+    #[test]
+    fn milestones_use_exact_amounts() {
+        assert_eq!(milestone(0, 10_000), 0);
+        assert_eq!(milestone(2_499, 10_000), 0);
+        assert_eq!(milestone(2_500, 10_000), 25);
+        assert_eq!(milestone(7_499, 10_000), 50);
+        assert_eq!(milestone(9_995, 10_000), 75);
+        assert_eq!(milestone(99_999, 100_000), 75);
+        assert_eq!(milestone(10_000, 10_000), 100);
+        assert_eq!(milestone(25_000, 10_000), 100);
+        assert_eq!(milestone(1, 3), 25);
+        assert_eq!(
+            milestone(Money::MAX_MINOR_UNITS, Money::MAX_MINOR_UNITS),
+            100
+        );
+    }
+}
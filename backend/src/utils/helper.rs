@@ -1,5 +1,6 @@
 use crate::{models::UserModel, schema::ApiResponse};
 use axum::http::StatusCode;
+use sqlx::PgExecutor;
 
 pub fn validate_name(name: &str) -> Result<(), ApiResponse<serde_json::Value>> {
     if name.len() < 2 {
@@ -61,6 +62,32 @@ pub async fn user_exists(
     get_user_by_email(db, email).await.map(|u| u.is_some())
 }
 
+/// Fails with 409 when a client-generated id is already used by a row of
+/// `table_name`, trashed ones included.
+pub async fn ensure_id_available<'e>(
+    conn: impl PgExecutor<'e>,
+    table_name: &str,
+    id_column: &str,
+    id: uuid::Uuid,
+    resource_name: &str,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    let taken: bool = sqlx::query_scalar(&format!(
+        "SELECT EXISTS (SELECT 1 FROM {} WHERE {} = $1)",
+        table_name, id_column
+    ))
+    .bind(id)
+    .fetch_one(conn)
+    .await?;
+
+    if taken {
+        return Err(ApiResponse::error(
+            &format!("{} with this id already exists", resource_name),
+            StatusCode::CONFLICT,
+        ));
+    }
+    Ok(())
+}
+
 #[macro_export]
 macro_rules! ok_or_err {
     ($result:expr, $err_msg:expr, $status:expr) => {
@@ -0,0 +1,195 @@
+use crate::{models::HouseholdModel, schema::ApiResponse};
+use axum::http::StatusCode;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+/// Tables whose rows can be shared in a household through a `household_id` column.
+pub const SHARED_TABLES: [&str; 4] = ["budgets", "categories", "expenses", "settlements"];
+
+pub const HOUSEHOLD_ROLES: [&str; 3] = ["owner", "editor", "viewer"];
+
+/// Roles that can add, change and delete shared expenses, budgets and categories.
+pub const EDITOR_ROLES: [&str; 2] = ["owner", "editor"];
+
+pub fn validate_household_role(role: &str) -> Result<String, ApiResponse<serde_json::Value>> {
+    let role = role.trim().to_lowercase();
+    if !HOUSEHOLD_ROLES.contains(&role.as_str()) {
+        return Err(ApiResponse::error(
+            &format!("role must be one of {}", HOUSEHOLD_ROLES.join(", ")),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(role)
+}
+
+pub async fn get_household_role<'e>(
+    conn: impl PgExecutor<'e>,
+    household_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT role FROM household_members WHERE household_id = $1 AND user_id = $2",
+    )
+    .bind(household_id)
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await
+}
+
+/// Checks that the user is a member of the household with one of `roles` and
+/// returns their role. Non-members get a 404, so household ids can't be probed.
+pub async fn require_household_role<'e>(
+    conn: impl PgExecutor<'e>,
+    household_id: Uuid,
+    user_id: Uuid,
+    roles: &[&str],
+) -> Result<String, ApiResponse<serde_json::Value>> {
+    let role = get_household_role(conn, household_id, user_id)
+        .await?
+        .ok_or_else(|| ApiResponse::error("Household not found", StatusCode::NOT_FOUND))?;
+
+    if !roles.contains(&role.as_str()) {
+        return Err(ApiResponse::error(
+            &format!("Your role '{}' doesn't allow this in the household", role),
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    Ok(role)
+}
+
+/// Household a list or report is scoped to: `None` for the user's personal data,
+/// otherwise a household the user is a member of.
+pub async fn resolve_workspace(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+) -> Result<Option<Uuid>, ApiResponse<serde_json::Value>> {
+    if let Some(household_id) = household_id {
+        require_household_role(db, household_id, user_id, &HOUSEHOLD_ROLES).await?;
+    }
+    Ok(household_id)
+}
+
+/// Household a new expense, budget or category is created in. Adding to a
+/// household needs an editor or owner role.
+pub async fn resolve_target_household<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    household_id: Option<&str>,
+) -> Result<Option<Uuid>, ApiResponse<serde_json::Value>> {
+    let Some(household_id) = household_id else {
+        return Ok(None);
+    };
+    let household_id = Uuid::parse_str(household_id)
+        .map_err(|_| ApiResponse::error("Invalid household ID format", StatusCode::BAD_REQUEST))?;
+
+    require_household_role(conn, household_id, user_id, &EDITOR_ROLES).await?;
+    Ok(Some(household_id))
+}
+
+/// Checks that the user may change a row they can already see. Personal rows
+/// are only ever visible to their owner, so only shared rows need a role check.
+pub async fn require_edit_access<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    if let Some(household_id) = household_id {
+        require_household_role(conn, household_id, user_id, &EDITOR_ROLES).await?;
+    }
+    Ok(())
+}
+
+/// Checks who paid for an expense: a member of its household, or the user
+/// themselves for a personal expense.
+pub async fn validate_paid_by<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+    paid_by: Uuid,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    let is_payer = match household_id {
+        Some(household_id) => get_household_role(conn, household_id, paid_by)
+            .await?
+            .is_some(),
+        None => paid_by == user_id,
+    };
+
+    if !is_payer {
+        return Err(ApiResponse::error(
+            "paidBy must be a member of the expense's household",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that a category or budget used by a row lives in the same workspace,
+/// e.g. a household expense can't use someone's personal budget.
+pub fn ensure_same_workspace(
+    resource_name: &str,
+    resource_household_id: Option<Uuid>,
+    household_id: Option<Uuid>,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    if resource_household_id != household_id {
+        return Err(ApiResponse::error(
+            &format!("{} belongs to a different workspace", resource_name),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(())
+}
+
+/// Locks the household's member rows until the end of the transaction, so
+/// owner checks and the role changes they allow can't interleave: two owners
+/// demoting each other at once would leave the household without one.
+pub async fn lock_household_members<'e>(
+    conn: impl PgExecutor<'e>,
+    household_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT 1 FROM household_members WHERE household_id = $1 FOR UPDATE")
+        .bind(household_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Whether the household has an owner other than `user_id`, so that user can
+/// step down or leave without orphaning it. Lock the members first, see
+/// [`lock_household_members`].
+pub async fn has_other_owner<'e>(
+    conn: impl PgExecutor<'e>,
+    household_id: Uuid,
+    user_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(
+            SELECT 1 FROM household_members
+            WHERE household_id = $1 AND role = 'owner' AND user_id <> $2
+        )",
+    )
+    .bind(household_id)
+    .bind(user_id)
+    .fetch_one(conn)
+    .await
+}
+
+/// Fetches a household with the user's role in it.
+pub async fn get_household(
+    db: &sqlx::PgPool,
+    household_id: Uuid,
+    user_id: Uuid,
+) -> Result<HouseholdModel, ApiResponse<serde_json::Value>> {
+    sqlx::query_as::<_, HouseholdModel>(
+        "SELECT h.*, m.role FROM households h
+         JOIN household_members m ON m.household_id = h.household_id AND m.user_id = $2
+         WHERE h.household_id = $1",
+    )
+    .bind(household_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| ApiResponse::error("Household not found", StatusCode::NOT_FOUND))
+}
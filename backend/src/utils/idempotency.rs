@@ -0,0 +1,141 @@
+use axum::http::{Method, Uri};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Longest `Idempotency-Key` accepted.
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// How long a claim without a saved response holds its key. Past this the
+/// request is assumed abandoned (the server stopped mid-way, say) and a retry
+/// may claim the key again instead of waiting out the whole TTL. Requests are
+/// a transaction or two, so this is far longer than any still running one
+/// takes; a retry taking over a live claim would run the request twice.
+const IN_PROGRESS_LEASE_SECS: f64 = 15.0 * 60.0;
+
+/// Outcome of claiming an idempotency key for a request.
+pub enum IdempotencyClaim {
+    /// First use of the key: run the request and save its response. The
+    /// claim is identified by when it was made, see
+    /// [`save_idempotent_response`].
+    Claimed { claimed_at: DateTime<Utc> },
+    /// The same request already finished; send its response again
+    Replay { status: u16, body: Vec<u8> },
+    /// The key was used for a different request
+    Mismatch,
+    /// The same request is still running
+    InProgress,
+}
+
+/// Hash of what makes a request the same request: method, path with query,
+/// and body.
+pub fn request_fingerprint(method: &Method, uri: &Uri, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b" ");
+    hasher.update(uri.to_string());
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Claims `key` for a request, taking over an expired claim or one still in
+/// progress after [`IN_PROGRESS_LEASE_SECS`]. When the key is taken, says what
+/// to do with the retry instead.
+pub async fn claim_idempotency_key(
+    db: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    fingerprint: &str,
+    ttl_hours: i32,
+) -> Result<IdempotencyClaim, sqlx::Error> {
+    let claimed_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "INSERT INTO idempotency_keys (user_id, idempotency_key, fingerprint, expires_at)
+         VALUES ($1, $2, $3, now() + make_interval(hours => $4))
+         ON CONFLICT (user_id, idempotency_key) DO UPDATE
+         SET fingerprint = EXCLUDED.fingerprint, status_code = NULL, response_body = NULL,
+            created_at = now(), expires_at = EXCLUDED.expires_at
+         WHERE idempotency_keys.expires_at <= now()
+            OR (idempotency_keys.status_code IS NULL
+                AND idempotency_keys.created_at < now() - make_interval(secs => $5))
+         RETURNING created_at",
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(fingerprint)
+    .bind(ttl_hours)
+    .bind(IN_PROGRESS_LEASE_SECS)
+    .fetch_optional(db)
+    .await?;
+
+    if let Some(claimed_at) = claimed_at {
+        return Ok(IdempotencyClaim::Claimed { claimed_at });
+    }
+
+    let existing: Option<(String, Option<i16>, Option<Vec<u8>>)> = sqlx::query_as(
+        "SELECT fingerprint, status_code, response_body FROM idempotency_keys
+         WHERE user_id = $1 AND idempotency_key = $2",
+    )
+    .bind(user_id)
+    .bind(key)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(match existing {
+        Some((existing, _, _)) if existing != fingerprint => IdempotencyClaim::Mismatch,
+        Some((_, Some(status), body)) => IdempotencyClaim::Replay {
+            status: status as u16,
+            body: body.unwrap_or_default(),
+        },
+        // Purged between the two queries or still running; either way the
+        // client can retry shortly.
+        _ => IdempotencyClaim::InProgress,
+    })
+}
+
+/// Stores the response to a claimed request for retries. Does nothing when
+/// the claim made at `claimed_at` was taken over in the meantime, so a late
+/// request can't overwrite what the retry stored.
+pub async fn save_idempotent_response(
+    db: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    claimed_at: DateTime<Utc>,
+    status: u16,
+    body: &[u8],
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE idempotency_keys SET status_code = $4, response_body = $5
+         WHERE user_id = $1 AND idempotency_key = $2 AND created_at = $3",
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(claimed_at)
+    .bind(status as i16)
+    .bind(body)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Gives up the claim made at `claimed_at`, so the request can be retried for
+/// real (after a server error, for instance). A claim taken over by a retry
+/// is left alone.
+pub async fn release_idempotency_key(
+    db: &PgPool,
+    user_id: Uuid,
+    key: &str,
+    claimed_at: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM idempotency_keys
+         WHERE user_id = $1 AND idempotency_key = $2 AND created_at = $3",
+    )
+    .bind(user_id)
+    .bind(key)
+    .bind(claimed_at)
+    .execute(db)
+    .await?;
+    Ok(())
+}
@@ -9,19 +9,18 @@ pub struct Claims {
     pub exp: usize,
 }
 
-pub fn sign(data: &String) -> Result<String, String> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::days(30))
-        .expect("valid timestamp")
+/// Issues a token for `data` (the user id) that is valid for `lifetime_days`.
+pub fn sign(data: &str, secret_key: &str, lifetime_days: i64) -> Result<String, String> {
+    let expiration = Duration::try_days(lifetime_days)
+        .and_then(|lifetime| Utc::now().checked_add_signed(lifetime))
+        .ok_or_else(|| format!("Invalid token lifetime of {} days", lifetime_days))?
         .timestamp() as usize;
 
     let claims = Claims {
-        sub: data.clone(), // Convert UUID to string for standard 'sub' claim
+        sub: data.to_string(), // Convert UUID to string for standard 'sub' claim
         exp: expiration,
     };
 
-    let secret_key = std::env::var("JWT_SECRET").expect("JWT_SECRET is not found in env");
-
     let token = match encode(
         &Header::default(),
         &claims,
@@ -34,12 +33,10 @@ pub fn sign(data: &String) -> Result<String, String> {
     Ok(token)
 }
 
-pub fn verify(token: &String) -> Result<Uuid, String> {
-    let secret_key = std::env::var("JWT_SECRET").expect("JWT_SECRET is not found in env");
-
+pub fn verify(token: &str, secret_key: &str) -> Result<Uuid, String> {
     let validation = Validation::default();
     let decoded = decode::<Claims>(
-        token.clone(),
+        token,
         &DecodingKey::from_secret(secret_key.as_ref()),
         &validation,
     );
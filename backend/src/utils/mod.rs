@@ -1,7 +1,24 @@
+pub mod account;
+pub mod attachment;
+pub mod audit;
+pub mod bill;
+pub mod budget;
+pub mod category;
+pub mod currency;
+pub mod etag;
+pub mod expense;
+pub mod goal;
 pub mod hash;
 pub mod helper;
+pub mod household;
+pub mod idempotency;
 pub mod jwt;
 pub mod pattern;
+pub mod rule;
+pub mod split;
+pub mod sync;
+pub mod tag;
+pub mod trash;
 
 pub use hash::*;
 pub use jwt::*;
@@ -0,0 +1,145 @@
+use crate::{
+    models::{ExpenseRuleModel, Money},
+    schema::ApiResponse,
+    utils::{budget::get_workspace_budget, category::get_workspace_category},
+};
+use axum::http::StatusCode;
+use regex::{Regex, RegexBuilder};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+/// Compiles a rule's name pattern. Matching is case-insensitive and the compiled
+/// size is capped so a user-supplied pattern can't blow up memory.
+pub fn compile_rule_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(1 << 20)
+        .build()
+}
+
+/// Whether every condition set on the rule matches the expense. `amount` is
+/// in the user's base currency, like the rule's bounds; when it's unknown
+/// (no exchange rate) the amount conditions are skipped, and a rule with only
+/// amount conditions doesn't match.
+pub fn rule_matches(rule: &ExpenseRuleModel, name: &str, amount: Option<Money>) -> bool {
+    if let Some(ref needle) = rule.name_contains
+        && !name.to_lowercase().contains(&needle.to_lowercase())
+    {
+        return false;
+    }
+
+    if let Some(ref pattern) = rule.name_regex {
+        match compile_rule_regex(pattern) {
+            Ok(regex) if regex.is_match(name) => {}
+            _ => return false,
+        }
+    }
+
+    let Some(amount) = amount else {
+        return rule.name_contains.is_some() || rule.name_regex.is_some();
+    };
+
+    if rule.min_amount.is_some_and(|min| amount < min) {
+        return false;
+    }
+
+    if rule.max_amount.is_some_and(|max| amount > max) {
+        return false;
+    }
+
+    true
+}
+
+/// First active rule, in priority order, that matches the expense. `amount`
+/// is in the user's base currency, see [`convert_to_base_currency`].
+///
+/// [`convert_to_base_currency`]: crate::utils::currency::convert_to_base_currency
+pub async fn find_matching_rule<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    name: &str,
+    amount: Option<Money>,
+) -> Result<Option<ExpenseRuleModel>, sqlx::Error> {
+    let rules = sqlx::query_as::<_, ExpenseRuleModel>(
+        "SELECT * FROM expense_rules WHERE user_id = $1 AND is_active ORDER BY priority, created_at",
+    )
+    .bind(user_id)
+    .fetch_all(conn)
+    .await?;
+
+    Ok(rules
+        .into_iter()
+        .find(|rule| rule_matches(rule, name, amount)))
+}
+
+pub fn validate_rule_conditions(
+    name_contains: Option<&str>,
+    name_regex: Option<&str>,
+    min_amount: Option<Money>,
+    max_amount: Option<Money>,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    if name_contains.is_none()
+        && name_regex.is_none()
+        && min_amount.is_none()
+        && max_amount.is_none()
+    {
+        return Err(ApiResponse::error(
+            "A rule needs at least one condition: nameContains, nameRegex, minAmount or maxAmount",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if name_contains.is_some_and(|n| n.trim().is_empty()) {
+        return Err(ApiResponse::error(
+            "nameContains cannot be empty",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if let Some(pattern) = name_regex
+        && let Err(err) = compile_rule_regex(pattern)
+    {
+        return Err(ApiResponse::error(
+            &format!("Invalid nameRegex: {}", err),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if let (Some(min), Some(max)) = (min_amount, max_amount)
+        && min > max
+    {
+        return Err(ApiResponse::error(
+            "minAmount can't be greater than maxAmount",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(())
+}
+
+pub async fn validate_rule_actions(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    category_id: Option<i32>,
+    tags: &[String],
+    budget_id: Option<Uuid>,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    if category_id.is_none() && tags.is_empty() && budget_id.is_none() {
+        return Err(ApiResponse::error(
+            "A rule needs at least one action: categoryId, tags or budgetId",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    // Rules only apply to personal expenses, so their category and budget
+    // must be personal (or global) too.
+    if let Some(category_id) = category_id {
+        get_workspace_category(db, user_id, None, category_id).await?;
+    }
+
+    if let Some(budget_id) = budget_id {
+        get_workspace_budget(db, user_id, None, budget_id).await?;
+    }
+
+    Ok(())
+}
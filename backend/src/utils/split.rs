@@ -0,0 +1,610 @@
+use crate::{
+    models::{ExpenseSplitModel, Money, SplitBalance, SplitDebtModel, SplitParty},
+    schema::{ApiResponse, SplitParticipantSchema},
+    utils::{currency::parse_amount, household::get_household_role},
+};
+use axum::http::StatusCode;
+use sqlx::{PgConnection, PgExecutor};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+pub const SPLIT_METHODS: [&str; 4] = ["equal", "exact", "percent", "shares"];
+
+/// Percentages are stored as basis points, so 100% is 10000.
+const FULL_PERCENT: i64 = 10_000;
+
+/// Most shares one participant can have in a shares split.
+pub const MAX_SHARES: i64 = 1_000_000;
+
+pub fn validate_split_method(method: &str) -> Result<String, ApiResponse<serde_json::Value>> {
+    let method = method.trim().to_lowercase();
+    if !SPLIT_METHODS.contains(&method.as_str()) {
+        return Err(ApiResponse::error(
+            &format!("method must be one of {}", SPLIT_METHODS.join(", ")),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(method)
+}
+
+/// Weight a participant's share is computed from under the given method.
+pub fn participant_weight(
+    method: &str,
+    participant: &SplitParticipantSchema,
+    currency: &str,
+) -> Result<i64, ApiResponse<serde_json::Value>> {
+    let weight = match method {
+        "equal" => 1,
+        "exact" => {
+            let amount = participant.amount.as_ref().ok_or_else(|| {
+                ApiResponse::error(
+                    "Every participant needs an amount for an exact split",
+                    StatusCode::BAD_REQUEST,
+                )
+            })?;
+            parse_amount(amount, currency)?.minor_units()
+        }
+        "percent" => {
+            let percent = participant.percent.ok_or_else(|| {
+                ApiResponse::error(
+                    "Every participant needs a percent for a percent split",
+                    StatusCode::BAD_REQUEST,
+                )
+            })?;
+            let basis_points = (percent * 100.0).round();
+            if !percent.is_finite() || (percent * 100.0 - basis_points).abs() > 1e-6 {
+                return Err(ApiResponse::error(
+                    "percent can have at most two decimal places",
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+            if basis_points > FULL_PERCENT as f64 {
+                return Err(ApiResponse::error(
+                    "percent can't be more than 100",
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+            basis_points as i64
+        }
+        _ => {
+            let shares = participant.shares.ok_or_else(|| {
+                ApiResponse::error(
+                    "Every participant needs a number of shares for a shares split",
+                    StatusCode::BAD_REQUEST,
+                )
+            })?;
+            if shares > MAX_SHARES {
+                return Err(ApiResponse::error(
+                    &format!("shares can't be more than {}", MAX_SHARES),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+            shares
+        }
+    };
+
+    if weight < 0 {
+        return Err(ApiResponse::error(
+            "Split values can't be less than zero",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(weight)
+}
+
+/// Divides `total` proportionally to `weights` using the largest remainder
+/// method, so the shares always add up to `total`. Leftover minor units go to
+/// the largest remainders, ties to the earlier participant. Negative weights
+/// count as zero.
+pub fn allocate_by_weights(total: Money, weights: &[i64]) -> Vec<Money> {
+    let weights: Vec<i128> = weights.iter().map(|w| (*w).max(0) as i128).collect();
+    let total_weight: i128 = weights.iter().sum();
+    if total_weight == 0 {
+        return vec![Money::ZERO; weights.len()];
+    }
+
+    let total_units = total.minor_units() as i128;
+    let mut shares: Vec<i128> = Vec::with_capacity(weights.len());
+    let mut remainders: Vec<(i128, usize)> = Vec::with_capacity(weights.len());
+    for (index, weight) in weights.iter().enumerate() {
+        let product = total_units * weight;
+        shares.push(product / total_weight);
+        remainders.push((product % total_weight, index));
+    }
+
+    let leftover = total_units - shares.iter().sum::<i128>();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, index) in remainders.into_iter().take(leftover as usize) {
+        shares[index] += 1;
+    }
+
+    shares
+        .into_iter()
+        .map(|share| Money::from_minor_units(share as i64))
+        .collect()
+}
+
+/// Computes each participant's share of `total` and checks that the weights
+/// make sense for the method.
+pub fn split_amounts(
+    method: &str,
+    total: Money,
+    weights: &[i64],
+) -> Result<Vec<Money>, ApiResponse<serde_json::Value>> {
+    let weight_sum = weights
+        .iter()
+        .try_fold(0i64, |sum, weight| sum.checked_add(*weight))
+        .ok_or_else(|| ApiResponse::error("Split values are too large", StatusCode::BAD_REQUEST))?;
+    match method {
+        "exact" if weight_sum != total.minor_units() => {
+            return Err(ApiResponse::error(
+                "Exact amounts must add up to the expense amount",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        "exact" => {
+            return Ok(weights
+                .iter()
+                .map(|w| Money::from_minor_units(*w))
+                .collect());
+        }
+        "percent" if weight_sum != FULL_PERCENT => {
+            return Err(ApiResponse::error(
+                "Percentages must add up to 100",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        "shares" if weight_sum == 0 => {
+            return Err(ApiResponse::error(
+                "At least one participant needs a share",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        _ => {}
+    }
+
+    Ok(allocate_by_weights(total, weights))
+}
+
+/// Resolves one side of a split or settlement: exactly one of a registered user
+/// or a contact. Registered users must be the current user or, in a household,
+/// one of its members; contacts must belong to the current user or a member.
+pub async fn resolve_split_party<'e>(
+    conn: impl PgExecutor<'e>,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+    party_user_id: Option<&str>,
+    party_contact_id: Option<&str>,
+) -> Result<(Option<Uuid>, Option<Uuid>), ApiResponse<serde_json::Value>> {
+    match (party_user_id, party_contact_id) {
+        (Some(party_user_id), None) => {
+            let party_user_id = Uuid::parse_str(party_user_id)?;
+            let allowed = match household_id {
+                Some(household_id) => get_household_role(conn, household_id, party_user_id)
+                    .await?
+                    .is_some(),
+                None => party_user_id == user_id,
+            };
+            if !allowed {
+                return Err(ApiResponse::error(
+                    "Only household members can take part as users, add other people as contacts",
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+            Ok((Some(party_user_id), None))
+        }
+        (None, Some(contact_id)) => {
+            let contact_id = Uuid::parse_str(contact_id)?;
+            let allowed: bool = sqlx::query_scalar(
+                "SELECT EXISTS(
+                    SELECT 1 FROM contacts c
+                    WHERE c.contact_id = $1 AND (c.user_id = $2 OR EXISTS(
+                        SELECT 1 FROM household_members m
+                        WHERE m.household_id = $3 AND m.user_id = c.user_id
+                    ))
+                )",
+            )
+            .bind(contact_id)
+            .bind(user_id)
+            .bind(household_id)
+            .fetch_one(conn)
+            .await?;
+            if !allowed {
+                return Err(ApiResponse::error(
+                    "Contact not found",
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+            Ok((None, Some(contact_id)))
+        }
+        _ => Err(ApiResponse::error(
+            "Set exactly one of userId and contactId",
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+/// Recomputes the shares of a split expense after its amount changed. Exact
+/// splits can't be rescaled and must be set again when they stop adding up.
+pub async fn resplit_expense(
+    conn: &mut PgConnection,
+    expense_id: Uuid,
+    amount: Money,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    let method: Option<String> =
+        sqlx::query_scalar("SELECT split_method FROM expenses WHERE expense_id = $1")
+            .bind(expense_id)
+            .fetch_one(&mut *conn)
+            .await?;
+    let Some(method) = method else {
+        return Ok(());
+    };
+
+    let splits: Vec<(Uuid, i64)> = sqlx::query_as(
+        "SELECT split_id, weight FROM expense_splits WHERE expense_id = $1 ORDER BY position",
+    )
+    .bind(expense_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let weights: Vec<i64> = splits.iter().map(|(_, weight)| *weight).collect();
+    let amounts = split_amounts(&method, amount, &weights).map_err(|_| {
+        ApiResponse::error(
+            "The expense has an exact split that no longer adds up, update the split first",
+            StatusCode::BAD_REQUEST,
+        )
+    })?;
+
+    let split_ids: Vec<Uuid> = splits.iter().map(|(split_id, _)| *split_id).collect();
+    let amounts: Vec<i64> = amounts.iter().map(|amount| amount.minor_units()).collect();
+    sqlx::query(
+        "UPDATE expense_splits s SET amount = u.amount
+         FROM UNNEST($1::UUID[], $2::BIGINT[]) AS u(split_id, amount)
+         WHERE s.split_id = u.split_id",
+    )
+    .bind(&split_ids)
+    .bind(&amounts)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Debts of a workspace with participant names, one row per debtor, creditor
+/// and currency.
+pub async fn get_split_debts(
+    db: &sqlx::PgPool,
+    user_id: Uuid,
+    household_id: Option<Uuid>,
+) -> Result<Vec<SplitDebtModel>, sqlx::Error> {
+    sqlx::query_as::<_, SplitDebtModel>(
+        "SELECT d.debtor_user_id, d.debtor_contact_id,
+            COALESCE(du.name, dc.name) AS debtor_name,
+            d.creditor_user_id, d.creditor_contact_id,
+            COALESCE(cu.name, cc.name) AS creditor_name,
+            d.currency, SUM(d.amount)::BIGINT AS amount
+         FROM split_debts($1, $2) d
+         LEFT JOIN users du ON du.user_id = d.debtor_user_id
+         LEFT JOIN contacts dc ON dc.contact_id = d.debtor_contact_id
+         LEFT JOIN users cu ON cu.user_id = d.creditor_user_id
+         LEFT JOIN contacts cc ON cc.contact_id = d.creditor_contact_id
+         GROUP BY d.debtor_user_id, d.debtor_contact_id, debtor_name,
+            d.creditor_user_id, d.creditor_contact_id, creditor_name, d.currency",
+    )
+    .bind(user_id)
+    .bind(household_id)
+    .fetch_all(db)
+    .await
+}
+
+/// Nets the debts of every pair of people per currency, so each pair and
+/// currency appears at most once with whoever owes the other.
+pub fn net_pair_balances(debts: Vec<SplitDebtModel>) -> Vec<SplitBalance> {
+    let mut net: BTreeMap<(SplitParty, SplitParty, String), i64> = BTreeMap::new();
+
+    for debt in debts {
+        let debtor = SplitParty {
+            user_id: debt.debtor_user_id,
+            contact_id: debt.debtor_contact_id,
+            name: debt.debtor_name,
+        };
+        let creditor = SplitParty {
+            user_id: debt.creditor_user_id,
+            contact_id: debt.creditor_contact_id,
+            name: debt.creditor_name,
+        };
+        if debtor == creditor {
+            continue;
+        }
+
+        // Key each pair in a fixed order; positive means the first owes the second.
+        let amount = debt.amount.minor_units();
+        let (key, amount) = if debtor < creditor {
+            ((debtor, creditor, debt.currency), amount)
+        } else {
+            ((creditor, debtor, debt.currency), -amount)
+        };
+        *net.entry(key).or_default() += amount;
+    }
+
+    net.into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .map(|((first, second, currency), amount)| {
+            let (from, to) = if amount > 0 {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            SplitBalance {
+                from,
+                to,
+                currency,
+                amount: Money::from_minor_units(amount.abs()),
+            }
+        })
+        .collect()
+}
+
+/// Suggests the payments that settle all balances: per currency, the largest
+/// debtor pays the largest creditor until everyone is even. Needs at most one
+/// payment fewer than there are people with a non-zero balance.
+pub fn simplify_debts(balances: &[SplitBalance]) -> Vec<SplitBalance> {
+    let mut positions: BTreeMap<String, BTreeMap<SplitParty, i64>> = BTreeMap::new();
+    for balance in balances {
+        let currency = positions.entry(balance.currency.clone()).or_default();
+        *currency.entry(balance.from.clone()).or_default() -= balance.amount.minor_units();
+        *currency.entry(balance.to.clone()).or_default() += balance.amount.minor_units();
+    }
+
+    let mut payments = Vec::new();
+    for (currency, parties) in positions {
+        let mut debtors: Vec<(SplitParty, i64)> = Vec::new();
+        let mut creditors: Vec<(SplitParty, i64)> = Vec::new();
+        for (party, position) in parties {
+            match position {
+                p if p < 0 => debtors.push((party, -p)),
+                p if p > 0 => creditors.push((party, p)),
+                _ => {}
+            }
+        }
+        debtors.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+        creditors.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+
+        let (mut d, mut c) = (0, 0);
+        while d < debtors.len() && c < creditors.len() {
+            let amount = debtors[d].1.min(creditors[c].1);
+            payments.push(SplitBalance {
+                from: debtors[d].0.clone(),
+                to: creditors[c].0.clone(),
+                currency: currency.clone(),
+                amount: Money::from_minor_units(amount),
+            });
+            debtors[d].1 -= amount;
+            creditors[c].1 -= amount;
+            if debtors[d].1 == 0 {
+                d += 1;
+            }
+            if creditors[c].1 == 0 {
+                c += 1;
+            }
+        }
+    }
+
+    payments
+}
+
+/// Participants of a split expense with their names, in the order they were given.
+pub async fn fetch_expense_splits(
+    conn: &mut PgConnection,
+    expense_id: Uuid,
+) -> Result<Vec<ExpenseSplitModel>, sqlx::Error> {
+    sqlx::query_as::<_, ExpenseSplitModel>(
+        "SELECT s.*, COALESCE(u.name, c.name) AS name
+         FROM expense_splits s
+         LEFT JOIN users u ON u.user_id = s.user_id
+         LEFT JOIN contacts c ON c.contact_id = s.contact_id
+         WHERE s.expense_id = $1
+         ORDER BY s.position",
+    )
+    .bind(expense_id)
+    .fetch_all(conn)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(units: &[i64]) -> Vec<Money> {
+        units.iter().map(|u| Money::from_minor_units(*u)).collect()
+    }
+
+    fn party(name: &str) -> SplitParty {
+        SplitParty {
+            user_id: None,
+            contact_id: None,
+            name: name.to_string(),
+        }
+    }
+
+    fn debt(debtor: &str, creditor: &str, currency: &str, amount: i64) -> SplitDebtModel {
+        SplitDebtModel {
+            debtor_user_id: None,
+            debtor_contact_id: None,
+            debtor_name: debtor.to_string(),
+            creditor_user_id: None,
+            creditor_contact_id: None,
+            creditor_name: creditor.to_string(),
+            currency: currency.to_string(),
+            amount: Money::from_minor_units(amount),
+        }
+    }
+
+    fn balance(from: &str, to: &str, amount: i64) -> SplitBalance {
+        SplitBalance {
+            from: party(from),
+            to: party(to),
+            currency: "INR".to_string(),
+            amount: Money::from_minor_units(amount),
+        }
+    }
+
+    /// Net position of every party per currency: positive is owed money.
+    fn positions(balances: &[SplitBalance]) -> BTreeMap<(String, String), i64> {
+        let mut positions = BTreeMap::new();
+        for balance in balances {
+            let currency = &balance.currency;
+            *positions
+                .entry((balance.from.name.clone(), currency.clone()))
+                .or_default() -= balance.amount.minor_units();
+            *positions
+                .entry((balance.to.name.clone(), currency.clone()))
+                .or_default() += balance.amount.minor_units();
+        }
+        positions.retain(|_, amount| *amount != 0);
+        positions
+    }
+
+    #[test]
+    fn allocation_hands_out_the_remainder() {
+        let total = Money::from_minor_units(100);
+        assert_eq!(allocate_by_weights(total, &[1, 1, 1]), money(&[34, 33, 33]));
+        assert_eq!(allocate_by_weights(total, &[1, 2]), money(&[33, 67]));
+        assert_eq!(
+            allocate_by_weights(Money::from_minor_units(1000), &[3333, 3333, 3334]),
+            money(&[333, 333, 334])
+        );
+    }
+
+    #[test]
+    fn allocation_sums_to_the_total() {
+        for total in [1, 7, 100, 9_999, 1_000_001, Money::MAX_MINOR_UNITS] {
+            for weights in [
+                vec![1, 1, 1],
+                vec![1, 2, 3, 4, 5, 6, 7],
+                vec![3333, 3333, 3334],
+                vec![MAX_SHARES, 1, MAX_SHARES - 1],
+                vec![17],
+            ] {
+                let shares = allocate_by_weights(Money::from_minor_units(total), &weights);
+                let sum: i64 = shares.iter().map(|s| s.minor_units()).sum();
+                assert_eq!(sum, total, "{total} split by {weights:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn allocation_skips_zero_and_negative_weights() {
+        let total = Money::from_minor_units(100);
+        assert_eq!(allocate_by_weights(total, &[0, 0]), money(&[0, 0]));
+        assert_eq!(allocate_by_weights(total, &[]), money(&[]));
+        assert_eq!(allocate_by_weights(total, &[0, 1, 1]), money(&[0, 50, 50]));
+        assert_eq!(allocate_by_weights(total, &[-5, 1, 2]), money(&[0, 33, 67]));
+        assert_eq!(allocate_by_weights(total, &[-1, -1]), money(&[0, 0]));
+    }
+
+    #[test]
+    fn split_amounts_checks_the_weights() {
+        let total = Money::from_minor_units(1000);
+        assert_eq!(
+            split_amounts("exact", total, &[400, 600]).unwrap(),
+            money(&[400, 600])
+        );
+        assert!(split_amounts("exact", total, &[400, 500]).is_err());
+        assert!(split_amounts("percent", total, &[5000, 4000]).is_err());
+        assert!(split_amounts("shares", total, &[0, 0]).is_err());
+        assert!(split_amounts("shares", total, &[i64::MAX, 1]).is_err());
+        assert!(split_amounts("exact", total, &[i64::MAX, i64::MAX]).is_err());
+    }
+
+    #[test]
+    fn participant_weight_caps_values() {
+        let participant = |percent: Option<f64>, shares: Option<i64>| SplitParticipantSchema {
+            user_id: None,
+            contact_id: None,
+            amount: None,
+            percent,
+            shares,
+        };
+        assert_eq!(
+            participant_weight("shares", &participant(None, Some(MAX_SHARES)), "INR").unwrap(),
+            MAX_SHARES
+        );
+        assert!(
+            participant_weight("shares", &participant(None, Some(MAX_SHARES + 1)), "INR").is_err()
+        );
+        assert!(participant_weight("shares", &participant(None, Some(-1)), "INR").is_err());
+        assert_eq!(
+            participant_weight("percent", &participant(Some(33.33), None), "INR").unwrap(),
+            3333
+        );
+        assert!(participant_weight("percent", &participant(Some(1e300), None), "INR").is_err());
+        assert!(participant_weight("percent", &participant(Some(f64::NAN), None), "INR").is_err());
+    }
+
+    #[test]
+    fn pair_balances_net_out() {
+        let balances = net_pair_balances(vec![
+            debt("a", "b", "INR", 500),
+            debt("b", "a", "INR", 200),
+            debt("b", "a", "USD", 100),
+            debt("c", "d", "INR", 300),
+            debt("d", "c", "INR", 300),
+            debt("a", "a", "INR", 999),
+        ]);
+
+        let summary: Vec<_> = balances
+            .iter()
+            .map(|b| {
+                (
+                    b.from.name.as_str(),
+                    b.to.name.as_str(),
+                    b.currency.as_str(),
+                    b.amount.minor_units(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("a", "b", "INR", 300), ("b", "a", "USD", 100)]
+        );
+    }
+
+    #[test]
+    fn simplified_debts_keep_every_balance() {
+        let balances = vec![
+            balance("a", "b", 1000),
+            balance("b", "c", 1000),
+            balance("c", "d", 500),
+            balance("d", "a", 200),
+            balance("e", "a", 750),
+            SplitBalance {
+                currency: "USD".to_string(),
+                ..balance("b", "e", 300)
+            },
+        ];
+
+        let payments = simplify_debts(&balances);
+        assert_eq!(positions(&payments), positions(&balances));
+        assert!(payments.iter().all(|p| p.amount.is_positive()));
+
+        // At most one payment fewer than the people who aren't even, per currency
+        let inr_parties = positions(&balances)
+            .keys()
+            .filter(|(_, currency)| currency == "INR")
+            .count();
+        let inr_payments = payments.iter().filter(|p| p.currency == "INR").count();
+        assert!(inr_payments < inr_parties);
+    }
+
+    #[test]
+    fn simplified_debts_drop_settled_circles() {
+        let balances = vec![
+            balance("a", "b", 100),
+            balance("b", "c", 100),
+            balance("c", "a", 100),
+        ];
+        assert!(simplify_debts(&balances).is_empty());
+    }
+}
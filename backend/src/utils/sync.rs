@@ -0,0 +1,205 @@
+use axum::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    models::{BudgetModel, CategoryModel, ExpenseModel, NotificationModel},
+    schema::ApiResponse,
+    utils::expense::EXPENSE_COLUMNS,
+};
+
+/// Most mutations a single sync can carry.
+pub const MAX_SYNC_MUTATIONS: usize = 500;
+
+/// Entities clients can change through a sync; categories and notifications
+/// only travel from the server to the client.
+pub const SYNC_MUTATION_TYPES: [&str; 2] = ["budget", "expense"];
+
+/// What a sync mutation can do to an entity.
+pub const SYNC_OPS: [&str; 2] = ["delete", "upsert"];
+
+/// Entities that changed for a user since a sync token: ids of the ones to
+/// send in full, and tombstones (entity type and id) of deleted ones.
+#[derive(Debug, Default)]
+pub struct SyncChanges {
+    pub expense_ids: Vec<Uuid>,
+    pub budget_ids: Vec<Uuid>,
+    pub category_ids: Vec<i32>,
+    pub notification_ids: Vec<Uuid>,
+    pub deleted: Vec<(String, String)>,
+}
+
+pub fn validate_sync_mutation(
+    entity_type: &str,
+    op: &str,
+) -> Result<(), ApiResponse<serde_json::Value>> {
+    if !SYNC_MUTATION_TYPES.contains(&entity_type) {
+        return Err(ApiResponse::error(
+            "entityType must be 'expense' or 'budget'",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    if !SYNC_OPS.contains(&op) {
+        return Err(ApiResponse::error(
+            "op must be 'upsert' or 'delete'",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(())
+}
+
+/// Position in the user's change sequence a sync token stands for; `None`
+/// without a token.
+pub fn parse_sync_token(
+    token: Option<&str>,
+) -> Result<Option<i64>, ApiResponse<serde_json::Value>> {
+    token
+        .map(|token| {
+            token
+                .parse::<i64>()
+                .ok()
+                .filter(|seq| *seq >= 0)
+                .ok_or_else(|| ApiResponse::error("Invalid syncToken", StatusCode::BAD_REQUEST))
+        })
+        .transpose()
+}
+
+/// Latest committed position in the user's change sequence. Changes up to it
+/// have all committed, so it is safe to hand out as the next sync token.
+pub async fn current_sync_seq(db: &PgPool, user_id: Uuid) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT COALESCE((SELECT last_seq FROM sync_sequences WHERE user_id = $1), 0)",
+    )
+    .bind(user_id)
+    .fetch_one(db)
+    .await
+}
+
+/// Entities changed for the user after position `since`, up to `until`.
+pub async fn get_sync_changes(
+    db: &PgPool,
+    user_id: Uuid,
+    since: i64,
+    until: i64,
+) -> Result<SyncChanges, sqlx::Error> {
+    let rows: Vec<(String, String, bool)> = sqlx::query_as(
+        "SELECT entity_type, entity_id, deleted FROM sync_changes
+         WHERE user_id = $1 AND seq > $2 AND seq <= $3
+         ORDER BY seq",
+    )
+    .bind(user_id)
+    .bind(since)
+    .bind(until)
+    .fetch_all(db)
+    .await?;
+
+    let mut changes = SyncChanges::default();
+    for (entity_type, entity_id, deleted) in rows {
+        if deleted {
+            changes.deleted.push((entity_type, entity_id));
+            continue;
+        }
+        match entity_type.as_str() {
+            "expense" => changes.expense_ids.extend(Uuid::parse_str(&entity_id).ok()),
+            "budget" => changes.budget_ids.extend(Uuid::parse_str(&entity_id).ok()),
+            "category" => changes.category_ids.extend(entity_id.parse::<i32>().ok()),
+            "notification" => changes
+                .notification_ids
+                .extend(Uuid::parse_str(&entity_id).ok()),
+            _ => {}
+        }
+    }
+    Ok(changes)
+}
+
+/// Live expenses the user can see, limited to `ids` when given.
+pub async fn get_sync_expenses(
+    db: &PgPool,
+    user_id: Uuid,
+    ids: Option<&[Uuid]>,
+) -> Result<Vec<ExpenseModel>, sqlx::Error> {
+    if ids.is_some_and(|ids| ids.is_empty()) {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_as::<_, ExpenseModel>(&format!(
+        "SELECT {} FROM expenses e
+         WHERE ($2::UUID[] IS NULL OR e.expense_id = ANY($2))
+            AND e.deleted_at IS NULL AND can_view_resource(e.user_id, e.household_id, $1)
+         ORDER BY e.created_at",
+        EXPENSE_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(ids)
+    .fetch_all(db)
+    .await
+}
+
+/// Live budgets the user can see, limited to `ids` when given.
+pub async fn get_sync_budgets(
+    db: &PgPool,
+    user_id: Uuid,
+    ids: Option<&[Uuid]>,
+) -> Result<Vec<BudgetModel>, sqlx::Error> {
+    if ids.is_some_and(|ids| ids.is_empty()) {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_as::<_, BudgetModel>(
+        "SELECT * FROM budgets
+         WHERE ($2::UUID[] IS NULL OR budget_id = ANY($2))
+            AND deleted_at IS NULL AND can_view_resource(user_id, household_id, $1)
+         ORDER BY created_at",
+    )
+    .bind(user_id)
+    .bind(ids)
+    .fetch_all(db)
+    .await
+}
+
+/// Live categories the user can see, global ones included, limited to `ids`
+/// when given.
+pub async fn get_sync_categories(
+    db: &PgPool,
+    user_id: Uuid,
+    ids: Option<&[i32]>,
+) -> Result<Vec<CategoryModel>, sqlx::Error> {
+    if ids.is_some_and(|ids| ids.is_empty()) {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_as::<_, CategoryModel>(
+        "SELECT c.*, COALESCE(o.is_hidden, FALSE) AS hidden
+        FROM categories c
+        LEFT JOIN user_category_overrides o ON o.category_id = c.category_id AND o.user_id = $1
+        WHERE ($2::INT[] IS NULL OR c.category_id = ANY($2))
+            AND c.deleted_at IS NULL
+            AND (c.user_id IS NULL OR can_view_resource(c.user_id, c.household_id, $1))
+        ORDER BY c.category_id",
+    )
+    .bind(user_id)
+    .bind(ids)
+    .fetch_all(db)
+    .await
+}
+
+/// The user's notifications, limited to `ids` when given.
+pub async fn get_sync_notifications(
+    db: &PgPool,
+    user_id: Uuid,
+    ids: Option<&[Uuid]>,
+) -> Result<Vec<NotificationModel>, sqlx::Error> {
+    if ids.is_some_and(|ids| ids.is_empty()) {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_as::<_, NotificationModel>(
+        "SELECT * FROM notifications
+         WHERE user_id = $1 AND ($2::UUID[] IS NULL OR notification_id = ANY($2))
+         ORDER BY created_at",
+    )
+    .bind(user_id)
+    .bind(ids)
+    .fetch_all(db)
+    .await
+}
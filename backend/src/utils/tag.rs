@@ -0,0 +1,84 @@
+use crate::schema::ApiResponse;
+use axum::http::StatusCode;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Normalizes a tag name: `#Work ` becomes `work`.
+pub fn normalize_tag(name: &str) -> Result<String, ApiResponse<serde_json::Value>> {
+    let tag = name.trim().trim_start_matches('#').to_lowercase();
+
+    if tag.is_empty() || tag.len() > 50 {
+        return Err(ApiResponse::error(
+            "Tag name must be between 1 and 50 characters",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    if !tag
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(ApiResponse::error(
+            &format!(
+                "Invalid tag '{}', only letters, digits, '-' and '_' are allowed",
+                name
+            ),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    Ok(tag)
+}
+
+/// Normalizes and de-duplicates a list of tag names, keeping the input order.
+pub fn normalize_tags(names: &[String]) -> Result<Vec<String>, ApiResponse<serde_json::Value>> {
+    let mut tags: Vec<String> = Vec::with_capacity(names.len());
+    for name in names {
+        let tag = normalize_tag(name)?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Ok(tags)
+}
+
+/// Replaces the tags of an expense, creating any tags the user doesn't have yet.
+/// Returns the tag names now attached to the expense, sorted by name.
+pub async fn set_expense_tags(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    expense_id: Uuid,
+    tags: &[String],
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query("DELETE FROM expense_tags WHERE expense_id = $1")
+        .bind(expense_id)
+        .execute(&mut *conn)
+        .await?;
+
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query(
+        "INSERT INTO tags (user_id, name) SELECT $1, unnest($2::TEXT[])
+         ON CONFLICT (user_id, name) DO NOTHING",
+    )
+    .bind(user_id)
+    .bind(tags)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO expense_tags (expense_id, tag_id)
+         SELECT $1, tag_id FROM tags WHERE user_id = $2 AND name = ANY($3)",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .bind(tags)
+    .execute(&mut *conn)
+    .await?;
+
+    let mut tags = tags.to_vec();
+    tags.sort();
+    Ok(tags)
+}
@@ -0,0 +1,206 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    models::{BudgetModel, CategoryModel, ExpenseModel},
+    schema::ApiResponse,
+};
+
+/// Tables whose rows are moved to the trash (`deleted_at`) instead of deleted.
+pub const TRASHABLE_TABLES: [&str; 3] = ["budgets", "categories", "expenses"];
+
+/// Kinds of resources that can be restored from the trash.
+pub const TRASH_TYPES: [&str; 3] = ["budget", "category", "expense"];
+
+/// Category `$1` and its subcategories that went to the trash with it (same
+/// `deleted_at` as `$2`), for queries over `subtree`.
+const TRASHED_SUBTREE: &str = "WITH RECURSIVE subtree AS (
+        SELECT category_id FROM categories WHERE category_id = $1
+        UNION
+        SELECT c.category_id FROM categories c
+        JOIN subtree s ON c.parent_id = s.category_id
+        WHERE c.deleted_at = $2
+    )";
+
+pub fn validate_trash_type(kind: &str) -> Result<&str, ApiResponse<serde_json::Value>> {
+    TRASH_TYPES
+        .iter()
+        .find(|t| **t == kind)
+        .copied()
+        .ok_or_else(|| {
+            ApiResponse::error(
+                "Type must be one of 'expense', 'budget' or 'category'",
+                StatusCode::BAD_REQUEST,
+            )
+        })
+}
+
+fn not_in_trash(resource_name: &str) -> ApiResponse<serde_json::Value> {
+    ApiResponse::error(
+        &format!(
+            "{} not found in the trash or you don't have permission to restore it",
+            resource_name
+        ),
+        StatusCode::NOT_FOUND,
+    )
+}
+
+/// Restores a trashed expense together with the refunds trashed with it. A
+/// refund can only come back while its original expense is live and has room
+/// for it. Returns how many transactions were restored.
+pub async fn restore_expense(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    expense_id: Uuid,
+) -> Result<u64, ApiResponse<serde_json::Value>> {
+    let expense = sqlx::query_as::<_, ExpenseModel>(
+        "SELECT * FROM expenses
+         WHERE expense_id = $1 AND deleted_at IS NOT NULL
+            AND can_edit_resource(user_id, household_id, $2)",
+    )
+    .bind(expense_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| not_in_trash("Expense"))?;
+
+    let restored = sqlx::query(
+        "UPDATE expenses SET deleted_at = NULL
+         WHERE (expense_id = $1 OR original_expense_id = $1) AND deleted_at = $2",
+    )
+    .bind(expense_id)
+    .bind(expense.deleted_at)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    if let Some(original_id) = expense.original_expense_id {
+        let original: Option<(Option<DateTime<Utc>>, i64, i64)> = sqlx::query_as(
+            "SELECT o.deleted_at, o.amount,
+                (SELECT COALESCE(SUM(r.amount), 0)::BIGINT FROM expenses r
+                 WHERE r.original_expense_id = o.expense_id AND r.deleted_at IS NULL)
+            FROM expenses o WHERE o.expense_id = $1",
+        )
+        .bind(original_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match original {
+            Some((None, amount, refunded)) if refunded > amount => {
+                return Err(ApiResponse::error(
+                    "Restoring this refund would refund more than the original expense",
+                    StatusCode::CONFLICT,
+                ));
+            }
+            Some((None, _, _)) => {}
+            _ => {
+                return Err(ApiResponse::error(
+                    "The original expense of this refund is in the trash, restore it first",
+                    StatusCode::CONFLICT,
+                ));
+            }
+        }
+    }
+
+    Ok(restored)
+}
+
+/// Restores a trashed budget. Expenses linked to it never lost the link.
+pub async fn restore_budget(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    budget_id: Uuid,
+) -> Result<u64, ApiResponse<serde_json::Value>> {
+    sqlx::query_as::<_, BudgetModel>(
+        "UPDATE budgets SET deleted_at = NULL
+         WHERE budget_id = $1 AND deleted_at IS NOT NULL
+            AND can_edit_resource(user_id, household_id, $2)
+         RETURNING *",
+    )
+    .bind(budget_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| not_in_trash("Budget"))?;
+
+    Ok(1)
+}
+
+/// Restores a trashed category together with the subcategories trashed with
+/// it. Its parent has to be live, and none of the names may have been taken in
+/// the meantime. Returns how many categories were restored.
+pub async fn restore_category(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+    category_id: i32,
+) -> Result<u64, ApiResponse<serde_json::Value>> {
+    let category = sqlx::query_as::<_, CategoryModel>(
+        "SELECT * FROM categories
+         WHERE category_id = $1 AND deleted_at IS NOT NULL
+            AND can_edit_resource(user_id, household_id, $2)",
+    )
+    .bind(category_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| not_in_trash("Category"))?;
+
+    if let Some(parent_id) = category.parent_id {
+        let parent_trashed: bool = sqlx::query_scalar(
+            "SELECT deleted_at IS NOT NULL FROM categories WHERE category_id = $1",
+        )
+        .bind(parent_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        if parent_trashed {
+            return Err(ApiResponse::error(
+                "The parent category is in the trash, restore it first",
+                StatusCode::CONFLICT,
+            ));
+        }
+    }
+
+    let taken_name: Option<String> = sqlx::query_scalar(&format!(
+        "{}
+        SELECT c.category_name FROM categories c
+        JOIN subtree s ON s.category_id = c.category_id
+        WHERE EXISTS (
+            SELECT 1 FROM categories o
+            WHERE o.deleted_at IS NULL AND o.category_name = c.category_name
+                AND in_workspace(o.user_id, o.household_id, c.user_id, c.household_id)
+        )
+        LIMIT 1",
+        TRASHED_SUBTREE
+    ))
+    .bind(category_id)
+    .bind(category.deleted_at)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(name) = taken_name {
+        return Err(ApiResponse::error(
+            &format!(
+                "A category named '{}' already exists, rename it before restoring",
+                name
+            ),
+            StatusCode::CONFLICT,
+        ));
+    }
+
+    let restored = sqlx::query(&format!(
+        "{}
+        UPDATE categories SET deleted_at = NULL
+        WHERE category_id IN (SELECT category_id FROM subtree)",
+        TRASHED_SUBTREE
+    ))
+    .bind(category_id)
+    .bind(category.deleted_at)
+    .execute(&mut *conn)
+    .await?
+    .rows_affected();
+
+    Ok(restored)
+}
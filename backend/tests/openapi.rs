@@ -0,0 +1,66 @@
+use std::{collections::BTreeSet, fs};
+
+use backend::openapi::ApiDoc;
+use regex::Regex;
+use utoipa::OpenApi;
+
+/// Every `(method, path)` mounted in `src/routes`, read from the
+/// `.route("/path", get(handler).post(handler))` calls. Routes served by
+/// closures, like `/openapi.json` itself, aren't API endpoints and are skipped.
+fn mounted_routes() -> BTreeSet<(String, String)> {
+    let path = Regex::new(r#"^\s*"([^"]+)""#).unwrap();
+    let method = Regex::new(r"\b(get|post|put|patch|delete)\(\w+\)").unwrap();
+
+    let mut routes = BTreeSet::new();
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/routes");
+    for entry in fs::read_dir(dir).unwrap() {
+        let source = fs::read_to_string(entry.unwrap().path()).unwrap();
+        for route in source.split(".route(").skip(1) {
+            let path = &path.captures(route).expect("route without a literal path")[1];
+            for handler in method.captures_iter(route) {
+                routes.insert((handler[1].to_string(), path.to_string()));
+            }
+        }
+    }
+    routes
+}
+
+#[test]
+fn every_route_is_in_the_openapi_document() {
+    let routes = mounted_routes();
+    assert!(routes.len() > 50, "found only {} routes", routes.len());
+
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    let missing: Vec<String> = routes
+        .iter()
+        .filter(|(method, path)| spec["paths"][path][method].is_null())
+        .map(|(method, path)| format!("{} {}", method.to_uppercase(), path))
+        .collect();
+
+    assert!(
+        missing.is_empty(),
+        "routes missing from the OpenAPI document (add #[utoipa::path] to the handler and list it in ApiDoc):\n{}",
+        missing.join("\n")
+    );
+}
+
+#[test]
+fn openapi_document_has_no_unmounted_routes() {
+    let routes = mounted_routes();
+    let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+    let mut stale = Vec::new();
+    for (path, item) in spec["paths"].as_object().unwrap() {
+        for method in item.as_object().unwrap().keys() {
+            if !routes.contains(&(method.clone(), path.clone())) {
+                stale.push(format!("{} {}", method.to_uppercase(), path));
+            }
+        }
+    }
+
+    assert!(
+        stale.is_empty(),
+        "documented routes that aren't mounted:\n{}",
+        stale.join("\n")
+    );
+}
@@ -2,6 +2,7 @@ import { type ApiResponse, ApiError } from "@/schema";
 import axios, { AxiosInstance } from "axios";
 import { Expense, CreateExpenseType, UpdateExpenseType } from "@/schema/expense";
 import { getItemAsync } from 'expo-secure-store'
+import { expo } from "../app.json";
 
 interface ExpenseResponse {
   expense: Expense;
@@ -23,9 +24,10 @@ export interface AllExpenseResponseWithPagination extends AllExpenseResponse {
 const API_BASE_URL = process.env.EXPO_PUBLIC_API_URL || "http://localhost:8080";
 
 export const apiClient: AxiosInstance = axios.create({
-  baseURL: API_BASE_URL,
+  baseURL: `${API_BASE_URL}/api/v1`,
   headers: {
     "Content-Type": "application/json",
+    "X-Client-Version": expo.version,
   },
 });
 
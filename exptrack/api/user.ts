@@ -1,6 +1,7 @@
 import { User, LoginUserType, RegisterUserType } from '@/schema/user';
 import axios, { AxiosInstance } from 'axios';
 import { type ApiResponse, ApiError } from '@/schema';
+import { expo } from '../app.json';
 
 interface UserResponse {
   user: User;
@@ -10,9 +11,10 @@ interface UserResponse {
 const API_BASE_URL = process.env.EXPO_PUBLIC_API_URL || 'http://localhost:8080';
 
 const apiClient: AxiosInstance = axios.create({
-  baseURL: API_BASE_URL,
+  baseURL: `${API_BASE_URL}/api/v1`,
   headers: {
     'Content-Type': 'application/json',
+    'X-Client-Version': expo.version,
   },
 });
 